@@ -0,0 +1,81 @@
+//! Measures how long it takes to serialize and deserialize large numeric arrays.
+//!
+//! Run using `cargo bench --bench arrays`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use nbtx::{nbt, BigEndian, EndiannessImpl, LittleEndian, NetworkLittleEndian, Value};
+use serde::Deserialize;
+
+const ITERATIONS: u32 = 1000;
+
+#[allow(dead_code)]
+#[derive(Deserialize)]
+struct Chunk {
+    ints: Vec<i32>,
+    longs: Vec<i64>,
+    bytes: Vec<i8>,
+}
+
+/// Returns the average time it takes to run `f`.
+fn measure(mut f: impl FnMut()) -> Duration {
+    // Warm up.
+    for _ in 0..ITERATIONS / 10 {
+        f();
+    }
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    start.elapsed() / ITERATIONS
+}
+
+fn bench<F: EndiannessImpl>(name: &str, value: &Value) {
+    let encoded = nbtx::to_bytes::<F>(value).unwrap();
+
+    let typed = measure(|| {
+        let chunk: Chunk = nbtx::from_bytes::<F, _>(&mut encoded.as_slice()).unwrap();
+        black_box(chunk);
+    });
+    let dynamic = measure(|| {
+        let value: Value = nbtx::from_bytes::<F, _>(&mut encoded.as_slice()).unwrap();
+        black_box(value);
+    });
+
+    println!("{name:>20}: typed {typed:>10?}, value {dynamic:>10?}");
+}
+
+fn bench_serialize<F: EndiannessImpl>(name: &str, value: &Value) {
+    let mut buffer = Vec::new();
+    let time = measure(|| {
+        buffer.clear();
+        nbtx::to_bytes_in::<F>(&mut buffer, value).unwrap();
+        black_box(&buffer);
+    });
+
+    println!("{name:>20}: {time:>10?}");
+}
+
+fn main() {
+    let value = nbt!({
+        "ints": Value::IntArray((0..4096).collect()),
+        "longs": Value::LongArray((0..4096).collect()),
+        "bytes": Value::ByteArray((0..4096).map(|i| i as u8).collect())
+    });
+
+    bench::<BigEndian>("big endian", &value);
+    bench::<LittleEndian>("little endian", &value);
+    bench::<NetworkLittleEndian>("network endian", &value);
+
+    // Byte arrays are written in a single call, while lists of bytes are written element by element.
+    let bytes: Vec<u8> = (0..65536).map(|i| i as u8).collect();
+    let array = nbt!({ "bytes": Value::ByteArray(bytes.clone()) });
+    let list =
+        nbt!({ "bytes": Value::List(bytes.into_iter().map(|b| Value::Byte(b as i8)).collect()) });
+
+    println!();
+    bench_serialize::<LittleEndian>("byte array", &array);
+    bench_serialize::<LittleEndian>("list of bytes", &list);
+}
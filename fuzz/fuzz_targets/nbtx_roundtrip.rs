@@ -0,0 +1,30 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use nbtx::{BigEndian, EndiannessImpl, LittleEndian, NetworkLittleEndian, Value};
+
+/// Decodes the data and, if it is valid, checks that encoding and decoding it again gives the same value.
+///
+/// Values are compared by their canonical encoding rather than with `==`, since NaN floats are not
+/// equal to themselves. Differences that are lost by decoding in the first place, such as the name of
+/// the root compound or the element type of empty lists, are not checked.
+fn roundtrip<F: EndiannessImpl>(mut data: &[u8]) {
+    let Ok(value) = nbtx::from_bytes::<F, Value>(&mut data) else {
+        return;
+    };
+
+    let encoded = nbtx::to_bytes::<F>(&value).expect("decoded value should encode");
+    let decoded: Value = nbtx::from_bytes::<F, _>(&mut encoded.as_slice()).expect("encoded value should decode");
+
+    assert_eq!(
+        value.canonical_bytes::<F>().unwrap(),
+        decoded.canonical_bytes::<F>().unwrap(),
+        "value changed after round trip: {value:?} became {decoded:?}"
+    );
+}
+
+fuzz_target!(|data: &[u8]| {
+    roundtrip::<BigEndian>(data);
+    roundtrip::<LittleEndian>(data);
+    roundtrip::<NetworkLittleEndian>(data);
+});
@@ -0,0 +1,184 @@
+//! Modules for use with `#[serde(with = "...")]` that force fields to be written as array tags.
+//!
+//! Sequences are written as lists by default, see the [crate documentation](crate#arrays).
+
+/// Serializes a `Vec<u8>` as a byte array rather than a list of bytes.
+///
+/// # Example
+///
+/// ```rust
+/// # use serde::{Deserialize, Serialize};
+/// # use nbtx::Value;
+/// #[derive(Serialize, Deserialize)]
+/// struct Chunk {
+///     #[serde(with = "nbtx::byte_array")]
+///     biomes: Vec<u8>,
+/// }
+///
+/// let chunk = Chunk { biomes: vec![1, 2, 3] };
+/// let value = nbtx::to_value(&chunk).unwrap();
+/// assert_eq!(value.as_compound().unwrap()["biomes"], Value::ByteArray(vec![1, 2, 3]));
+/// ```
+pub mod byte_array {
+    use alloc::vec::Vec;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    /// Serializes the bytes as a byte array.
+    #[inline]
+    pub fn serialize<S>(v: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(v)
+    }
+
+    /// Deserializes a byte array or a list of bytes.
+    #[inline]
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Vec::deserialize(deserializer)
+    }
+}
+
+/// Serializes a `Vec<i32>` as an int array rather than a list of ints.
+///
+/// # Example
+///
+/// ```rust
+/// # use serde::{Deserialize, Serialize};
+/// # use nbtx::Value;
+/// #[derive(Serialize, Deserialize)]
+/// struct Entity {
+///     #[serde(with = "nbtx::int_array")]
+///     uuid: Vec<i32>,
+/// }
+///
+/// let entity = Entity { uuid: vec![1, 2, 3, 4] };
+/// let value = nbtx::to_value(&entity).unwrap();
+/// assert_eq!(value.as_compound().unwrap()["uuid"], Value::IntArray(vec![1, 2, 3, 4]));
+/// ```
+pub mod int_array {
+    use alloc::vec::Vec;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use crate::value::INT_ARRAY_TOKEN;
+
+    /// Serializes the integers as an int array.
+    #[inline]
+    pub fn serialize<S>(v: &[i32], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_newtype_struct(INT_ARRAY_TOKEN, v)
+    }
+
+    /// Deserializes an int array or a list of ints.
+    #[inline]
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<i32>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Vec::deserialize(deserializer)
+    }
+}
+
+/// Serializes a `Vec<i64>` as a long array rather than a list of longs.
+///
+/// # Example
+///
+/// ```rust
+/// # use serde::{Deserialize, Serialize};
+/// # use nbtx::Value;
+/// #[derive(Serialize, Deserialize)]
+/// struct Section {
+///     #[serde(with = "nbtx::long_array")]
+///     block_states: Vec<i64>,
+/// }
+///
+/// let section = Section { block_states: vec![1, 2] };
+/// let value = nbtx::to_value(&section).unwrap();
+/// assert_eq!(value.as_compound().unwrap()["block_states"], Value::LongArray(vec![1, 2]));
+/// ```
+pub mod long_array {
+    use alloc::vec::Vec;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use crate::value::LONG_ARRAY_TOKEN;
+
+    /// Serializes the integers as a long array.
+    #[inline]
+    pub fn serialize<S>(v: &[i64], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_newtype_struct(LONG_ARRAY_TOKEN, v)
+    }
+
+    /// Deserializes a long array or a list of longs.
+    #[inline]
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<i64>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Vec::deserialize(deserializer)
+    }
+}
+
+/// Serializes a `u128` as a long array of two longs.
+///
+/// The array contains the most significant 64 bits followed by the least significant 64 bits,
+/// each reinterpreted as a signed long. This is the same layout as the `UUIDMost` and `UUIDLeast`
+/// longs used by older versions of Java Edition, so the value of a UUID is preserved when read by other tools.
+///
+/// A list of two longs is also accepted when deserializing.
+///
+/// # Example
+///
+/// ```rust
+/// # use serde::{Deserialize, Serialize};
+/// # use nbtx::Value;
+/// #[derive(Serialize, Deserialize)]
+/// struct Entity {
+///     #[serde(with = "nbtx::u128_long_array")]
+///     uuid: u128,
+/// }
+///
+/// let entity = Entity { uuid: 1 << 64 | 2 };
+/// let value = nbtx::to_value(&entity).unwrap();
+/// assert_eq!(value.as_compound().unwrap()["uuid"], Value::LongArray(vec![1, 2]));
+/// ```
+pub mod u128_long_array {
+    use alloc::vec::Vec;
+
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use crate::value::LONG_ARRAY_TOKEN;
+
+    /// Serializes the integer as a long array of its most and least significant halves.
+    #[inline]
+    pub fn serialize<S>(v: &u128, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let longs = [(v >> 64) as u64 as i64, *v as u64 as i64];
+        serializer.serialize_newtype_struct(LONG_ARRAY_TOKEN, &longs)
+    }
+
+    /// Deserializes a long array or a list of two longs.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<u128, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let longs = Vec::<i64>::deserialize(deserializer)?;
+        match longs[..] {
+            [most, least] => Ok((most as u64 as u128) << 64 | least as u64 as u128),
+            _ => Err(D::Error::invalid_length(longs.len(), &"two longs")),
+        }
+    }
+}
@@ -0,0 +1,345 @@
+//! Asynchronous reading and writing using [`tokio`].
+//!
+//! Reading walks the structure of the NBT data to find out how many bytes it consists of,
+//! without decoding it. Once a complete root compound has been read into a buffer, it is
+//! deserialised synchronously. This means no more bytes are read than the data consists of,
+//! which makes it possible to read NBT that is followed by other data, such as in network packets.
+
+use std::borrow::Cow;
+use std::io;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::{
+    from_bytes, to_bytes, BigEndian, EndiannessImpl, FieldType, LittleEndian, NbtError,
+    NetworkLittleEndian, Variant,
+};
+
+/// A container that is currently being read.
+enum Frame {
+    Compound,
+    List { ty: FieldType, remaining: u32 },
+}
+
+/// Reads exactly `len` bytes and appends them to the buffer.
+async fn read_bytes<R>(reader: &mut R, buf: &mut Vec<u8>, len: u64) -> Result<(), NbtError>
+where
+    R: AsyncRead + Unpin,
+{
+    // This grows the buffer as data arrives, rather than trusting the length up front.
+    let read = (&mut *reader).take(len).read_to_end(buf).await?;
+    if read as u64 != len {
+        return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+    }
+
+    Ok(())
+}
+
+/// Reads a single byte and appends it to the buffer.
+async fn read_u8<R>(reader: &mut R, buf: &mut Vec<u8>) -> Result<u8, NbtError>
+where
+    R: AsyncRead + Unpin,
+{
+    let byte = reader.read_u8().await?;
+    buf.push(byte);
+    Ok(byte)
+}
+
+/// Reads a variable-length integer of at most `max_len` bytes and appends it to the buffer.
+async fn read_varint<R>(reader: &mut R, buf: &mut Vec<u8>, max_len: u32) -> Result<u64, NbtError>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut value = 0;
+    for i in 0..max_len {
+        let byte = read_u8(reader, buf).await?;
+        value |= ((byte & 0x7f) as u64) << (7 * i);
+
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+
+    Err(NbtError::Other(Cow::Borrowed(
+        "Variable-length integer is too long",
+    )))
+}
+
+/// Reads the length prefix of a string and appends it to the buffer.
+async fn read_string_len<F, R>(reader: &mut R, buf: &mut Vec<u8>) -> Result<u64, NbtError>
+where
+    F: EndiannessImpl,
+    R: AsyncRead + Unpin,
+{
+    Ok(match F::AS_ENUM {
+        Variant::BigEndian | Variant::LittleEndian => {
+            let start = buf.len();
+            read_bytes(reader, buf, 2).await?;
+
+            let bytes = [buf[start], buf[start + 1]];
+            if F::AS_ENUM == Variant::BigEndian {
+                u16::from_be_bytes(bytes) as u64
+            } else {
+                u16::from_le_bytes(bytes) as u64
+            }
+        }
+        Variant::NetworkEndian => read_varint(reader, buf, 5).await?,
+    })
+}
+
+/// Reads the length prefix of a list or array and appends it to the buffer.
+async fn read_seq_len<F, R>(reader: &mut R, buf: &mut Vec<u8>) -> Result<u32, NbtError>
+where
+    F: EndiannessImpl,
+    R: AsyncRead + Unpin,
+{
+    Ok(match F::AS_ENUM {
+        Variant::BigEndian | Variant::LittleEndian => {
+            let start = buf.len();
+            read_bytes(reader, buf, 4).await?;
+
+            let bytes = [buf[start], buf[start + 1], buf[start + 2], buf[start + 3]];
+            if F::AS_ENUM == Variant::BigEndian {
+                u32::from_be_bytes(bytes)
+            } else {
+                u32::from_le_bytes(bytes)
+            }
+        }
+        Variant::NetworkEndian => {
+            // Zigzag encoded, like the other network integers.
+            let n = read_varint(reader, buf, 5).await? as u32;
+            ((n >> 1) as i32 ^ -((n & 1) as i32)) as u32
+        }
+    })
+}
+
+/// Reads the value of a tag and appends it to the buffer.
+///
+/// Lists and compounds are pushed onto the stack instead of being read directly.
+async fn read_value<F, R>(
+    reader: &mut R,
+    buf: &mut Vec<u8>,
+    stack: &mut Vec<Frame>,
+    ty: FieldType,
+) -> Result<(), NbtError>
+where
+    F: EndiannessImpl,
+    R: AsyncRead + Unpin,
+{
+    let is_network = F::AS_ENUM == Variant::NetworkEndian;
+
+    match ty {
+        FieldType::End => {
+            return Err(NbtError::Unsupported(
+                "Lists of end tags cannot contain elements",
+            ))
+        }
+        FieldType::Byte => read_bytes(reader, buf, 1).await?,
+        FieldType::Short => read_bytes(reader, buf, 2).await?,
+        FieldType::Float => read_bytes(reader, buf, 4).await?,
+        FieldType::Double => read_bytes(reader, buf, 8).await?,
+        FieldType::Int if is_network => read_varint(reader, buf, 5).await.map(drop)?,
+        FieldType::Long if is_network => read_varint(reader, buf, 10).await.map(drop)?,
+        FieldType::Int => read_bytes(reader, buf, 4).await?,
+        FieldType::Long => read_bytes(reader, buf, 8).await?,
+        FieldType::String => {
+            let len = read_string_len::<F, _>(reader, buf).await?;
+            read_bytes(reader, buf, len).await?;
+        }
+        FieldType::ByteArray => {
+            let len = read_seq_len::<F, _>(reader, buf).await?;
+            read_bytes(reader, buf, len as u64).await?;
+        }
+        FieldType::IntArray | FieldType::LongArray => {
+            let len = read_seq_len::<F, _>(reader, buf).await?;
+            let (size, max_len) = if ty == FieldType::IntArray {
+                (4, 5)
+            } else {
+                (8, 10)
+            };
+
+            if is_network {
+                for _ in 0..len {
+                    read_varint(reader, buf, max_len).await?;
+                }
+            } else {
+                read_bytes(reader, buf, len as u64 * size).await?;
+            }
+        }
+        FieldType::List => {
+            let ty = FieldType::try_from(read_u8(reader, buf).await?)?;
+            let len = read_seq_len::<F, _>(reader, buf).await?;
+            stack.push(Frame::List { ty, remaining: len });
+        }
+        FieldType::Compound => stack.push(Frame::Compound),
+    }
+
+    Ok(())
+}
+
+/// Reads the raw bytes of a single root compound, without decoding them.
+async fn read_root<F, R>(reader: &mut R) -> Result<Vec<u8>, NbtError>
+where
+    F: EndiannessImpl,
+    R: AsyncRead + Unpin,
+{
+    let mut buf = Vec::new();
+
+    let ty = FieldType::try_from(read_u8(reader, &mut buf).await?)?;
+    if ty != FieldType::Compound {
+        return Err(NbtError::UnexpectedType {
+            expected: FieldType::Compound,
+            actual: ty,
+        });
+    }
+
+    let len = read_string_len::<F, _>(reader, &mut buf).await?;
+    read_bytes(reader, &mut buf, len).await?;
+
+    let mut stack = vec![Frame::Compound];
+    while let Some(frame) = stack.last_mut() {
+        match frame {
+            Frame::Compound => {
+                let ty = FieldType::try_from(read_u8(reader, &mut buf).await?)?;
+                if ty == FieldType::End {
+                    stack.pop();
+                    continue;
+                }
+
+                let len = read_string_len::<F, _>(reader, &mut buf).await?;
+                read_bytes(reader, &mut buf, len).await?;
+                read_value::<F, _>(reader, &mut buf, &mut stack, ty).await?;
+            }
+            Frame::List { ty, remaining } => {
+                if *remaining == 0 {
+                    stack.pop();
+                    continue;
+                }
+
+                *remaining -= 1;
+                let ty = *ty;
+                read_value::<F, _>(reader, &mut buf, &mut stack, ty).await?;
+            }
+        }
+    }
+
+    Ok(buf)
+}
+
+/// Asynchronously reads a single object of type `T` from the given reader.
+///
+/// Exactly the bytes of the object are read, so the reader can contain other data after it.
+/// See the [module documentation](self) for how this works.
+///
+/// # Example
+///
+/// ```rust
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// # let owned_buffer = nbtx::to_be_bytes(&nbtx::nbt!({ "value": "Hello, World!" })).unwrap();
+/// # let mut reader = owned_buffer.as_slice();
+///  let value: nbtx::Value = nbtx::from_async_bytes::<nbtx::BigEndian, _, _>(&mut reader).await.unwrap();
+/// # }
+/// ```
+pub async fn from_async_bytes<F, T, R>(reader: &mut R) -> Result<T, NbtError>
+where
+    F: EndiannessImpl,
+    T: DeserializeOwned,
+    R: AsyncRead + Unpin,
+{
+    let buf = read_root::<F, _>(reader).await?;
+    from_bytes::<F, T>(&mut buf.as_slice())
+}
+
+/// Asynchronously reads a single object of type `T` in big endian format, see [`from_async_bytes`].
+///
+/// This format is used by Minecraft: Java Edition.
+#[inline]
+pub async fn from_async_be_bytes<T, R>(reader: &mut R) -> Result<T, NbtError>
+where
+    T: DeserializeOwned,
+    R: AsyncRead + Unpin,
+{
+    from_async_bytes::<BigEndian, T, R>(reader).await
+}
+
+/// Asynchronously reads a single object of type `T` in little endian format, see [`from_async_bytes`].
+///
+/// This format is used by disk formats in Minecraft: Bedrock Edition.
+#[inline]
+pub async fn from_async_le_bytes<T, R>(reader: &mut R) -> Result<T, NbtError>
+where
+    T: DeserializeOwned,
+    R: AsyncRead + Unpin,
+{
+    from_async_bytes::<LittleEndian, T, R>(reader).await
+}
+
+/// Asynchronously reads a single object of type `T` in network little endian format, see [`from_async_bytes`].
+///
+/// This format is used by the network protocol of Minecraft: Bedrock Edition.
+#[inline]
+pub async fn from_async_net_bytes<T, R>(reader: &mut R) -> Result<T, NbtError>
+where
+    T: DeserializeOwned,
+    R: AsyncRead + Unpin,
+{
+    from_async_bytes::<NetworkLittleEndian, T, R>(reader).await
+}
+
+/// Asynchronously writes the given data to the writer.
+///
+/// The data is serialised into a buffer first, which is then written to the writer.
+///
+/// # Example
+///
+/// ```rust
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+///  let mut writer = Vec::new();
+///  nbtx::to_async_bytes::<nbtx::BigEndian, _, _>(&mut writer, &nbtx::nbt!({ "value": 1i32 })).await.unwrap();
+/// # }
+/// ```
+pub async fn to_async_bytes<F, T, W>(writer: &mut W, v: &T) -> Result<(), NbtError>
+where
+    F: EndiannessImpl,
+    T: ?Sized + Serialize,
+    W: AsyncWrite + Unpin,
+{
+    let buf = to_bytes::<F>(v)?;
+    writer.write_all(&buf).await?;
+
+    Ok(())
+}
+
+/// Asynchronously writes the given data in big endian format, see [`to_async_bytes`].
+#[inline]
+pub async fn to_async_be_bytes<T, W>(writer: &mut W, v: &T) -> Result<(), NbtError>
+where
+    T: ?Sized + Serialize,
+    W: AsyncWrite + Unpin,
+{
+    to_async_bytes::<BigEndian, T, W>(writer, v).await
+}
+
+/// Asynchronously writes the given data in little endian format, see [`to_async_bytes`].
+#[inline]
+pub async fn to_async_le_bytes<T, W>(writer: &mut W, v: &T) -> Result<(), NbtError>
+where
+    T: ?Sized + Serialize,
+    W: AsyncWrite + Unpin,
+{
+    to_async_bytes::<LittleEndian, T, W>(writer, v).await
+}
+
+/// Asynchronously writes the given data in network little endian format, see [`to_async_bytes`].
+#[inline]
+pub async fn to_async_net_bytes<T, W>(writer: &mut W, v: &T) -> Result<(), NbtError>
+where
+    T: ?Sized + Serialize,
+    W: AsyncWrite + Unpin,
+{
+    to_async_bytes::<NetworkLittleEndian, T, W>(writer, v).await
+}
@@ -0,0 +1,326 @@
+use std::borrow::Cow;
+use std::io::{Cursor, Read};
+
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+#[cfg(feature = "gzip")]
+use flate2::read::GzDecoder;
+#[cfg(feature = "zlib")]
+use flate2::read::{DeflateDecoder, ZlibDecoder};
+#[cfg(feature = "gzip")]
+use flate2::write::GzEncoder;
+#[cfg(feature = "zlib")]
+use flate2::write::{DeflateEncoder, ZlibEncoder};
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+
+use crate::{from_bytes, to_bytes_in, EndiannessImpl, NbtError};
+
+/// Magic bytes that every gzip stream starts with.
+#[cfg(feature = "gzip")]
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Whether the two bytes form a valid zlib header.
+///
+/// The first byte must specify the deflate method and the header as a whole must be
+/// a multiple of 31, as described in RFC 1950.
+#[cfg(feature = "zlib")]
+#[inline]
+fn is_zlib_header(header: [u8; 2]) -> bool {
+    header[0] & 0x0f == 8 && u16::from_be_bytes(header).is_multiple_of(31)
+}
+
+/// Reads a single gzip compressed object of type `T` from the given buffer.
+///
+/// Returns an error if the data does not start with the gzip magic bytes.
+#[cfg(feature = "gzip")]
+pub fn from_gzip_bytes<'de, F, T>(reader: &mut impl ReadBytesExt) -> Result<T, NbtError>
+where
+    T: Deserialize<'de>,
+    F: EndiannessImpl + 'de,
+{
+    let mut magic = [0; 2];
+    reader.read_exact(&mut magic)?;
+
+    if magic != GZIP_MAGIC {
+        return Err(NbtError::Other(Cow::Owned(format!(
+            "Expected gzip magic bytes {GZIP_MAGIC:02x?}, found {magic:02x?}"
+        ))));
+    }
+
+    let mut decoder = GzDecoder::new(Cursor::new(magic).chain(reader));
+    from_bytes::<F, T>(&mut decoder)
+}
+
+/// Reads a single gzip compressed object of type `T` from the given buffer.
+///
+/// This function uses the big endian format of NBT, which is used by disk formats such as
+/// `level.dat` and player data in Minecraft: Java Edition.
+///
+/// # Example
+///
+/// ```rust
+/// # use nbtx as nbt;
+/// # fn main() {
+///  #[derive(serde::Serialize, serde::Deserialize, Debug)]
+///  struct Data {
+///     value: String
+///  }
+///
+/// # let data = Data {
+/// #   value: String::from("Hello, World!")
+/// # };
+/// # let owned_buffer = nbt::to_gzip_be_bytes(&data).unwrap();
+/// # let mut buffer = owned_buffer.as_slice();
+///
+///  let data: Data = nbt::from_gzip_be_bytes(&mut buffer).unwrap();
+///
+///  println!("Got {data:?}!");
+/// # }
+/// ```
+#[cfg(feature = "gzip")]
+#[inline]
+pub fn from_gzip_be_bytes<'de, T, R>(reader: &mut R) -> Result<T, NbtError>
+where
+    R: ReadBytesExt,
+    T: Deserialize<'de>,
+{
+    from_gzip_bytes::<BigEndian, T>(reader)
+}
+
+/// Serializes the given data in any endian format and compresses it using gzip.
+#[cfg(feature = "gzip")]
+pub fn to_gzip_bytes<E>(v: &(impl Serialize + ?Sized)) -> Result<Vec<u8>, NbtError>
+where
+    E: EndiannessImpl,
+{
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    to_bytes_in::<E>(&mut encoder, v)?;
+
+    Ok(encoder.finish()?)
+}
+
+/// Serializes the given data in big endian format and compresses it using gzip.
+///
+/// This is the format used by files such as `level.dat` in Minecraft: Java Edition.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() {
+///  #[derive(serde::Serialize, serde::Deserialize)]
+///  struct Data {
+///     value: String
+///  }
+///
+///  let data = Data { value: "Hello, World!".to_owned() };
+///  let encoded = nbtx::to_gzip_be_bytes(&data).unwrap();
+/// # }
+/// ```
+#[cfg(feature = "gzip")]
+#[inline]
+pub fn to_gzip_be_bytes<T>(v: &T) -> Result<Vec<u8>, NbtError>
+where
+    T: ?Sized + Serialize,
+{
+    to_gzip_bytes::<BigEndian>(v)
+}
+
+/// Serializes the given data in any endian format, compressing it into the given writer using gzip.
+#[cfg(feature = "gzip")]
+pub fn to_gzip_bytes_in<E>(
+    writer: &mut impl WriteBytesExt,
+    v: &(impl Serialize + ?Sized),
+) -> Result<(), NbtError>
+where
+    E: EndiannessImpl,
+{
+    let mut encoder = GzEncoder::new(writer, Compression::default());
+    to_bytes_in::<E>(&mut encoder, v)?;
+    encoder.finish()?;
+
+    Ok(())
+}
+
+/// Reads a single zlib compressed object of type `T` from the given buffer.
+///
+/// Returns an error if the data does not start with a valid zlib header.
+#[cfg(feature = "zlib")]
+pub fn from_zlib_bytes<'de, F, T>(reader: &mut impl ReadBytesExt) -> Result<T, NbtError>
+where
+    T: Deserialize<'de>,
+    F: EndiannessImpl + 'de,
+{
+    let mut header = [0; 2];
+    reader.read_exact(&mut header)?;
+
+    if !is_zlib_header(header) {
+        return Err(NbtError::Other(Cow::Owned(format!(
+            "Expected a zlib header, found {header:02x?}"
+        ))));
+    }
+
+    let mut decoder = ZlibDecoder::new(Cursor::new(header).chain(reader));
+    from_bytes::<F, T>(&mut decoder)
+}
+
+/// Reads a single zlib compressed object of type `T` from the given buffer.
+///
+/// This function uses the big endian format of NBT, which is used by the chunks stored
+/// in region files of Minecraft: Java Edition.
+///
+/// # Example
+///
+/// ```rust
+/// # use nbtx as nbt;
+/// # fn main() {
+///  #[derive(serde::Serialize, serde::Deserialize, Debug)]
+///  struct Data {
+///     value: String
+///  }
+///
+/// # let data = Data {
+/// #   value: String::from("Hello, World!")
+/// # };
+/// # let owned_buffer = nbt::to_zlib_be_bytes(&data).unwrap();
+/// # let mut buffer = owned_buffer.as_slice();
+///
+///  let data: Data = nbt::from_zlib_be_bytes(&mut buffer).unwrap();
+///
+///  println!("Got {data:?}!");
+/// # }
+/// ```
+#[cfg(feature = "zlib")]
+#[inline]
+pub fn from_zlib_be_bytes<'de, T, R>(reader: &mut R) -> Result<T, NbtError>
+where
+    R: ReadBytesExt,
+    T: Deserialize<'de>,
+{
+    from_zlib_bytes::<BigEndian, T>(reader)
+}
+
+/// Reads a single object of type `T` compressed using raw deflate (without a zlib header).
+///
+/// Since raw deflate streams have no header, the data cannot be verified up front.
+#[cfg(feature = "zlib")]
+pub fn from_deflate_bytes<'de, F, T>(reader: &mut impl ReadBytesExt) -> Result<T, NbtError>
+where
+    T: Deserialize<'de>,
+    F: EndiannessImpl + 'de,
+{
+    let mut decoder = DeflateDecoder::new(reader);
+    from_bytes::<F, T>(&mut decoder)
+}
+
+/// Serializes the given data in any endian format and compresses it using zlib.
+#[cfg(feature = "zlib")]
+pub fn to_zlib_bytes<E>(v: &(impl Serialize + ?Sized)) -> Result<Vec<u8>, NbtError>
+where
+    E: EndiannessImpl,
+{
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    to_bytes_in::<E>(&mut encoder, v)?;
+
+    Ok(encoder.finish()?)
+}
+
+/// Serializes the given data in big endian format and compresses it using zlib.
+///
+/// This is the format used by the chunks stored in region files of Minecraft: Java Edition.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() {
+///  #[derive(serde::Serialize, serde::Deserialize)]
+///  struct Data {
+///     value: String
+///  }
+///
+///  let data = Data { value: "Hello, World!".to_owned() };
+///  let encoded = nbtx::to_zlib_be_bytes(&data).unwrap();
+/// # }
+/// ```
+#[cfg(feature = "zlib")]
+#[inline]
+pub fn to_zlib_be_bytes<T>(v: &T) -> Result<Vec<u8>, NbtError>
+where
+    T: ?Sized + Serialize,
+{
+    to_zlib_bytes::<BigEndian>(v)
+}
+
+/// Serializes the given data in any endian format, compressing it into the given writer using zlib.
+#[cfg(feature = "zlib")]
+pub fn to_zlib_bytes_in<E>(
+    writer: &mut impl WriteBytesExt,
+    v: &(impl Serialize + ?Sized),
+) -> Result<(), NbtError>
+where
+    E: EndiannessImpl,
+{
+    let mut encoder = ZlibEncoder::new(writer, Compression::default());
+    to_bytes_in::<E>(&mut encoder, v)?;
+    encoder.finish()?;
+
+    Ok(())
+}
+
+/// Serializes the given data in any endian format and compresses it using raw deflate
+/// (without a zlib header).
+#[cfg(feature = "zlib")]
+pub fn to_deflate_bytes<E>(v: &(impl Serialize + ?Sized)) -> Result<Vec<u8>, NbtError>
+where
+    E: EndiannessImpl,
+{
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+    to_bytes_in::<E>(&mut encoder, v)?;
+
+    Ok(encoder.finish()?)
+}
+
+/// Reads a single object of type `T` that is either gzip compressed, zlib compressed or
+/// not compressed at all.
+///
+/// The compression is detected by inspecting the first two bytes of the data.
+/// Raw deflate streams cannot be detected and should be read using [`from_deflate_bytes`].
+///
+/// # Example
+///
+/// ```rust
+/// # use nbtx as nbt;
+/// # fn main() {
+///  #[derive(serde::Serialize, serde::Deserialize, Debug)]
+///  struct Data {
+///     value: String
+///  }
+///
+/// # let data = Data {
+/// #   value: String::from("Hello, World!")
+/// # };
+/// # let owned_buffer = nbt::to_gzip_be_bytes(&data).unwrap();
+/// # let mut buffer = owned_buffer.as_slice();
+///
+///  let data: Data = nbt::from_compressed_bytes::<nbt::BigEndian, _>(&mut buffer).unwrap();
+///
+///  println!("Got {data:?}!");
+/// # }
+/// ```
+#[cfg(all(feature = "gzip", feature = "zlib"))]
+pub fn from_compressed_bytes<'de, F, T>(reader: &mut impl ReadBytesExt) -> Result<T, NbtError>
+where
+    T: Deserialize<'de>,
+    F: EndiannessImpl + 'de,
+{
+    let mut header = [0; 2];
+    reader.read_exact(&mut header)?;
+
+    let mut reader = Cursor::new(header).chain(reader);
+    if header == GZIP_MAGIC {
+        from_bytes::<F, T>(&mut GzDecoder::new(reader))
+    } else if is_zlib_header(header) {
+        from_bytes::<F, T>(&mut ZlibDecoder::new(reader))
+    } else {
+        from_bytes::<F, T>(&mut reader)
+    }
+}
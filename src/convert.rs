@@ -0,0 +1,612 @@
+//! Conversions between serializable types and [`Value`], without encoding to bytes.
+
+use alloc::borrow::{Cow, ToOwned};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use serde::de::{self, DeserializeOwned, IntoDeserializer, Visitor};
+use serde::ser::{self, Impossible, Serialize};
+
+use crate::de::visit_array;
+use crate::value::{ArrayAccess, INT_ARRAY_TOKEN, LONG_ARRAY_TOKEN, VALUE_TOKEN};
+use crate::{Map, NbtError, Value};
+
+/// Converts the given data to a [`Value`].
+///
+/// This produces the same value as serializing the data and deserializing the result into a [`Value`],
+/// but without encoding anything to bytes. The same data is rejected as well, such as lists
+/// with elements of different types.
+///
+/// # Example
+///
+/// ```rust
+/// # use nbtx::{nbt, Value};
+/// # fn main() {
+///  #[derive(serde::Serialize)]
+///  struct Data {
+///     value: String
+///  }
+///
+///  let data = Data { value: "Hello, World!".to_owned() };
+///  let value = nbtx::to_value(&data).unwrap();
+///
+///  assert_eq!(value, nbt!({ "value": "Hello, World!" }));
+/// # }
+/// ```
+pub fn to_value<T>(v: &T) -> Result<Value, NbtError>
+where
+    T: ?Sized + Serialize,
+{
+    v.serialize(ValueSerializer)?.ok_or(NbtError::Unsupported(
+        "Serializing Options is not supported",
+    ))
+}
+
+/// Converts a [`Value`] to an object of type `T`.
+///
+/// This is the counterpart of [`to_value`] and follows the same rules as deserializing from bytes.
+///
+/// # Example
+///
+/// ```rust
+/// # use nbtx::nbt;
+/// # fn main() {
+///  #[derive(serde::Deserialize, Debug)]
+///  struct Data {
+///     value: String
+///  }
+///
+///  let data: Data = nbtx::from_value(nbt!({ "value": "Hello, World!" })).unwrap();
+///
+///  assert_eq!(data.value, "Hello, World!");
+/// # }
+/// ```
+pub fn from_value<T>(value: Value) -> Result<T, NbtError>
+where
+    T: DeserializeOwned,
+{
+    T::deserialize(value)
+}
+
+/// Serializer that builds a [`Value`].
+///
+/// This produces `None` for values that should be skipped, which is the case for `None` options in structs.
+struct ValueSerializer;
+
+/// Builds the value of a list.
+struct ListBuilder {
+    list: Vec<Value>,
+}
+
+/// Builds the value of a compound.
+struct CompoundBuilder {
+    map: Map,
+    key: Option<String>,
+}
+
+/// Converts a list of integers to an int or long array.
+fn list_to_array<T>(
+    list: Value,
+    into: impl Fn(Value) -> Result<T, Value>,
+) -> Result<Vec<T>, NbtError> {
+    let Value::List(list) = list else {
+        return Err(NbtError::Unsupported("Arrays must be sequences"));
+    };
+
+    list.into_iter()
+        .map(|v| {
+            into(v).map_err(|v| NbtError::Other(Cow::Owned(format!("Invalid array element {v:?}"))))
+        })
+        .collect()
+}
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = Option<Value>;
+    type Error = NbtError;
+
+    type SerializeSeq = ListBuilder;
+    type SerializeTuple = ListBuilder;
+    type SerializeTupleStruct = ListBuilder;
+    type SerializeTupleVariant = Impossible<Option<Value>, NbtError>;
+    type SerializeMap = CompoundBuilder;
+    type SerializeStruct = CompoundBuilder;
+    type SerializeStructVariant = Impossible<Option<Value>, NbtError>;
+
+    #[inline]
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok, NbtError> {
+        Ok(Some(Value::Byte(v as i8)))
+    }
+
+    #[inline]
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok, NbtError> {
+        Ok(Some(Value::Byte(v)))
+    }
+
+    #[inline]
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok, NbtError> {
+        Ok(Some(Value::Short(v)))
+    }
+
+    #[inline]
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok, NbtError> {
+        Ok(Some(Value::Int(v)))
+    }
+
+    #[inline]
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok, NbtError> {
+        Ok(Some(Value::Long(v)))
+    }
+
+    #[inline]
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok, NbtError> {
+        self.serialize_i8(v as i8)
+    }
+
+    #[inline]
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok, NbtError> {
+        self.serialize_i16(v as i16)
+    }
+
+    #[inline]
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok, NbtError> {
+        self.serialize_i32(v as i32)
+    }
+
+    #[inline]
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok, NbtError> {
+        self.serialize_i64(v as i64)
+    }
+
+    #[inline]
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok, NbtError> {
+        Ok(Some(Value::Float(v)))
+    }
+
+    #[inline]
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok, NbtError> {
+        Ok(Some(Value::Double(v)))
+    }
+
+    #[inline]
+    fn serialize_char(self, v: char) -> Result<Self::Ok, NbtError> {
+        Ok(Some(Value::String(v.to_string())))
+    }
+
+    #[inline]
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, NbtError> {
+        Ok(Some(Value::String(v.to_owned())))
+    }
+
+    #[inline]
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, NbtError> {
+        Ok(Some(Value::ByteArray(v.to_vec())))
+    }
+
+    #[inline]
+    fn serialize_none(self) -> Result<Self::Ok, NbtError> {
+        Ok(None)
+    }
+
+    #[inline]
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Self::Ok, NbtError> {
+        value.serialize(self)
+    }
+
+    /// Units are stored as empty compounds.
+    #[inline]
+    fn serialize_unit(self) -> Result<Self::Ok, NbtError> {
+        Ok(Some(Value::Compound(Map::default())))
+    }
+
+    /// Unit structs are stored as empty compounds.
+    #[inline]
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, NbtError> {
+        Ok(Some(Value::Compound(Map::default())))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<Self::Ok, NbtError> {
+        Err(NbtError::Unsupported(
+            "Serializing unit variants is not supported",
+        ))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, NbtError> {
+        let list = || to_value(value);
+
+        match name {
+            INT_ARRAY_TOKEN => Ok(Some(Value::IntArray(list_to_array(
+                list()?,
+                Value::into_int,
+            )?))),
+            LONG_ARRAY_TOKEN => Ok(Some(Value::LongArray(list_to_array(
+                list()?,
+                Value::into_long,
+            )?))),
+            _ => Err(NbtError::Unsupported(
+                "Serializing newtype structs is not supported",
+            )),
+        }
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Self::Ok, NbtError> {
+        Err(NbtError::Unsupported(
+            "Serializing newtype variants is not supported",
+        ))
+    }
+
+    #[inline]
+    fn serialize_seq(self, len: Option<usize>) -> Result<ListBuilder, NbtError> {
+        Ok(ListBuilder {
+            list: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+
+    #[inline]
+    fn serialize_tuple(self, len: usize) -> Result<ListBuilder, NbtError> {
+        self.serialize_seq(Some(len))
+    }
+
+    #[inline]
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, NbtError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, NbtError> {
+        Err(NbtError::Unsupported(
+            "Serializing tuple variants is not supported",
+        ))
+    }
+
+    #[inline]
+    fn serialize_map(self, _len: Option<usize>) -> Result<CompoundBuilder, NbtError> {
+        Ok(CompoundBuilder {
+            map: Map::new(),
+            key: None,
+        })
+    }
+
+    #[inline]
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<CompoundBuilder, NbtError> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, NbtError> {
+        Err(NbtError::Unsupported(
+            "Serializing struct variants is not supported",
+        ))
+    }
+
+    #[inline]
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+impl ser::SerializeSeq for ListBuilder {
+    type Ok = Option<Value>;
+    type Error = NbtError;
+
+    #[inline]
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), NbtError> {
+        let value = to_value(value)?;
+        if let Some(first) = self.list.first() {
+            let (expected, actual) = (first.field_type(), value.field_type());
+            if actual != expected {
+                return Err(NbtError::HeterogeneousList { expected, actual });
+            }
+        }
+
+        self.list.push(value);
+        Ok(())
+    }
+
+    #[inline]
+    fn end(self) -> Result<Self::Ok, NbtError> {
+        Ok(Some(Value::List(self.list)))
+    }
+}
+
+impl ser::SerializeTuple for ListBuilder {
+    type Ok = Option<Value>;
+    type Error = NbtError;
+
+    #[inline]
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), NbtError> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    #[inline]
+    fn end(self) -> Result<Self::Ok, NbtError> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for ListBuilder {
+    type Ok = Option<Value>;
+    type Error = NbtError;
+
+    #[inline]
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), NbtError> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    #[inline]
+    fn end(self) -> Result<Self::Ok, NbtError> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeMap for CompoundBuilder {
+    type Ok = Option<Value>;
+    type Error = NbtError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), NbtError> {
+        match to_value(key)? {
+            Value::String(key) => {
+                self.key = Some(key);
+                Ok(())
+            }
+            _ => Err(NbtError::Unsupported("Compound keys must be strings")),
+        }
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), NbtError> {
+        let key = self.key.take().ok_or(NbtError::Unsupported(
+            "SerializeMap::serialize_value called before serialize_key",
+        ))?;
+
+        self.map.insert(key, to_value(value)?);
+        Ok(())
+    }
+
+    #[inline]
+    fn end(self) -> Result<Self::Ok, NbtError> {
+        Ok(Some(Value::Compound(self.map)))
+    }
+}
+
+impl ser::SerializeStruct for CompoundBuilder {
+    type Ok = Option<Value>;
+    type Error = NbtError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), NbtError> {
+        // `None` fields are skipped, just like when serializing to bytes.
+        if let Some(value) = value.serialize(ValueSerializer)? {
+            self.map.insert(key.to_owned(), value);
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    fn end(self) -> Result<Self::Ok, NbtError> {
+        Ok(Some(Value::Compound(self.map)))
+    }
+}
+
+impl<'de> IntoDeserializer<'de, NbtError> for Value {
+    type Deserializer = Self;
+
+    #[inline]
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Value {
+    type Error = NbtError;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, NbtError>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Value::Byte(v) => visitor.visit_i8(v),
+            Value::Short(v) => visitor.visit_i16(v),
+            Value::Int(v) => visitor.visit_i32(v),
+            Value::Long(v) => visitor.visit_i64(v),
+            Value::Float(v) => visitor.visit_f32(v),
+            Value::Double(v) => visitor.visit_f64(v),
+            Value::ByteArray(v) => visitor.visit_byte_buf(v),
+            Value::String(v) => visitor.visit_string(v),
+            Value::List(v) => visitor.visit_seq(de::value::SeqDeserializer::new(v.into_iter())),
+            Value::Compound(v) => visitor.visit_map(de::value::MapDeserializer::new(v.into_iter())),
+            Value::IntArray(v) => visit_array(v, visitor),
+            Value::LongArray(v) => visit_array(v, visitor),
+        }
+    }
+
+    #[inline]
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value, NbtError>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Value::Byte(v) => visitor.visit_bool(v != 0),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    /// Unsigned integers are read from the signed tag of the same size, reinterpreting the bits.
+    #[inline]
+    fn deserialize_u8<V>(self, visitor: V) -> Result<V::Value, NbtError>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Value::Byte(v) => visitor.visit_u8(v as u8),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    #[inline]
+    fn deserialize_u16<V>(self, visitor: V) -> Result<V::Value, NbtError>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Value::Short(v) => visitor.visit_u16(v as u16),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    #[inline]
+    fn deserialize_u32<V>(self, visitor: V) -> Result<V::Value, NbtError>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Value::Int(v) => visitor.visit_u32(v as u32),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    #[inline]
+    fn deserialize_u64<V>(self, visitor: V) -> Result<V::Value, NbtError>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Value::Long(v) => visitor.visit_u64(v as u64),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    #[inline]
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, NbtError>
+    where
+        V: Visitor<'de>,
+    {
+        // Missing fields are handled by serde, so a present value is always some.
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, NbtError>
+    where
+        V: Visitor<'de>,
+    {
+        // Other newtype structs are rejected, just like the binary deserializer does.
+        if name != VALUE_TOKEN {
+            return Err(NbtError::Unsupported(
+                "Deserializing newtype structs is not supported",
+            ));
+        }
+
+        // Int and long arrays are passed to the value as an enum with a private variant name,
+        // just like the binary deserializer does.
+        match self {
+            Value::IntArray(_) | Value::LongArray(_) => visitor.visit_enum(ArrayAccess::new(self)),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    #[inline]
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, NbtError>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Value::ByteArray(v) => visit_array(v.into_iter().map(|b| b as i8), visitor),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    #[inline]
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value, NbtError>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    #[inline]
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, NbtError>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    /// Units are read from empty compounds.
+    #[inline]
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, NbtError>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Value::Compound(v) if v.is_empty() => visitor.visit_unit(),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    #[inline]
+    fn deserialize_unit_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, NbtError>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_unit(visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        i8 i16 i32 i64 i128 u128 f32 f64 char str string bytes byte_buf
+        map struct enum identifier ignored_any
+    }
+
+    #[inline]
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
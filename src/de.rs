@@ -1,12 +1,21 @@
-use std::borrow::Cow;
-use std::marker::PhantomData;
-
-use byteorder::{BigEndian, LittleEndian, ReadBytesExt};
+use alloc::borrow::{Cow, ToOwned};
+#[cfg(not(feature = "std"))]
+use alloc::collections::BTreeSet as HashSet;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+#[cfg(feature = "std")]
+use std::collections::HashSet;
+
+use byteorder::{BigEndian, LittleEndian};
 use paste::paste;
+use serde::de::DeserializeOwned;
 use serde::de::{DeserializeSeed, MapAccess, SeqAccess, Visitor};
 use serde::{de, Deserialize};
-use varint_rs::VarintReader;
 
+use crate::io::{self, Read, ReadBytesExt, VarintReader};
+use crate::value::{ArrayAccess, VALUE_TOKEN};
 use crate::{EndiannessImpl, FieldType, NbtError, NetworkLittleEndian, Variant};
 
 /// Verifies that the deserialized type is equal to the expected type.
@@ -31,23 +40,668 @@ macro_rules! forward_unsupported {
                 V: Visitor<'de>
             {
                 return Err(NbtError::Unsupported(
-                    concat!("Deserialization of `", stringify!($ty), "` is not supported")
+                    concat!(
+                        "Deserialization of `", stringify!($ty), "` is not supported, as NBT has no 128-bit integers. ",
+                        "Use `nbtx::u128_long_array` to store a `u128` as a long array"
+                    )
                 ));
             }
         )+}
     }
 }
 
+/// Reads the length prefix of a string.
+#[inline]
+pub(crate) fn read_string_len<F>(input: &mut impl ReadBytesExt) -> Result<u32, NbtError>
+where
+    F: EndiannessImpl,
+{
+    Ok(match F::AS_ENUM {
+        Variant::BigEndian => input.read_u16::<BigEndian>()? as u32,
+        Variant::LittleEndian => input.read_u16::<LittleEndian>()? as u32,
+        Variant::NetworkEndian => input.read_u32_varint()?,
+    })
+}
+
+/// Reads a length-prefixed UTF-8 string.
+#[inline]
+pub(crate) fn read_string<F>(input: &mut impl ReadBytesExt) -> Result<String, NbtError>
+where
+    F: EndiannessImpl,
+{
+    read_string_with::<F>(input, false, false, usize::MAX)
+}
+
+/// Reads a length-prefixed UTF-8 string.
+///
+/// If `lossy` is set, invalid UTF-8 is replaced with `U+FFFD` instead of returning an error.
+/// If `modified` is set, Java's modified UTF-8 is accepted as well, see [`DeserializeOptions::legacy_java`].
+/// Strings longer than `max_len` bytes return [`NbtError::StringTooLong`].
+#[inline]
+fn read_string_with<F>(
+    input: &mut impl ReadBytesExt,
+    lossy: bool,
+    modified: bool,
+    max_len: usize,
+) -> Result<String, NbtError>
+where
+    F: EndiannessImpl,
+{
+    let len = read_string_len::<F>(input)?;
+    if len as usize > max_len {
+        return Err(NbtError::StringTooLong {
+            len: len as usize,
+            max: max_len,
+        });
+    }
+
+    let buf = read_bytes(input, len as usize)?;
+    decode_string(buf, lossy, modified)
+}
+
+/// Decodes the bytes of a string, see [`read_string_with`].
+fn decode_string(buf: Vec<u8>, lossy: bool, modified: bool) -> Result<String, NbtError> {
+    match String::from_utf8(buf) {
+        Ok(string) => Ok(string),
+        Err(err) if modified => match decode_modified_utf8(err.as_bytes(), lossy) {
+            Some(string) => Ok(string),
+            None => Err(err.into()),
+        },
+        Err(err) if lossy => Ok(String::from_utf8_lossy(err.as_bytes()).into_owned()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Decodes Java's modified UTF-8.
+///
+/// This differs from UTF-8 in that `U+0000` is encoded as the two bytes `C0 80` and characters outside of the
+/// Basic Multilingual Plane are encoded as a surrogate pair, each of which takes three bytes.
+/// Regular four-byte sequences are accepted as well.
+///
+/// Returns `None` if the bytes are invalid, unless `lossy` is set, in which case invalid sequences
+/// are replaced with `U+FFFD`.
+fn decode_modified_utf8(bytes: &[u8], lossy: bool) -> Option<String> {
+    let mut units = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let b = bytes[i];
+        let (len, min, init) = match b {
+            0x00..=0x7f => (1, 0, b as u32),
+            0xc0..=0xdf => (2, 0, (b & 0x1f) as u32),
+            0xe0..=0xef => (3, 0x800, (b & 0x0f) as u32),
+            0xf0..=0xf4 => (4, 0x10000, (b & 0x07) as u32),
+            _ => (0, 0, 0),
+        };
+
+        let tail = bytes.get(i + 1..i + len.max(1)).unwrap_or(&[0]);
+        let code = tail
+            .iter()
+            .try_fold(init, |code, &b| {
+                (b & 0xc0 == 0x80).then_some((code << 6) | (b & 0x3f) as u32)
+            })
+            // Only `C0 80` may be overlong, as it is how Java encodes `U+0000`.
+            .filter(|&code| code >= min && (len != 2 || code >= 0x80 || code == 0))
+            .filter(|&code| code <= 0x10ffff);
+
+        match code {
+            Some(code) if len > 0 => {
+                if let Ok(code) = u16::try_from(code) {
+                    units.push(code);
+                } else {
+                    let code = code - 0x10000;
+                    units.push(0xd800 | (code >> 10) as u16);
+                    units.push(0xdc00 | (code & 0x3ff) as u16);
+                }
+                i += len;
+            }
+            _ if lossy => {
+                units.push(0xfffd);
+                i += 1;
+            }
+            _ => return None,
+        }
+    }
+
+    if lossy {
+        Some(String::from_utf16_lossy(&units))
+    } else {
+        String::from_utf16(&units).ok()
+    }
+}
+
+/// Reads the length prefix of a list or array.
+#[inline]
+pub(crate) fn read_seq_len<F>(input: &mut impl ReadBytesExt) -> Result<u32, NbtError>
+where
+    F: EndiannessImpl,
+{
+    Ok(match F::AS_ENUM {
+        Variant::BigEndian => input.read_i32::<BigEndian>()? as u32,
+        Variant::LittleEndian => input.read_i32::<LittleEndian>()? as u32,
+        Variant::NetworkEndian => input.read_i32_varint()? as u32,
+    })
+}
+
+/// Amount of elements that arrays are read in at once.
+const ARRAY_CHUNK_LEN: usize = 4096;
+
+/// Reads an array with the given length in chunks.
+///
+/// The length is read from untrusted data, so the buffer only grows as elements actually arrive.
+/// Otherwise, a few bytes declaring a huge length could allocate gigabytes up front.
+#[inline]
+fn read_array<T>(
+    len: usize,
+    mut read_chunk: impl FnMut(&mut [T]) -> io::Result<()>,
+) -> Result<Vec<T>, NbtError>
+where
+    T: Copy + Default,
+{
+    let mut buf = Vec::with_capacity(len.min(ARRAY_CHUNK_LEN));
+    while buf.len() < len {
+        let start = buf.len();
+        buf.resize(len.min(start + ARRAY_CHUNK_LEN), T::default());
+        read_chunk(&mut buf[start..])?;
+    }
+
+    Ok(buf)
+}
+
+/// Reads the given amount of bytes, see [`read_array`].
+#[inline]
+pub(crate) fn read_bytes(input: &mut impl Read, len: usize) -> Result<Vec<u8>, NbtError> {
+    read_array(len, |chunk| input.read_exact(chunk))
+}
+
+/// Reads the contents of an int array with the given length in a single pass.
+pub(crate) fn read_i32_array<F>(
+    input: &mut impl ReadBytesExt,
+    len: u32,
+) -> Result<Vec<i32>, NbtError>
+where
+    F: EndiannessImpl,
+{
+    read_array(len as usize, |chunk| match F::AS_ENUM {
+        Variant::BigEndian => input.read_i32_into::<BigEndian>(chunk),
+        Variant::LittleEndian => input.read_i32_into::<LittleEndian>(chunk),
+        Variant::NetworkEndian => chunk.iter_mut().try_for_each(|v| {
+            *v = input.read_i32_varint()?;
+            Ok(())
+        }),
+    })
+}
+
+/// Reads the contents of a long array with the given length in a single pass.
+pub(crate) fn read_i64_array<F>(
+    input: &mut impl ReadBytesExt,
+    len: u32,
+) -> Result<Vec<i64>, NbtError>
+where
+    F: EndiannessImpl,
+{
+    read_array(len as usize, |chunk| match F::AS_ENUM {
+        Variant::BigEndian => input.read_i64_into::<BigEndian>(chunk),
+        Variant::LittleEndian => input.read_i64_into::<LittleEndian>(chunk),
+        Variant::NetworkEndian => chunk.iter_mut().try_for_each(|v| {
+            *v = input.read_i64_varint()?;
+            Ok(())
+        }),
+    })
+}
+
+/// Discards the given amount of bytes.
+fn skip_bytes(input: &mut impl Read, len: u64) -> Result<(), NbtError> {
+    let mut buf = [0; 512];
+    let mut remaining = len;
+    while remaining > 0 {
+        let chunk = remaining.min(buf.len() as u64) as usize;
+        input.read_exact(&mut buf[..chunk])?;
+        remaining -= chunk as u64;
+    }
+
+    Ok(())
+}
+
+/// Returns whether the input contains any more data, consuming at most a single byte.
+fn has_remaining(input: &mut impl Read) -> io::Result<bool> {
+    let mut buf = [0];
+    loop {
+        match input.read(&mut buf) {
+            Ok(n) => return Ok(n > 0),
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Skips the remaining entries of a compound, including the end tag.
+pub(crate) fn skip_compound<F>(input: &mut impl Read) -> Result<(), NbtError>
+where
+    F: EndiannessImpl,
+{
+    loop {
+        let ty = FieldType::try_from(input.read_u8()?)?;
+        if ty == FieldType::End {
+            return Ok(());
+        }
+
+        let len = read_string_len::<F>(input)?;
+        skip_bytes(input, len as u64)?;
+        skip_value::<F>(input, ty)?;
+    }
+}
+
+/// Skips a value of the given type.
+pub(crate) fn skip_value<F>(input: &mut impl Read, ty: FieldType) -> Result<(), NbtError>
+where
+    F: EndiannessImpl,
+{
+    let is_network = F::AS_ENUM == Variant::NetworkEndian;
+
+    match ty {
+        FieldType::End => Ok(()),
+        FieldType::Byte => skip_bytes(input, 1),
+        FieldType::Short => skip_bytes(input, 2),
+        FieldType::Float => skip_bytes(input, 4),
+        FieldType::Double => skip_bytes(input, 8),
+        // Network integers are variable-length and have to be decoded to find their size.
+        FieldType::Int if is_network => read_i32::<F>(input).map(drop),
+        FieldType::Long if is_network => read_i64::<F>(input).map(drop),
+        FieldType::Int => skip_bytes(input, 4),
+        FieldType::Long => skip_bytes(input, 8),
+        FieldType::String => {
+            let len = read_string_len::<F>(input)?;
+            skip_bytes(input, len as u64)
+        }
+        FieldType::ByteArray => {
+            let len = read_seq_len::<F>(input)?;
+            skip_bytes(input, len as u64)
+        }
+        FieldType::IntArray | FieldType::LongArray if !is_network => {
+            let len = read_seq_len::<F>(input)? as u64;
+            let size = if ty == FieldType::IntArray { 4 } else { 8 };
+            skip_bytes(input, len * size)
+        }
+        FieldType::IntArray | FieldType::LongArray | FieldType::List => {
+            let elem_ty = if ty == FieldType::List {
+                FieldType::try_from(input.read_u8()?)?
+            } else if ty == FieldType::IntArray {
+                FieldType::Int
+            } else {
+                FieldType::Long
+            };
+
+            let len = read_seq_len::<F>(input)?;
+            if elem_ty == FieldType::End && len != 0 {
+                return Err(NbtError::UnmatchedEnd);
+            }
+            for _ in 0..len {
+                skip_value::<F>(input, elem_ty)?;
+            }
+            Ok(())
+        }
+        FieldType::Compound => skip_compound::<F>(input),
+    }
+}
+
+/// Verifies that a sequence has the length expected by a fixed-size type such as a tuple.
+///
+/// An expected length of 0 means that the length is not known in advance.
+#[inline]
+fn check_seq_len(expected: usize, actual: u32, ty: FieldType) -> Result<(), NbtError> {
+    if expected != 0 && expected != actual as usize {
+        return Err(NbtError::LengthMismatch {
+            expected,
+            actual: actual as usize,
+            ty,
+        });
+    }
+
+    Ok(())
+}
+
+/// Passes an already decoded array to the visitor.
+#[inline]
+pub(crate) fn visit_array<'de, V, T>(
+    array: impl IntoIterator<Item = T>,
+    visitor: V,
+) -> Result<V::Value, NbtError>
+where
+    V: Visitor<'de>,
+    ArrayElement<T>: de::IntoDeserializer<'de, NbtError>,
+{
+    visitor.visit_seq(de::value::SeqDeserializer::new(
+        array.into_iter().map(ArrayElement),
+    ))
+}
+
+/// Deserializes a single element of an already decoded array.
+///
+/// Unlike the primitive deserializers provided by serde, this reinterprets the bits
+/// for unsigned integers, just like [`Deserializer`] does.
+pub(crate) struct ArrayElement<T>(T);
+
+/// Implements the unsigned deserialize methods by reinterpreting the bits as the given unsigned type.
+macro_rules! forward_unsigned {
+    ($unsigned: ident, $($ty: ident),+) => {
+        paste! {$(
+            #[inline]
+            fn [<deserialize_ $ty>]<V>(self, visitor: V) -> Result<V::Value, NbtError>
+            where
+                V: Visitor<'de>,
+            {
+                visitor.[<visit_ $unsigned>](self.0 as $unsigned)
+            }
+        )+}
+    }
+}
+
+macro_rules! impl_array_element {
+    ($($ty: ident => $unsigned: ident),+) => {
+        paste! {$(
+            impl<'de> de::Deserializer<'de> for ArrayElement<$ty> {
+                type Error = NbtError;
+
+                #[inline]
+                fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, NbtError>
+                where
+                    V: Visitor<'de>,
+                {
+                    visitor.[<visit_ $ty>](self.0)
+                }
+
+                forward_unsigned!($unsigned, u8, u16, u32, u64);
+
+                #[inline]
+                fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value, NbtError>
+                where
+                    V: Visitor<'de>,
+                {
+                    visitor.visit_bool(self.0 != 0)
+                }
+
+                serde::forward_to_deserialize_any! {
+                    i8 i16 i32 i64 i128 u128 f32 f64 char str string
+                    bytes byte_buf option unit unit_struct newtype_struct seq tuple
+                    tuple_struct map struct enum identifier ignored_any
+                }
+            }
+
+            impl<'de> de::IntoDeserializer<'de, NbtError> for ArrayElement<$ty> {
+                type Deserializer = Self;
+
+                #[inline]
+                fn into_deserializer(self) -> Self {
+                    self
+                }
+            }
+        )+}
+    }
+}
+
+impl_array_element!(i8 => u8, i32 => u32, i64 => u64);
+
+#[inline]
+pub(crate) fn read_i16<F>(input: &mut impl ReadBytesExt) -> Result<i16, NbtError>
+where
+    F: EndiannessImpl,
+{
+    Ok(match F::AS_ENUM {
+        Variant::BigEndian => input.read_i16::<BigEndian>(),
+        Variant::LittleEndian | Variant::NetworkEndian => input.read_i16::<LittleEndian>(),
+    }?)
+}
+
+#[inline]
+pub(crate) fn read_i32<F>(input: &mut impl ReadBytesExt) -> Result<i32, NbtError>
+where
+    F: EndiannessImpl,
+{
+    Ok(match F::AS_ENUM {
+        Variant::BigEndian => input.read_i32::<BigEndian>(),
+        Variant::LittleEndian => input.read_i32::<LittleEndian>(),
+        Variant::NetworkEndian => input.read_i32_varint(),
+    }?)
+}
+
+#[inline]
+pub(crate) fn read_i64<F>(input: &mut impl ReadBytesExt) -> Result<i64, NbtError>
+where
+    F: EndiannessImpl,
+{
+    Ok(match F::AS_ENUM {
+        Variant::BigEndian => input.read_i64::<BigEndian>(),
+        Variant::LittleEndian => input.read_i64::<LittleEndian>(),
+        Variant::NetworkEndian => input.read_i64_varint(),
+    }?)
+}
+
+#[inline]
+pub(crate) fn read_f32<F>(input: &mut impl ReadBytesExt) -> Result<f32, NbtError>
+where
+    F: EndiannessImpl,
+{
+    Ok(match F::AS_ENUM {
+        Variant::BigEndian => input.read_f32::<BigEndian>(),
+        _ => input.read_f32::<LittleEndian>(),
+    }?)
+}
+
+#[inline]
+pub(crate) fn read_f64<F>(input: &mut impl ReadBytesExt) -> Result<f64, NbtError>
+where
+    F: EndiannessImpl,
+{
+    Ok(match F::AS_ENUM {
+        Variant::BigEndian => input.read_f64::<BigEndian>(),
+        _ => input.read_f64::<LittleEndian>(),
+    }?)
+}
+
+/// Reader that keeps track of the amount of bytes that have been read.
+#[derive(Debug)]
+struct CountingReader<'re, R> {
+    inner: &'re mut R,
+    offset: usize,
+    /// Maximum amount of bytes that may be read, if any.
+    limit: Option<usize>,
+    /// Whether a read failed because it would have exceeded the limit.
+    limit_exceeded: bool,
+}
+
+impl<R> CountingReader<'_, R> {
+    /// Makes sure that `len` more bytes can be read without exceeding the limit.
+    #[inline]
+    fn check_limit(&mut self, len: usize) -> io::Result<()> {
+        match self.limit {
+            Some(limit) if self.offset.saturating_add(len) > limit => {
+                self.limit_exceeded = true;
+                Err(io::Error::other("byte limit exceeded"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Converts an error into [`NbtError::LimitExceeded`] if it was caused by the limit,
+    /// and attaches the given location to it.
+    fn located(&self, err: NbtError, path: String) -> NbtError {
+        match self.limit {
+            Some(limit) if self.limit_exceeded => NbtError::LimitExceeded { limit },
+            _ => err,
+        }
+        .located(self.offset, path)
+    }
+}
+
+impl<R> Read for CountingReader<'_, R>
+where
+    R: Read,
+{
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        // Partial reads are allowed, so this only fails once the limit has been reached.
+        let len = match self.limit {
+            Some(limit) => buf.len().min(limit.saturating_sub(self.offset)),
+            None => buf.len(),
+        };
+        if len == 0 && !buf.is_empty() {
+            self.check_limit(buf.len())?;
+        }
+
+        let n = self.inner.read(&mut buf[..len])?;
+        self.offset += n;
+        Ok(n)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        self.check_limit(buf.len())?;
+        self.inner.read_exact(buf)?;
+        self.offset += buf.len();
+        Ok(())
+    }
+}
+
+/// Reads the type and name of the root compound.
+///
+/// If `headless` is set, only the type is read and the name is empty.
+fn read_root_header<F>(
+    input: &mut impl ReadBytesExt,
+    options: &DeserializeOptions,
+) -> Result<String, NbtError>
+where
+    F: EndiannessImpl,
+{
+    let ty = FieldType::try_from(input.read_u8()?)?;
+    if ty != FieldType::Compound {
+        return Err(NbtError::UnexpectedType {
+            actual: ty,
+            expected: FieldType::Compound,
+        });
+    }
+
+    if options.headless {
+        return Ok(String::new());
+    }
+
+    read_string_with::<F>(
+        input,
+        options.lossy_utf8,
+        options.legacy_java,
+        options.max_string_len,
+    )
+}
+
+/// Options for [`from_bytes_with_options`].
+///
+/// The default options are the same as those used by [`from_bytes`].
+#[derive(Debug, Clone)]
+pub struct DeserializeOptions {
+    /// Whether invalid UTF-8 in strings should be replaced with `U+FFFD`, like [`String::from_utf8_lossy`].
+    ///
+    /// By default, invalid UTF-8 results in an error.
+    /// This also applies to compound keys and the name of the root compound.
+    pub lossy_utf8: bool,
+    /// Maximum amount of bytes that may be read, including the header of the root compound.
+    ///
+    /// Reading more than this returns [`NbtError::LimitExceeded`], which protects against
+    /// data that is unexpectedly large, such as decompression bombs. By default, there is no limit.
+    pub max_bytes: Option<usize>,
+    /// Whether the root compound is written without a name.
+    ///
+    /// Some network protocols only write the type of the root compound, followed directly by its
+    /// entries. Minecraft: Java Edition does this for all NBT sent over the network since 1.20.2
+    /// (protocol version 764). [`Deserializer::root_name`] is empty in this case.
+    pub headless: bool,
+    /// Whether compounds that contain the same key more than once result in an error.
+    ///
+    /// Such compounds are malformed, but they do occur in corrupted files. By default, the last value of a
+    /// duplicate key wins, see the [crate documentation](crate#duplicate-keys). When this is enabled,
+    /// [`NbtError::DuplicateKey`] is returned instead.
+    pub deny_duplicate_keys: bool,
+    /// Whether strings are decoded leniently, as written by Minecraft: Java Edition.
+    ///
+    /// Java writes strings, including compound keys and the name of the root compound, in
+    /// [modified UTF-8](https://docs.oracle.com/javase/8/docs/api/java/io/DataInput.html#modified-utf-8).
+    /// Strings that contain `U+0000` or characters outside of the Basic Multilingual Plane, such as emoji,
+    /// are therefore not valid UTF-8 and result in an error by default. Older files are more likely to contain
+    /// these, since they were often written by third-party tools. When this is enabled, such strings are decoded
+    /// as modified UTF-8 instead. Strings that are valid UTF-8 are not affected.
+    pub legacy_java: bool,
+    /// Maximum length of a string in bytes, including compound keys and the name of the root compound.
+    ///
+    /// Longer strings return [`NbtError::StringTooLong`] before any memory is allocated for them. Extremely long
+    /// strings are almost always caused by corrupted data. The default is 16 MiB, which only matters for
+    /// [`NetworkLittleEndian`], since the other variants cannot store strings longer than 65535 bytes.
+    /// Lower limits apply to every variant.
+    pub max_string_len: usize,
+    /// Whether lists of numbers are deserialized into arrays when deserializing a [`Value`](crate::Value).
+    ///
+    /// By default, lists are always deserialized into [`Value::List`](crate::Value::List), so that they are written back as lists.
+    /// When this is enabled, the element type of every list decides the variant instead: non-empty lists of
+    /// bytes, ints and longs become [`Value::ByteArray`](crate::Value::ByteArray), [`Value::IntArray`](crate::Value::IntArray) and [`Value::LongArray`](crate::Value::LongArray), and are
+    /// read in a single pass like array tags. Empty lists and lists of other types are still deserialized into
+    /// [`Value::List`](crate::Value::List). This is the same rule as [`Value::compact`](crate::Value::compact), but the elements are never stored as separate
+    /// values. Other types, such as a `Vec<i32>` field, are not affected.
+    pub lists_as_arrays: bool,
+}
+
+impl Default for DeserializeOptions {
+    fn default() -> Self {
+        DeserializeOptions {
+            lossy_utf8: false,
+            max_bytes: None,
+            headless: false,
+            deny_duplicate_keys: false,
+            legacy_java: false,
+            max_string_len: 16 * 1024 * 1024,
+            lists_as_arrays: false,
+        }
+    }
+}
+
+/// A segment of the path to the tag that is currently being deserialized.
+#[derive(Debug)]
+enum PathSegment {
+    /// Key of a compound entry.
+    Key(String),
+    /// Index of a list element.
+    Index(u32),
+}
+
 /// NBT deserializer.
+///
+/// Errors returned by [`from_bytes`] and the functions built on it carry the byte offset at
+/// which they occurred and the path to the tag that was being deserialized,
+/// see [`NbtError::offset`] and [`NbtError::path`].
 #[derive(Debug)]
 pub struct Deserializer<'re, 'de, F, R>
 where
     R: ReadBytesExt,
     F: EndiannessImpl + 'de,
 {
-    input: &'re mut R,
+    input: CountingReader<'re, R>,
     next_ty: FieldType,
     is_key: bool,
+    /// The most recently read compound key.
+    key: Option<String>,
+    /// Path to the tag that is currently being deserialized.
+    path: Vec<PathSegment>,
+    /// Name of the root compound.
+    root_name: String,
+    /// Whether invalid UTF-8 is replaced rather than returning an error.
+    lossy_utf8: bool,
+    /// Whether duplicate keys in compounds return an error.
+    deny_duplicate_keys: bool,
+    /// Whether Java's modified UTF-8 is accepted in strings.
+    legacy_java: bool,
+    /// Maximum length of a string in bytes.
+    max_string_len: usize,
+    /// Whether lists of numbers are deserialized into array values.
+    lists_as_arrays: bool,
+    /// The complete input, if it is a slice. Strings are then borrowed from it where possible.
+    source: Option<&'de [u8]>,
     _marker: PhantomData<&'de F>,
 }
 
@@ -57,35 +711,123 @@ where
     F: EndiannessImpl + 'de,
 {
     /// Creates a new deserializer, consuming the reader.
+    #[inline]
     pub fn new(input: &'re mut R) -> Result<Self, NbtError> {
-        let next_ty = FieldType::try_from(input.read_u8()?)?;
-        if next_ty != FieldType::Compound {
-            return Err(NbtError::UnexpectedType {
-                actual: next_ty,
-                expected: FieldType::Compound,
-            });
-        }
+        Self::with_options(input, &DeserializeOptions::default())
+    }
+
+    /// Creates a new deserializer using the given options, consuming the reader.
+    pub fn with_options(input: &'re mut R, options: &DeserializeOptions) -> Result<Self, NbtError> {
+        let mut input = CountingReader {
+            inner: input,
+            offset: 0,
+            limit: options.max_bytes,
+            limit_exceeded: false,
+        };
+
+        let root_name = match read_root_header::<F>(&mut input, options) {
+            Ok(name) => name,
+            Err(err) => return Err(input.located(err, String::new())),
+        };
 
-        let de = Deserializer {
+        Ok(Deserializer {
             input,
-            next_ty,
+            next_ty: FieldType::Compound,
             is_key: false,
+            key: None,
+            path: Vec::new(),
+            root_name,
+            lossy_utf8: options.lossy_utf8,
+            deny_duplicate_keys: options.deny_duplicate_keys,
+            legacy_java: options.legacy_java,
+            max_string_len: options.max_string_len,
+            lists_as_arrays: options.lists_as_arrays,
+            source: None,
             _marker: PhantomData,
-        };
+        })
+    }
 
-        // Ignore name of root component
-        let len = match F::AS_ENUM {
-            Variant::BigEndian => de.input.read_u16::<BigEndian>()? as u32,
-            Variant::LittleEndian => de.input.read_u16::<LittleEndian>()? as u32,
-            Variant::NetworkEndian => de.input.read_u32_varint()?,
-        };
+    /// Returns the name of the root compound.
+    ///
+    /// Most files use an empty name, but some files such as those used by Java Edition
+    /// have a non-empty root name.
+    #[inline]
+    pub fn root_name(&self) -> &str {
+        &self.root_name
+    }
 
-        let mut buf = vec![0; len as usize];
-        de.input.read_exact(&mut buf)?;
+    /// Returns the amount of bytes that have been read so far, including the root compound's header.
+    #[inline]
+    pub fn offset(&self) -> usize {
+        self.input.offset
+    }
 
-        let _name = String::from_utf8(buf)?;
+    /// Returns the path to the tag that is currently being deserialized, such as `Level.Sections[3].Name`.
+    ///
+    /// This is empty while deserializing the root compound itself.
+    pub fn path(&self) -> String {
+        let mut path = String::new();
+        for segment in &self.path {
+            match segment {
+                PathSegment::Key(key) => {
+                    if !path.is_empty() {
+                        path.push('.');
+                    }
+                    path.push_str(key);
+                }
+                PathSegment::Index(i) => {
+                    path.push('[');
+                    path.push_str(&i.to_string());
+                    path.push(']');
+                }
+            }
+        }
+        path
+    }
+
+    /// Consumes the deserializer and returns the name of the root compound.
+    #[inline]
+    pub fn into_root_name(self) -> String {
+        self.root_name
+    }
+
+    /// Reads an array of `len` elements of type `ty` and passes it to a [`Value`](crate::Value) visitor.
+    fn visit_array_value<V>(
+        &mut self,
+        ty: FieldType,
+        len: u32,
+        visitor: V,
+    ) -> Result<V::Value, NbtError>
+    where
+        V: Visitor<'de>,
+    {
+        self.reserve_array(len, ty)?;
+        match ty {
+            FieldType::Byte => visitor.visit_byte_buf(read_bytes(&mut self.input, len as usize)?),
+            FieldType::Int => visitor.visit_enum(ArrayAccess::new(crate::Value::IntArray(
+                read_i32_array::<F>(&mut self.input, len)?,
+            ))),
+            _ => visitor.visit_enum(ArrayAccess::new(crate::Value::LongArray(read_i64_array::<
+                F,
+            >(
+                &mut self.input,
+                len,
+            )?))),
+        }
+    }
 
-        Ok(de)
+    /// Makes sure that an array of `len` elements of type `ty` fits within the byte limit,
+    /// before any memory is allocated for it.
+    fn reserve_array(&mut self, len: u32, ty: FieldType) -> Result<(), NbtError> {
+        // Varints take up at least a single byte.
+        let size = match (ty, F::AS_ENUM) {
+            (FieldType::Byte, _) | (_, Variant::NetworkEndian) => 1,
+            (FieldType::Int, _) => 4,
+            _ => 8,
+        };
+        self.input
+            .check_limit((len as usize).saturating_mul(size))?;
+        Ok(())
     }
 }
 
@@ -99,11 +841,133 @@ where
     F: EndiannessImpl + 'de,
 {
     let mut deserializer = Deserializer::<F, _>::new(reader)?;
-    let output = T::deserialize(&mut deserializer)?;
+    let output = T::deserialize(&mut deserializer)
+        .map_err(|err| deserializer.input.located(err, deserializer.path()))?;
 
     Ok(output)
 }
 
+/// Reads a single object of type `T` from the given buffer, in the given format variant which is selected at runtime.
+///
+/// This is the same as [`from_bytes`] with the [`EndiannessImpl`] that corresponds to `variant`.
+///
+/// # Example
+///
+/// ```rust
+/// # use nbtx::{nbt, Value, Variant};
+/// # fn main() {
+///  let buffer = nbtx::to_le_bytes(&nbt!({ "value": 1i32 })).unwrap();
+///  let value: Value = nbtx::from_bytes_dyn(Variant::LittleEndian, &mut buffer.as_slice()).unwrap();
+///  assert_eq!(value, nbt!({ "value": 1i32 }));
+/// # }
+/// ```
+pub fn from_bytes_dyn<'de, T>(
+    variant: Variant,
+    reader: &mut impl ReadBytesExt,
+) -> Result<T, NbtError>
+where
+    T: Deserialize<'de>,
+{
+    match variant {
+        Variant::BigEndian => from_bytes::<BigEndian, T>(reader),
+        Variant::LittleEndian => from_bytes::<LittleEndian, T>(reader),
+        Variant::NetworkEndian => from_bytes::<NetworkLittleEndian, T>(reader),
+    }
+}
+
+/// Reads a single object of type `T` from the given buffer, requiring that nothing follows it.
+///
+/// If any data remains in the reader after the root compound, [`NbtError::TrailingData`] is returned.
+/// This is useful to validate files, where trailing data indicates that the file is corrupted or consists
+/// of several concatenated files. Only a single byte is read to check this, so the reader does not have to
+/// end after the data. Use [`from_slice`] to get the remaining data instead.
+///
+/// # Example
+///
+/// ```rust
+/// # use nbtx::{nbt, NbtError, Value};
+/// # fn main() {
+///  let mut buffer = nbtx::to_be_bytes(&nbt!({ "name": "Steve" })).unwrap();
+///  assert!(nbtx::from_bytes_exact::<nbtx::BigEndian, Value>(&mut buffer.as_slice()).is_ok());
+///
+///  buffer.extend_from_slice(&[0, 0]);
+///  let result = nbtx::from_bytes_exact::<nbtx::BigEndian, Value>(&mut buffer.as_slice());
+///  assert!(matches!(result, Err(NbtError::TrailingData)));
+/// # }
+/// ```
+pub fn from_bytes_exact<'de, F, T>(reader: &mut impl ReadBytesExt) -> Result<T, NbtError>
+where
+    T: Deserialize<'de>,
+    F: EndiannessImpl + 'de,
+{
+    let output = from_bytes::<F, T>(reader)?;
+
+    if has_remaining(reader)? {
+        return Err(NbtError::TrailingData);
+    }
+
+    Ok(output)
+}
+
+/// Reads a single object of type `T` from the given buffer, using the given options.
+///
+/// # Example
+///
+/// ```rust
+/// # use nbtx::{nbt, DeserializeOptions, Value};
+/// # fn main() {
+///  let mut buffer = nbtx::to_be_bytes(&nbt!({ "name": "ab" })).unwrap();
+///  let len = buffer.len();
+///  buffer[len - 2] = 0xff;
+///
+///  let options = DeserializeOptions { lossy_utf8: true, ..Default::default() };
+///  let value: Value = nbtx::from_bytes_with_options::<nbtx::BigEndian, _>(&mut buffer.as_slice(), &options).unwrap();
+///  assert_eq!(value, nbt!({ "name": "a\u{fffd}" }));
+/// # }
+/// ```
+pub fn from_bytes_with_options<'de, F, T>(
+    reader: &mut impl ReadBytesExt,
+    options: &DeserializeOptions,
+) -> Result<T, NbtError>
+where
+    T: Deserialize<'de>,
+    F: EndiannessImpl + 'de,
+{
+    let mut deserializer = Deserializer::<F, _>::with_options(reader, options)?;
+    let output = T::deserialize(&mut deserializer)
+        .map_err(|err| deserializer.input.located(err, deserializer.path()))?;
+
+    Ok(output)
+}
+
+/// Reads a single object of type `T` from the given buffer, together with the name of the root compound.
+///
+/// See [`to_bytes_named`](crate::to_bytes_named) to write the root name back.
+///
+/// # Example
+///
+/// ```rust
+/// # use nbtx as nbt;
+/// # fn main() {
+/// # let owned_buffer = nbt::to_bytes_named::<nbt::BigEndian>("root", &nbt::nbt!({ "a": 1i32 })).unwrap();
+/// # let mut buffer = owned_buffer.as_slice();
+///  let (name, value) = nbt::from_bytes_named::<nbt::BigEndian, nbt::Value>(&mut buffer).unwrap();
+///
+///  assert_eq!(name, "root");
+/// # }
+/// ```
+pub fn from_bytes_named<'de, F, T>(reader: &mut impl ReadBytesExt) -> Result<(String, T), NbtError>
+where
+    T: Deserialize<'de>,
+    F: EndiannessImpl + 'de,
+{
+    let mut deserializer = Deserializer::<F, _>::new(reader)?;
+    let output = T::deserialize(&mut deserializer)
+        .map_err(|err| deserializer.input.located(err, deserializer.path()))?;
+
+    Ok((deserializer.into_root_name(), output))
+}
+
 /// Reads a single object of type `T` from the given buffer.
 ///
 /// This function uses the little endian format of NBT, which is used by disk formats
@@ -215,6 +1079,177 @@ where
     from_bytes::<NetworkLittleEndian, T>(reader)
 }
 
+/// Reads a single object of type `T` from the given buffer, where the root compound has no name.
+///
+/// This function uses the variable format of NBT, which is used by network formats
+/// in Minecraft: Bedrock Edition. See [`DeserializeOptions::headless`] for when the name is omitted.
+///
+/// # Example
+///
+/// ```rust
+/// # use nbtx::{nbt, Value};
+/// # fn main() {
+///  // Compound type, followed directly by an `End` tag.
+///  let buffer = [10, 0];
+///
+///  let data: Value = nbtx::from_net_bytes_headless(&mut buffer.as_slice()).unwrap();
+///  assert_eq!(data, nbt!({}));
+/// # }
+/// ```
+#[inline]
+pub fn from_net_bytes_headless<'data, T, R>(reader: &mut R) -> Result<T, NbtError>
+where
+    R: ReadBytesExt,
+    T: Deserialize<'data>,
+{
+    let options = DeserializeOptions {
+        headless: true,
+        ..Default::default()
+    };
+    from_bytes_with_options::<NetworkLittleEndian, T>(reader, &options)
+}
+
+/// Iterator over consecutive objects in a reader, created by [`from_bytes_iter`].
+pub struct FromBytesIter<F, T, R> {
+    reader: R,
+    /// Whether the end of the stream or an error has been reached.
+    done: bool,
+    _marker: PhantomData<(F, T)>,
+}
+
+impl<F, T, R> Iterator for FromBytesIter<F, T, R>
+where
+    F: EndiannessImpl,
+    T: DeserializeOwned,
+    R: Read,
+{
+    type Item = Result<T, NbtError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        // Read the first byte separately, to tell the end of the stream apart from truncated data.
+        let mut first = [0];
+        let result = loop {
+            match self.reader.read(&mut first) {
+                Ok(0) => {
+                    self.done = true;
+                    return None;
+                }
+                Ok(_) => {
+                    let mut reader = first.chain(&mut self.reader);
+                    break from_bytes::<F, T>(&mut reader);
+                }
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => break Err(err.into()),
+            }
+        };
+
+        self.done = result.is_err();
+        Some(result)
+    }
+}
+
+/// Returns an iterator that reads consecutive objects of type `T` from the given reader.
+///
+/// Every object is read as a separate root compound. The iterator ends when the end of the reader
+/// is reached between two objects. If an error occurs, it is returned and the iterator ends.
+///
+/// # Example
+///
+/// ```rust
+/// # use nbtx::{nbt, Value};
+/// # fn main() {
+///  let mut data = nbtx::to_le_bytes(&nbt!({ "id": 1i32 })).unwrap();
+///  data.extend(nbtx::to_le_bytes(&nbt!({ "id": 2i32 })).unwrap());
+///
+///  let values = nbtx::from_bytes_iter::<nbtx::LittleEndian, Value, _>(data.as_slice())
+///     .collect::<Result<Vec<_>, _>>()
+///     .unwrap();
+///
+///  assert_eq!(values, [nbt!({ "id": 1i32 }), nbt!({ "id": 2i32 })]);
+/// # }
+/// ```
+#[inline]
+pub fn from_bytes_iter<F, T, R>(reader: R) -> FromBytesIter<F, T, R>
+where
+    F: EndiannessImpl,
+    T: DeserializeOwned,
+    R: Read,
+{
+    FromBytesIter {
+        reader,
+        done: false,
+        _marker: PhantomData,
+    }
+}
+
+/// Reads a single object of type `T` from the start of the given slice.
+///
+/// On success, the deserialized object and the remaining bytes after it are returned.
+/// This is useful when the NBT data is followed by other data, such as in network packets.
+/// Strings are borrowed from the slice, so `T` can contain `&str` and `Cow<str>` fields that do not allocate.
+///
+/// # Example
+///
+/// ```rust
+/// # use nbtx::{nbt, Value};
+/// # fn main() {
+///  let mut data = nbtx::to_be_bytes(&nbt!({ "name": "Steve" })).unwrap();
+///  data.extend_from_slice(&[1, 2, 3]);
+///
+///  let (value, rest) = nbtx::from_slice::<nbtx::BigEndian, Value>(&data).unwrap();
+///  assert_eq!(value, nbt!({ "name": "Steve" }));
+///  assert_eq!(rest, [1, 2, 3]);
+/// # }
+/// ```
+#[inline]
+pub fn from_slice<'de, F, T>(data: &'de [u8]) -> Result<(T, &'de [u8]), NbtError>
+where
+    T: Deserialize<'de>,
+    F: EndiannessImpl + 'de,
+{
+    let mut rest = data;
+    let mut deserializer = Deserializer::<F, _>::new(&mut rest)?;
+    deserializer.source = Some(data);
+    let output = T::deserialize(&mut deserializer)
+        .map_err(|err| deserializer.input.located(err, deserializer.path()))?;
+
+    Ok((output, rest))
+}
+
+/// Reads a single object of type `T` in big endian format from the start of the given slice,
+/// see [`from_slice`].
+#[inline]
+pub fn from_be_slice<'de, T>(data: &'de [u8]) -> Result<(T, &'de [u8]), NbtError>
+where
+    T: Deserialize<'de>,
+{
+    from_slice::<BigEndian, T>(data)
+}
+
+/// Reads a single object of type `T` in little endian format from the start of the given slice,
+/// see [`from_slice`].
+#[inline]
+pub fn from_le_slice<'de, T>(data: &'de [u8]) -> Result<(T, &'de [u8]), NbtError>
+where
+    T: Deserialize<'de>,
+{
+    from_slice::<LittleEndian, T>(data)
+}
+
+/// Reads a single object of type `T` in network little endian format from the start of the given slice,
+/// see [`from_slice`].
+#[inline]
+pub fn from_net_slice<'de, T>(data: &'de [u8]) -> Result<(T, &'de [u8]), NbtError>
+where
+    T: Deserialize<'de>,
+{
+    from_slice::<NetworkLittleEndian, T>(data)
+}
+
 impl<'de, 'a, F, R> de::Deserializer<'de> for &'a mut Deserializer<'_, 'de, F, R>
 where
     R: ReadBytesExt,
@@ -222,7 +1257,7 @@ where
 {
     type Error = NbtError;
 
-    forward_unsupported!(char, u8, u16, u32, u64, i128, u128);
+    forward_unsupported!(i128, u128);
 
     fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, NbtError>
     where
@@ -232,9 +1267,7 @@ where
             self.deserialize_string(visitor)
         } else {
             match self.next_ty {
-                FieldType::End => Err(NbtError::Other(Cow::Borrowed(
-                    "Encountered unmatched end tag",
-                ))),
+                FieldType::End => Err(NbtError::UnmatchedEnd),
                 FieldType::Byte => self.deserialize_i8(visitor),
                 FieldType::Short => self.deserialize_i16(visitor),
                 FieldType::Int => self.deserialize_i32(visitor),
@@ -280,10 +1313,7 @@ where
     {
         is_ty!(Short, self.next_ty);
 
-        let n = match F::AS_ENUM {
-            Variant::BigEndian => self.input.read_i16::<BigEndian>(),
-            Variant::LittleEndian | Variant::NetworkEndian => self.input.read_i16::<LittleEndian>(),
-        }?;
+        let n = read_i16::<F>(&mut self.input)?;
 
         visitor.visit_i16(n)
     }
@@ -295,11 +1325,7 @@ where
     {
         is_ty!(Int, self.next_ty);
 
-        let n = match F::AS_ENUM {
-            Variant::BigEndian => self.input.read_i32::<BigEndian>(),
-            Variant::LittleEndian => self.input.read_i32::<LittleEndian>(),
-            Variant::NetworkEndian => self.input.read_i32_varint(),
-        }?;
+        let n = read_i32::<F>(&mut self.input)?;
 
         visitor.visit_i32(n)
     }
@@ -311,15 +1337,56 @@ where
     {
         is_ty!(Long, self.next_ty);
 
-        let n = match F::AS_ENUM {
-            Variant::BigEndian => self.input.read_i64::<BigEndian>(),
-            Variant::LittleEndian => self.input.read_i64::<LittleEndian>(),
-            Variant::NetworkEndian => self.input.read_i64_varint(),
-        }?;
+        let n = read_i64::<F>(&mut self.input)?;
 
         visitor.visit_i64(n)
     }
 
+    /// Unsigned integers are read from the signed tag of the same size, reinterpreting the bits.
+    #[inline]
+    fn deserialize_u8<V>(self, visitor: V) -> Result<V::Value, NbtError>
+    where
+        V: Visitor<'de>,
+    {
+        is_ty!(Byte, self.next_ty);
+
+        let n = self.input.read_u8()?;
+        visitor.visit_u8(n)
+    }
+
+    #[inline]
+    fn deserialize_u16<V>(self, visitor: V) -> Result<V::Value, NbtError>
+    where
+        V: Visitor<'de>,
+    {
+        is_ty!(Short, self.next_ty);
+
+        let n = read_i16::<F>(&mut self.input)?;
+        visitor.visit_u16(n as u16)
+    }
+
+    #[inline]
+    fn deserialize_u32<V>(self, visitor: V) -> Result<V::Value, NbtError>
+    where
+        V: Visitor<'de>,
+    {
+        is_ty!(Int, self.next_ty);
+
+        let n = read_i32::<F>(&mut self.input)?;
+        visitor.visit_u32(n as u32)
+    }
+
+    #[inline]
+    fn deserialize_u64<V>(self, visitor: V) -> Result<V::Value, NbtError>
+    where
+        V: Visitor<'de>,
+    {
+        is_ty!(Long, self.next_ty);
+
+        let n = read_i64::<F>(&mut self.input)?;
+        visitor.visit_u64(n as u64)
+    }
+
     #[inline]
     fn deserialize_f32<V>(self, visitor: V) -> Result<V::Value, NbtError>
     where
@@ -327,10 +1394,7 @@ where
     {
         is_ty!(Float, self.next_ty);
 
-        let n = match F::AS_ENUM {
-            Variant::BigEndian => self.input.read_f32::<BigEndian>(),
-            _ => self.input.read_f32::<LittleEndian>(),
-        }?;
+        let n = read_f32::<F>(&mut self.input)?;
 
         visitor.visit_f32(n)
     }
@@ -342,22 +1406,73 @@ where
     {
         is_ty!(Double, self.next_ty);
 
-        let n = match F::AS_ENUM {
-            Variant::BigEndian => self.input.read_f64::<BigEndian>(),
-            _ => self.input.read_f64::<LittleEndian>(),
-        }?;
+        let n = read_f64::<F>(&mut self.input)?;
 
         visitor.visit_f64(n)
     }
 
-    #[inline]
-    fn deserialize_str<V>(self, _visitor: V) -> Result<V::Value, NbtError>
+    fn deserialize_char<V>(self, visitor: V) -> Result<V::Value, NbtError>
     where
         V: Visitor<'de>,
     {
-        Err(NbtError::Unsupported(
-            "Deserializing string references is not supported",
-        ))
+        is_ty!(String, self.next_ty);
+
+        let string = read_string_with::<F>(
+            &mut self.input,
+            self.lossy_utf8,
+            self.legacy_java,
+            self.max_string_len,
+        )?;
+        let mut chars = string.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => visitor.visit_char(c),
+            _ => Err(NbtError::Other(Cow::Owned(format!(
+                "Expected a string containing a single character, found {string:?}"
+            )))),
+        }
+    }
+
+    /// Strings are borrowed from the input when reading from a slice using [`from_slice`], so `&str` fields
+    /// and `Cow<str>` fields with `#[serde(borrow)]` do not allocate. Strings that are not valid UTF-8 but can
+    /// still be decoded, see [`DeserializeOptions::legacy_java`], are passed as owned strings instead.
+    /// Strings read from any other [`Read`] are always owned, so `&str` fields are only supported for slices.
+    fn deserialize_str<V>(self, visitor: V) -> Result<V::Value, NbtError>
+    where
+        V: Visitor<'de>,
+    {
+        let Some(source) = self.source else {
+            return self.deserialize_string(visitor);
+        };
+        is_ty!(String, self.next_ty);
+
+        let len = read_string_len::<F>(&mut self.input)? as usize;
+        if len > self.max_string_len {
+            return Err(NbtError::StringTooLong {
+                len,
+                max: self.max_string_len,
+            });
+        }
+
+        // The source is the same slice that is being read from, so the offset is the position within it.
+        let start = self.input.offset;
+        skip_bytes(&mut self.input, len as u64)?;
+        let bytes = &source[start..start + len];
+
+        match core::str::from_utf8(bytes) {
+            Ok(string) => {
+                if self.is_key {
+                    self.key = Some(string.to_owned());
+                }
+                visitor.visit_borrowed_str(string)
+            }
+            Err(_) => {
+                let string = decode_string(bytes.to_vec(), self.lossy_utf8, self.legacy_java)?;
+                if self.is_key {
+                    self.key = Some(string.clone());
+                }
+                visitor.visit_string(string)
+            }
+        }
     }
 
     #[inline]
@@ -367,16 +1482,16 @@ where
     {
         is_ty!(String, self.next_ty);
 
-        let len = match F::AS_ENUM {
-            Variant::BigEndian => self.input.read_u16::<BigEndian>()? as u32,
-            Variant::LittleEndian => self.input.read_u16::<LittleEndian>()? as u32,
-            Variant::NetworkEndian => self.input.read_u32_varint()?,
-        };
-
-        let mut buf = vec![0; len as usize];
-        self.input.read_exact(&mut buf)?;
+        let string = read_string_with::<F>(
+            &mut self.input,
+            self.lossy_utf8,
+            self.legacy_java,
+            self.max_string_len,
+        )?;
+        if self.is_key {
+            self.key = Some(string.clone());
+        }
 
-        let string = String::from_utf8(buf)?;
         visitor.visit_string(string)
     }
 
@@ -410,14 +1525,10 @@ where
     {
         is_ty!(ByteArray, self.next_ty);
 
-        let len = match F::AS_ENUM {
-            Variant::BigEndian => self.input.read_i32::<BigEndian>()? as u32,
-            Variant::LittleEndian => self.input.read_i32::<LittleEndian>()? as u32,
-            Variant::NetworkEndian => self.input.read_i32_varint()? as u32,
-        };
+        let len = read_seq_len::<F>(&mut self.input)?;
+        self.reserve_array(len, FieldType::Byte)?;
 
-        let mut buf = vec![0; len as usize];
-        self.input.read_exact(&mut buf)?;
+        let buf = read_bytes(&mut self.input, len as usize)?;
 
         visitor.visit_byte_buf(buf)
     }
@@ -433,36 +1544,81 @@ where
         visitor.visit_some(self)
     }
 
-    fn deserialize_unit<V>(self, _visitor: V) -> Result<V::Value, NbtError>
+    /// Units are read from empty compounds.
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, NbtError>
     where
         V: Visitor<'de>,
     {
-        Err(NbtError::Unsupported(
-            "Deserializing unit values is not supported",
-        ))
+        is_ty!(Compound, self.next_ty);
+
+        let ty = FieldType::try_from(self.input.read_u8()?)?;
+        // Units are empty compounds, so the compound must end immediately.
+        if ty != FieldType::End {
+            return Err(NbtError::UnexpectedType {
+                expected: FieldType::End,
+                actual: ty,
+            });
+        }
+
+        visitor.visit_unit()
     }
 
+    #[inline]
     fn deserialize_unit_struct<V>(
         self,
         _name: &'static str,
-        _visitor: V,
+        visitor: V,
     ) -> Result<V::Value, NbtError>
     where
         V: Visitor<'de>,
     {
-        Err(NbtError::Unsupported(
-            "Deserializing unit structs is not supported",
-        ))
+        self.deserialize_unit(visitor)
     }
 
     fn deserialize_newtype_struct<V>(
         self,
-        _name: &'static str,
-        _visitor: V,
+        name: &'static str,
+        visitor: V,
     ) -> Result<V::Value, NbtError>
     where
         V: Visitor<'de>,
     {
+        if name == VALUE_TOKEN && !self.is_key {
+            // Int and long arrays are passed to the value as an enum with a private variant name,
+            // so they can be distinguished from lists.
+            return match self.next_ty {
+                FieldType::IntArray => {
+                    let len = read_seq_len::<F>(&mut self.input)?;
+                    self.visit_array_value(FieldType::Int, len, visitor)
+                }
+                FieldType::LongArray => {
+                    let len = read_seq_len::<F>(&mut self.input)?;
+                    self.visit_array_value(FieldType::Long, len, visitor)
+                }
+                FieldType::List if self.lists_as_arrays => {
+                    let ty = FieldType::try_from(self.input.read_u8()?)?;
+                    let len = read_seq_len::<F>(&mut self.input)?;
+
+                    // The elements of lists of numbers are encoded the same way as those of arrays.
+                    match ty {
+                        FieldType::Byte | FieldType::Int | FieldType::Long if len > 0 => {
+                            self.visit_array_value(ty, len, visitor)
+                        }
+                        _ => {
+                            self.next_ty = ty;
+                            visitor.visit_seq(SeqDeserializer {
+                                de: self,
+                                ty,
+                                remaining: len,
+                                index: 0,
+                            })
+                        }
+                    }
+                }
+                _ => self.deserialize_any(visitor),
+            };
+        }
+
         Err(NbtError::Unsupported(
             "Deserializing newtype structs is not supported",
         ))
@@ -481,29 +1637,51 @@ where
     where
         V: Visitor<'de>,
     {
-        let ty = match self.next_ty {
-            FieldType::ByteArray => FieldType::Byte,
-            FieldType::IntArray => FieldType::Int,
-            FieldType::LongArray => FieldType::Long,
-            _ => FieldType::try_from(self.input.read_u8()?)?,
-        };
+        // The array types are read in a single pass rather than element by element.
+        match self.next_ty {
+            FieldType::ByteArray => {
+                let remaining = read_seq_len::<F>(&mut self.input)?;
+                check_seq_len(len, remaining, FieldType::Byte)?;
+                self.reserve_array(remaining, FieldType::Byte)?;
+
+                let buf = read_bytes(&mut self.input, remaining as usize)?;
 
-        let de = SeqDeserializer::new(self, ty, len as u32)?;
-        visitor.visit_seq(de)
+                visit_array(buf.into_iter().map(|b| b as i8), visitor)
+            }
+            FieldType::IntArray => {
+                let remaining = read_seq_len::<F>(&mut self.input)?;
+                check_seq_len(len, remaining, FieldType::Int)?;
+                self.reserve_array(remaining, FieldType::Int)?;
+
+                visit_array(read_i32_array::<F>(&mut self.input, remaining)?, visitor)
+            }
+            FieldType::LongArray => {
+                let remaining = read_seq_len::<F>(&mut self.input)?;
+                check_seq_len(len, remaining, FieldType::Long)?;
+                self.reserve_array(remaining, FieldType::Long)?;
+
+                visit_array(read_i64_array::<F>(&mut self.input, remaining)?, visitor)
+            }
+            _ => {
+                let ty = FieldType::try_from(self.input.read_u8()?)?;
+                let de = SeqDeserializer::new(self, ty, len as u32)?;
+                visitor.visit_seq(de)
+            }
+        }
     }
 
+    /// Tuple structs are read from lists and arrays, just like tuples.
+    #[inline]
     fn deserialize_tuple_struct<V>(
         self,
         _name: &'static str,
-        _len: usize,
-        _visitor: V,
+        len: usize,
+        visitor: V,
     ) -> Result<V::Value, NbtError>
     where
         V: Visitor<'de>,
     {
-        Err(NbtError::Unsupported(
-            "Deserializing tuple structs is not supported",
-        ))
+        self.deserialize_tuple(len, visitor)
     }
 
     #[inline]
@@ -552,12 +1730,19 @@ where
         self.deserialize_string(visitor)
     }
 
+    /// Ignored values are skipped without decoding them, which is much faster than deserializing them.
     #[inline]
     fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, NbtError>
     where
         V: Visitor<'de>,
     {
-        self.deserialize_any(visitor)
+        // Keys are remembered for paths and duplicate key checks, so they are read as usual.
+        if self.is_key {
+            return self.deserialize_any(visitor);
+        }
+
+        skip_value::<F>(&mut self.input, self.next_ty)?;
+        visitor.visit_unit()
     }
 
     #[inline]
@@ -579,6 +1764,8 @@ where
     de: &'a mut Deserializer<'re, 'de, F, R>,
     ty: FieldType,
     remaining: u32,
+    /// Index of the next element.
+    index: u32,
 }
 
 impl<'de, 're, 'a, F, R> SeqDeserializer<'a, 're, 'de, F, R>
@@ -594,22 +1781,16 @@ where
     ) -> Result<Self, NbtError> {
         // debug_assert_ne!(ty, FieldType::End, "Cannot serialize sequence of end tags");
 
-        // ty is not read in here because the x_array types don't have a type prefix.
-
         de.next_ty = ty;
-        let remaining = match F::AS_ENUM {
-            Variant::BigEndian => de.input.read_i32::<BigEndian>()? as u32,
-            Variant::LittleEndian => de.input.read_i32::<LittleEndian>()? as u32,
-            Variant::NetworkEndian => de.input.read_i32_varint()? as u32,
-        };
-
-        if expected_len != 0 && expected_len != remaining {
-            return Err(NbtError::Other(Cow::Owned(format!(
-                "Sequence of {expected_len} {ty:?} expected, found only {remaining} items"
-            ))));
-        }
-
-        Ok(Self { de, ty, remaining })
+        let remaining = read_seq_len::<F>(&mut de.input)?;
+        check_seq_len(expected_len as usize, remaining, ty)?;
+
+        Ok(Self {
+            de,
+            ty,
+            remaining,
+            index: 0,
+        })
     }
 }
 
@@ -628,13 +1809,23 @@ where
         if self.remaining > 0 {
             self.remaining -= 1;
 
-            let output = seed.deserialize(&mut *self.de).map(Some);
+            // The segment is only removed on success, so that errors can report the full path.
+            self.de.path.push(PathSegment::Index(self.index));
+            let output = seed.deserialize(&mut *self.de)?;
+            self.de.path.pop();
+
+            self.index += 1;
             self.de.next_ty = self.ty;
-            output
+            Ok(Some(output))
         } else {
             Ok(None)
         }
     }
+
+    #[inline]
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining as usize)
+    }
 }
 
 /// Deserialises NBT compounds.
@@ -645,6 +1836,8 @@ where
     F: EndiannessImpl,
 {
     de: &'a mut Deserializer<'re, 'de, F, R>,
+    /// Keys that have been read so far, if duplicate keys are not allowed.
+    keys: Option<HashSet<String>>,
 }
 
 impl<'de, 're, 'a, F, R> From<&'a mut Deserializer<'re, 'de, F, R>>
@@ -655,7 +1848,8 @@ where
 {
     #[inline]
     fn from(v: &'a mut Deserializer<'re, 'de, F, R>) -> Self {
-        Self { de: v }
+        let keys = v.deny_duplicate_keys.then(HashSet::new);
+        Self { de: v, keys }
     }
 }
 
@@ -698,6 +1892,20 @@ where
             FieldType::End,
             "Cannot serialize end as a map field"
         );
-        seed.deserialize(&mut *self.de)
+
+        // The segment is only removed on success, so that errors can report the full path.
+        let key = self.de.key.take().unwrap_or_default();
+        if let Some(keys) = &mut self.keys {
+            if !keys.insert(key.clone()) {
+                self.de.path.push(PathSegment::Key(key.clone()));
+                return Err(NbtError::DuplicateKey { key });
+            }
+        }
+
+        self.de.path.push(PathSegment::Key(key));
+        let output = seed.deserialize(&mut *self.de)?;
+        self.de.path.pop();
+
+        Ok(output)
     }
 }
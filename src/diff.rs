@@ -0,0 +1,179 @@
+use alloc::borrow::ToOwned;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::Value;
+
+/// A single difference between two values, as reported by [`Value::diff_paths`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    /// The value only exists in the new value.
+    Added(Value),
+    /// The value only exists in the old value.
+    Removed(Value),
+    /// The value exists in both, but is different.
+    Changed {
+        /// The old value.
+        old: Value,
+        /// The new value.
+        new: Value,
+    },
+}
+
+impl Value {
+    /// Returns the differences between this value and `other`.
+    ///
+    /// Compounds are compared key by key and lists and arrays are compared element-wise.
+    /// Every difference is reported together with its path, which is a JSON pointer such as `/Level/Sections/0`.
+    /// The root value has the empty path. As in JSON pointers, `~` and `/` in keys are escaped as `~0` and `~1`.
+    ///
+    /// Differences are ordered by key within compounds and by index within lists and arrays.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use nbtx::{nbt, Change, Value};
+    /// let old = nbt!({ "health": 20i16, "pos": [I; 0, 64, 0] });
+    /// let new = nbt!({ "health": 18i16, "pos": [I; 0, 64, 0], "fire": 1i16 });
+    ///
+    /// assert_eq!(old.diff_paths(&new), [
+    ///     ("/fire".to_owned(), Change::Added(Value::Short(1))),
+    ///     ("/health".to_owned(), Change::Changed { old: Value::Short(20), new: Value::Short(18) }),
+    /// ]);
+    /// ```
+    pub fn diff_paths(&self, other: &Value) -> Vec<(String, Change)> {
+        let mut changes = Vec::new();
+        diff(&mut String::new(), self, other, &mut changes);
+        changes
+    }
+}
+
+/// Appends a path segment, escaping it as a JSON pointer.
+pub(crate) fn push_segment(path: &mut String, segment: &str) {
+    path.push('/');
+    for c in segment.chars() {
+        match c {
+            '~' => path.push_str("~0"),
+            '/' => path.push_str("~1"),
+            c => path.push(c),
+        }
+    }
+}
+
+/// Compares two sequences element-wise.
+fn diff_seq<T>(
+    path: &mut String,
+    old: &[T],
+    new: &[T],
+    changes: &mut Vec<(String, Change)>,
+    mut diff_element: impl FnMut(&mut String, &T, &T, &mut Vec<(String, Change)>),
+    to_value: impl Fn(&T) -> Value,
+) {
+    for i in 0..old.len().max(new.len()) {
+        let len = path.len();
+        push_segment(path, &i.to_string());
+
+        match (old.get(i), new.get(i)) {
+            (Some(old), Some(new)) => diff_element(path, old, new, changes),
+            (Some(old), None) => changes.push((path.clone(), Change::Removed(to_value(old)))),
+            (None, Some(new)) => changes.push((path.clone(), Change::Added(to_value(new)))),
+            (None, None) => unreachable!(),
+        }
+
+        path.truncate(len);
+    }
+}
+
+/// Compares two array elements.
+fn diff_scalar<T>(
+    path: &str,
+    old: &T,
+    new: &T,
+    changes: &mut Vec<(String, Change)>,
+    to_value: impl Fn(&T) -> Value,
+) where
+    T: PartialEq,
+{
+    if old != new {
+        changes.push((
+            path.to_owned(),
+            Change::Changed {
+                old: to_value(old),
+                new: to_value(new),
+            },
+        ));
+    }
+}
+
+fn diff(path: &mut String, old: &Value, new: &Value, changes: &mut Vec<(String, Change)>) {
+    match (old, new) {
+        (Value::Compound(old), Value::Compound(new)) => {
+            let mut keys: Vec<&String> = old
+                .keys()
+                .chain(new.keys().filter(|k| !old.contains_key(*k)))
+                .collect();
+            keys.sort();
+
+            for key in keys {
+                let len = path.len();
+                push_segment(path, key);
+
+                match (old.get(key), new.get(key)) {
+                    (Some(old), Some(new)) => diff(path, old, new, changes),
+                    (Some(old), None) => changes.push((path.clone(), Change::Removed(old.clone()))),
+                    (None, Some(new)) => changes.push((path.clone(), Change::Added(new.clone()))),
+                    (None, None) => unreachable!(),
+                }
+
+                path.truncate(len);
+            }
+        }
+        (Value::List(old), Value::List(new)) => {
+            diff_seq(path, old, new, changes, diff, Value::clone)
+        }
+        (Value::ByteArray(old), Value::ByteArray(new)) => {
+            let to_value = |v: &u8| Value::Byte(*v as i8);
+            diff_seq(
+                path,
+                old,
+                new,
+                changes,
+                |p, a, b, c| diff_scalar(p, a, b, c, to_value),
+                to_value,
+            )
+        }
+        (Value::IntArray(old), Value::IntArray(new)) => {
+            let to_value = |v: &i32| Value::Int(*v);
+            diff_seq(
+                path,
+                old,
+                new,
+                changes,
+                |p, a, b, c| diff_scalar(p, a, b, c, to_value),
+                to_value,
+            )
+        }
+        (Value::LongArray(old), Value::LongArray(new)) => {
+            let to_value = |v: &i64| Value::Long(*v);
+            diff_seq(
+                path,
+                old,
+                new,
+                changes,
+                |p, a, b, c| diff_scalar(p, a, b, c, to_value),
+                to_value,
+            )
+        }
+        (old, new) => {
+            if old != new {
+                changes.push((
+                    path.clone(),
+                    Change::Changed {
+                        old: old.clone(),
+                        new: new.clone(),
+                    },
+                ));
+            }
+        }
+    }
+}
@@ -1,76 +1,307 @@
-use std::borrow::Cow;
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::string::{FromUtf8Error, String, ToString};
+use core::fmt;
+use core::str::Utf8Error;
 
-use thiserror::Error;
-
-use crate::FieldType;
+use crate::{io, FieldType};
 
 /// Errors that can occur while serializing or deserializing NBT data.
-#[derive(Error, Debug, Clone)]
+///
+/// Errors implement [`Clone`], so that they can be cached or passed to multiple consumers.
+/// Since [`std::io::Error`] cannot be cloned, IO errors are stored as their kind and message.
+#[derive(Debug, Clone)]
 pub enum NbtError {
     /// The encountered NBT tag type is invalid.
-    #[error("An unknown tag type was encountered ({actual}), it should be in the range 0-12")]
     TypeOutOfRange { actual: u8 },
     /// Found a type different from the type that was expected.
-    #[error("Expected tag of type {expected:?}, received {actual:?}")]
     UnexpectedType {
         /// Type that the deserializer was expecting to find.
         expected: FieldType,
         /// Type that was found in the NBT stream.
         actual: FieldType,
     },
+    /// An end tag was found where a tag with a value was expected.
+    UnmatchedEnd,
+    /// A sequence does not have the length required by a fixed-size type such as a tuple or array.
+    LengthMismatch {
+        /// Length required by the type that is being deserialized.
+        expected: usize,
+        /// Length of the sequence that was found.
+        actual: usize,
+        /// Type of the elements of the sequence.
+        ty: FieldType,
+    },
+    /// A compound contains the same key more than once.
+    DuplicateKey {
+        /// The key that occurs more than once.
+        key: String,
+    },
+    /// The data is nested deeper than the configured maximum depth.
+    MaxDepthExceeded {
+        /// The maximum depth that was configured.
+        max: usize,
+    },
+    /// A list contains elements of different types.
+    HeterogeneousList {
+        /// Type of the first element in the list.
+        expected: FieldType,
+        /// Type of the element that did not match.
+        actual: FieldType,
+    },
+    /// A string is longer than the configured maximum length.
+    StringTooLong {
+        /// Length of the string in bytes.
+        len: usize,
+        /// The maximum length that was configured.
+        max: usize,
+    },
+    /// More bytes were read than the configured limit allows.
+    LimitExceeded {
+        /// The maximum amount of bytes that was configured.
+        limit: usize,
+    },
+    /// The input contained more data after the root compound.
+    TrailingData,
     /// The requested operation is not supported.
-    #[error("{0}")]
     Unsupported(&'static str),
     /// Any errors related to reading and writing from the stream.
-    #[error("{0}")]
-    ByteError(#[from] StreamError),
+    ByteError(StreamError),
     /// Other errors that do not fit in any of the previous categories.
-    #[error("{0}")]
     Other(Cow<'static, str>),
+    /// An error that occurred while deserializing binary NBT, together with where it occurred.
+    Located {
+        /// Amount of bytes that had been read when the error occurred.
+        offset: usize,
+        /// Path to the tag that was being deserialized, such as `Level.Sections[3].Name`.
+        ///
+        /// This is empty if the error occurred in the root compound.
+        path: String,
+        /// The error that occurred.
+        source: Box<NbtError>,
+    },
 }
 
-impl From<std::io::Error> for NbtError {
-    fn from(value: std::io::Error) -> Self {
-        Self::ByteError(StreamError::IoError(value.to_string()))
+impl NbtError {
+    /// Returns the byte offset at which the error occurred, if known.
+    #[inline]
+    pub fn offset(&self) -> Option<usize> {
+        match self {
+            Self::Located { offset, .. } => Some(*offset),
+            _ => None,
+        }
+    }
+
+    /// Returns the path to the tag that was being deserialized when the error occurred, if known.
+    ///
+    /// Compound keys are separated by dots and list indices are written in brackets,
+    /// such as `Level.Sections[3].Palette[0].Name`.
+    #[inline]
+    pub fn path(&self) -> Option<&str> {
+        match self {
+            Self::Located { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+
+    /// Returns the underlying error, without any location information.
+    #[inline]
+    pub fn inner(&self) -> &NbtError {
+        match self {
+            Self::Located { source, .. } => source.inner(),
+            err => err,
+        }
+    }
+
+    /// Attaches the given location to this error, unless it already has one.
+    pub(crate) fn located(self, offset: usize, path: String) -> NbtError {
+        match self {
+            Self::Located { .. } => self,
+            err => Self::Located {
+                offset,
+                path,
+                source: Box::new(err),
+            },
+        }
     }
 }
 
-impl From<std::str::Utf8Error> for NbtError {
-    fn from(value: std::str::Utf8Error) -> Self {
+impl fmt::Display for NbtError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TypeOutOfRange { actual } => write!(
+                f,
+                "An unknown tag type was encountered ({actual}), it should be in the range 0-12"
+            ),
+            Self::UnexpectedType { expected, actual } => {
+                write!(f, "Expected tag of type {expected:?}, received {actual:?}")
+            }
+            Self::UnmatchedEnd => f.write_str("Encountered unmatched end tag"),
+            Self::LengthMismatch {
+                expected,
+                actual,
+                ty,
+            } => write!(
+                f,
+                "Sequence of {expected} {ty:?} expected, found {actual} items"
+            ),
+            Self::DuplicateKey { key } => {
+                write!(f, "Compound contains the key {key:?} more than once")
+            }
+            Self::MaxDepthExceeded { max } => {
+                write!(f, "Maximum nesting depth of {max} was exceeded")
+            }
+            Self::HeterogeneousList { expected, actual } => write!(
+                f,
+                "List elements must all have the same type, expected {expected:?} but found {actual:?}"
+            ),
+            Self::StringTooLong { len, max } => write!(
+                f,
+                "String of {len} bytes exceeds the maximum length of {max} bytes"
+            ),
+            Self::LimitExceeded { limit } => {
+                write!(f, "Maximum of {limit} bytes to read was exceeded")
+            }
+            Self::TrailingData => f.write_str("Found trailing data after the root compound"),
+            Self::Unsupported(msg) => f.write_str(msg),
+            Self::ByteError(err) => fmt::Display::fmt(err, f),
+            Self::Other(msg) => f.write_str(msg),
+            Self::Located {
+                offset,
+                path,
+                source,
+            } => {
+                write!(f, "{source} (at byte offset {offset}")?;
+                if !path.is_empty() {
+                    write!(f, ", in `{path}`")?;
+                }
+                f.write_str(")")
+            }
+        }
+    }
+}
+
+impl core::error::Error for NbtError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::ByteError(err) => Some(err),
+            Self::Located { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<StreamError> for NbtError {
+    fn from(value: StreamError) -> Self {
+        Self::ByteError(value)
+    }
+}
+
+impl From<io::Error> for NbtError {
+    fn from(value: io::Error) -> Self {
+        Self::ByteError(StreamError::from(value))
+    }
+}
+
+/// Converts the error into an IO error, so that it can be used in functions returning [`std::io::Result`].
+///
+/// IO errors keep their original [`ErrorKind`](std::io::ErrorKind) and reading past the end of the data becomes
+/// [`UnexpectedEof`](std::io::ErrorKind::UnexpectedEof). All other errors indicate invalid data and become
+/// [`InvalidData`](std::io::ErrorKind::InvalidData). The original error is kept as the source of the IO error.
+#[cfg(feature = "std")]
+impl From<NbtError> for std::io::Error {
+    fn from(value: NbtError) -> Self {
+        let kind = match value.inner() {
+            NbtError::ByteError(StreamError::IoError { kind, .. }) => *kind,
+            NbtError::ByteError(StreamError::UnexpectedEof { .. }) => {
+                std::io::ErrorKind::UnexpectedEof
+            }
+            _ => std::io::ErrorKind::InvalidData,
+        };
+
+        std::io::Error::new(kind, value)
+    }
+}
+
+impl From<Utf8Error> for NbtError {
+    fn from(value: Utf8Error) -> Self {
         Self::ByteError(StreamError::Utf8Error(value))
     }
 }
 
-impl From<std::string::FromUtf8Error> for NbtError {
-    fn from(value: std::string::FromUtf8Error) -> Self {
+impl From<FromUtf8Error> for NbtError {
+    fn from(value: FromUtf8Error) -> Self {
         Self::ByteError(StreamError::FromUtf8Error(value))
     }
 }
 
 /// Errors related to binary reading and writing.
-#[derive(Debug, Clone, Error)]
+#[derive(Debug, Clone)]
 pub enum StreamError {
     // TODO: std::io::Error does not implement Clone while the ProtoCodec error type requires it.
-    // This is why I convert the error to a string rather than storing it directly like the others.
-    /// An IO [`Error`](std::io::Error).
-    #[error("{0}")]
-    IoError(String),
+    // This is why I convert the error to its kind and message rather than storing it directly like the others.
+    /// An IO [`Error`](io::Error).
+    IoError {
+        /// Kind of the original error.
+        kind: io::ErrorKind,
+        /// Message of the original error.
+        message: String,
+    },
     /// A byte slice could not be converted into a `String` because it is invalid UTF-8.
-    #[error("{0}")]
-    FromUtf8Error(#[from] std::string::FromUtf8Error),
+    FromUtf8Error(FromUtf8Error),
     /// A byte slice could not be converted into a `str` because it is invalid UTF-8.
-    #[error("{0}")]
-    Utf8Error(#[from] std::str::Utf8Error),
+    Utf8Error(Utf8Error),
     /// The deserializer tried to read past the end of the buffer.
-    #[error("Expected {expected} remaining bytes, found only {remaining}")]
     UnexpectedEof { expected: usize, remaining: usize },
     /// Any errors that do not fit the previous categories.
-    #[error("{0}")]
     Other(Cow<'static, str>),
 }
 
-impl From<std::io::Error> for StreamError {
-    fn from(value: std::io::Error) -> Self {
-        Self::IoError(value.to_string())
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::IoError { message, .. } => f.write_str(message),
+            Self::FromUtf8Error(err) => fmt::Display::fmt(err, f),
+            Self::Utf8Error(err) => fmt::Display::fmt(err, f),
+            Self::UnexpectedEof {
+                expected,
+                remaining,
+            } => write!(
+                f,
+                "Expected {expected} remaining bytes, found only {remaining}"
+            ),
+            Self::Other(msg) => f.write_str(msg),
+        }
+    }
+}
+
+impl core::error::Error for StreamError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::FromUtf8Error(err) => Some(err),
+            Self::Utf8Error(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<FromUtf8Error> for StreamError {
+    fn from(value: FromUtf8Error) -> Self {
+        Self::FromUtf8Error(value)
+    }
+}
+
+impl From<Utf8Error> for StreamError {
+    fn from(value: Utf8Error) -> Self {
+        Self::Utf8Error(value)
+    }
+}
+
+impl From<io::Error> for StreamError {
+    fn from(value: io::Error) -> Self {
+        Self::IoError {
+            kind: value.kind(),
+            message: value.to_string(),
+        }
     }
 }
@@ -0,0 +1,420 @@
+//! Readers and writers used to read and write binary NBT.
+//!
+//! With the `std` feature, which is enabled by default, these are [`std::io::Read`] and [`std::io::Write`],
+//! together with the extension traits of [`byteorder`] and `varint-rs`. Any reader or writer from the
+//! standard library can therefore be used.
+//!
+//! Without the `std` feature, this module provides minimal replacements instead. [`Read`] is implemented
+//! for byte slices and [`Write`] for `Vec<u8>`, so NBT can be read from and written to memory.
+//! Writing into a `&mut [u8]` is supported as well. Other sources can be used by implementing [`Read`] or
+//! [`Write`] for them, the extension traits are then implemented automatically.
+
+#[cfg(feature = "std")]
+pub use byteorder::{ReadBytesExt, WriteBytesExt};
+#[cfg(feature = "std")]
+pub use std::io::{Error, ErrorKind, Read, Result, Write};
+#[cfg(feature = "std")]
+pub use varint_rs::{VarintReader, VarintWriter};
+
+#[cfg(not(feature = "std"))]
+pub use self::no_std::*;
+
+#[cfg(not(feature = "std"))]
+mod no_std {
+    use alloc::vec::Vec;
+    use core::fmt;
+
+    use byteorder::ByteOrder;
+
+    /// Result of reading or writing.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    /// The kind of an [`Error`].
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    #[non_exhaustive]
+    pub enum ErrorKind {
+        /// The end of the input was reached before all data was read.
+        UnexpectedEof,
+        /// The writer did not accept any more data.
+        WriteZero,
+        /// The data that was read is invalid.
+        InvalidData,
+        /// The operation was interrupted and can be retried.
+        Interrupted,
+        /// Any other error.
+        Other,
+    }
+
+    /// An error that occurred while reading or writing.
+    #[derive(Debug, Clone)]
+    pub struct Error {
+        kind: ErrorKind,
+        message: &'static str,
+    }
+
+    impl Error {
+        /// Creates an error of the given kind with the given message.
+        #[inline]
+        pub const fn new(kind: ErrorKind, message: &'static str) -> Self {
+            Self { kind, message }
+        }
+
+        /// Creates an error of kind [`ErrorKind::Other`] with the given message.
+        #[inline]
+        pub const fn other(message: &'static str) -> Self {
+            Self::new(ErrorKind::Other, message)
+        }
+
+        /// Returns the kind of this error.
+        #[inline]
+        pub const fn kind(&self) -> ErrorKind {
+            self.kind
+        }
+    }
+
+    impl From<ErrorKind> for Error {
+        fn from(kind: ErrorKind) -> Self {
+            let message = match kind {
+                ErrorKind::UnexpectedEof => "failed to fill whole buffer",
+                ErrorKind::WriteZero => "failed to write whole buffer",
+                ErrorKind::InvalidData => "invalid data",
+                ErrorKind::Interrupted => "operation interrupted",
+                ErrorKind::Other => "other error",
+            };
+            Self::new(kind, message)
+        }
+    }
+
+    impl fmt::Display for Error {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(self.message)
+        }
+    }
+
+    impl core::error::Error for Error {}
+
+    /// A source of bytes, such as a byte slice.
+    pub trait Read {
+        /// Reads some bytes into the given buffer and returns how many were read.
+        ///
+        /// Returning zero for a non-empty buffer means that the end of the input was reached.
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize>;
+
+        /// Reads exactly enough bytes to fill the given buffer.
+        fn read_exact(&mut self, mut buf: &mut [u8]) -> Result<()> {
+            while !buf.is_empty() {
+                match self.read(buf)? {
+                    0 => return Err(ErrorKind::UnexpectedEof.into()),
+                    n => buf = &mut buf[n..],
+                }
+            }
+            Ok(())
+        }
+
+        /// Creates a reader that reads all of this reader and then all of `next`.
+        #[inline]
+        fn chain<R: Read>(self, next: R) -> Chain<Self, R>
+        where
+            Self: Sized,
+        {
+            Chain {
+                first: self,
+                second: next,
+                done_first: false,
+            }
+        }
+    }
+
+    /// Reader returned by [`Read::chain`].
+    #[derive(Debug)]
+    pub struct Chain<T, U> {
+        first: T,
+        second: U,
+        done_first: bool,
+    }
+
+    impl<T: Read, U: Read> Read for Chain<T, U> {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+            if !self.done_first {
+                match self.first.read(buf)? {
+                    0 if !buf.is_empty() => self.done_first = true,
+                    n => return Ok(n),
+                }
+            }
+            self.second.read(buf)
+        }
+    }
+
+    impl Read for &[u8] {
+        #[inline]
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+            let len = buf.len().min(self.len());
+            let (head, tail) = self.split_at(len);
+            buf[..len].copy_from_slice(head);
+            *self = tail;
+            Ok(len)
+        }
+    }
+
+    impl<R: Read + ?Sized> Read for &mut R {
+        #[inline]
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+            (**self).read(buf)
+        }
+
+        #[inline]
+        fn read_exact(&mut self, buf: &mut [u8]) -> Result<()> {
+            (**self).read_exact(buf)
+        }
+    }
+
+    /// A destination for bytes, such as a `Vec<u8>`.
+    pub trait Write {
+        /// Writes some bytes from the given buffer and returns how many were written.
+        fn write(&mut self, buf: &[u8]) -> Result<usize>;
+
+        /// Writes the whole buffer.
+        fn write_all(&mut self, mut buf: &[u8]) -> Result<()> {
+            while !buf.is_empty() {
+                match self.write(buf)? {
+                    0 => return Err(ErrorKind::WriteZero.into()),
+                    n => buf = &buf[n..],
+                }
+            }
+            Ok(())
+        }
+
+        /// Flushes any buffered data.
+        #[inline]
+        fn flush(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Write for Vec<u8> {
+        #[inline]
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            self.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+    }
+
+    impl Write for &mut [u8] {
+        #[inline]
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            let len = buf.len().min(self.len());
+            let (head, tail) = core::mem::take(self).split_at_mut(len);
+            head.copy_from_slice(&buf[..len]);
+            *self = tail;
+            Ok(len)
+        }
+    }
+
+    impl<W: Write + ?Sized> Write for &mut W {
+        #[inline]
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            (**self).write(buf)
+        }
+
+        #[inline]
+        fn flush(&mut self) -> Result<()> {
+            (**self).flush()
+        }
+    }
+
+    macro_rules! read_impl {
+        ($($read: ident => $ty: ty),+) => {
+            $(
+                #[doc = concat!("Reads a `", stringify!($ty), "` in the given byte order.")]
+                #[inline]
+                fn $read<B: ByteOrder>(&mut self) -> Result<$ty> {
+                    let mut buf = [0; core::mem::size_of::<$ty>()];
+                    self.read_exact(&mut buf)?;
+                    Ok(B::$read(&buf))
+                }
+            )+
+        };
+    }
+
+    /// Reads numbers in a given byte order, like `byteorder::ReadBytesExt`.
+    pub trait ReadBytesExt: Read {
+        /// Reads a `u8`.
+        #[inline]
+        fn read_u8(&mut self) -> Result<u8> {
+            let mut buf = [0];
+            self.read_exact(&mut buf)?;
+            Ok(buf[0])
+        }
+
+        /// Reads an `i8`.
+        #[inline]
+        fn read_i8(&mut self) -> Result<i8> {
+            self.read_u8().map(|v| v as i8)
+        }
+
+        read_impl!(
+            read_u16 => u16,
+            read_i16 => i16,
+            read_u32 => u32,
+            read_i32 => i32,
+            read_i64 => i64,
+            read_f32 => f32,
+            read_f64 => f64
+        );
+
+        /// Reads enough `u32`s in the given byte order to fill the buffer.
+        #[inline]
+        fn read_u32_into<B: ByteOrder>(&mut self, dst: &mut [u32]) -> Result<()> {
+            dst.iter_mut().try_for_each(|v| {
+                *v = self.read_u32::<B>()?;
+                Ok(())
+            })
+        }
+
+        /// Reads enough `i32`s in the given byte order to fill the buffer.
+        #[inline]
+        fn read_i32_into<B: ByteOrder>(&mut self, dst: &mut [i32]) -> Result<()> {
+            dst.iter_mut().try_for_each(|v| {
+                *v = self.read_i32::<B>()?;
+                Ok(())
+            })
+        }
+
+        /// Reads enough `i64`s in the given byte order to fill the buffer.
+        #[inline]
+        fn read_i64_into<B: ByteOrder>(&mut self, dst: &mut [i64]) -> Result<()> {
+            dst.iter_mut().try_for_each(|v| {
+                *v = self.read_i64::<B>()?;
+                Ok(())
+            })
+        }
+    }
+
+    impl<R: Read + ?Sized> ReadBytesExt for R {}
+
+    macro_rules! write_impl {
+        ($($write: ident => $ty: ty),+) => {
+            $(
+                #[doc = concat!("Writes a `", stringify!($ty), "` in the given byte order.")]
+                #[inline]
+                fn $write<B: ByteOrder>(&mut self, v: $ty) -> Result<()> {
+                    let mut buf = [0; core::mem::size_of::<$ty>()];
+                    B::$write(&mut buf, v);
+                    self.write_all(&buf)
+                }
+            )+
+        };
+    }
+
+    /// Writes numbers in a given byte order, like `byteorder::WriteBytesExt`.
+    pub trait WriteBytesExt: Write {
+        /// Writes a `u8`.
+        #[inline]
+        fn write_u8(&mut self, v: u8) -> Result<()> {
+            self.write_all(&[v])
+        }
+
+        /// Writes an `i8`.
+        #[inline]
+        fn write_i8(&mut self, v: i8) -> Result<()> {
+            self.write_u8(v as u8)
+        }
+
+        write_impl!(
+            write_u16 => u16,
+            write_i16 => i16,
+            write_u32 => u32,
+            write_i32 => i32,
+            write_u64 => u64,
+            write_i64 => i64,
+            write_f32 => f32,
+            write_f64 => f64
+        );
+    }
+
+    impl<W: Write + ?Sized> WriteBytesExt for W {}
+
+    /// Reads LEB128 varints, like `varint_rs::VarintReader`.
+    ///
+    /// Signed integers are zigzag encoded.
+    pub trait VarintReader: Read {
+        /// Reads a `u32` varint.
+        fn read_u32_varint(&mut self) -> Result<u32> {
+            self.read_u64_varint().and_then(|v| {
+                u32::try_from(v)
+                    .map_err(|_| Error::new(ErrorKind::InvalidData, "varint is too large"))
+            })
+        }
+
+        /// Reads a zigzag encoded `i32` varint.
+        fn read_i32_varint(&mut self) -> Result<i32> {
+            let v = self.read_u32_varint()?;
+            Ok((v >> 1) as i32 ^ -((v & 1) as i32))
+        }
+
+        /// Reads a `u64` varint.
+        fn read_u64_varint(&mut self) -> Result<u64> {
+            let mut value = 0;
+            for shift in (0..64).step_by(7) {
+                let mut byte = [0];
+                self.read_exact(&mut byte)?;
+                value |= ((byte[0] & 0x7f) as u64) << shift;
+                if byte[0] & 0x80 == 0 {
+                    return Ok(value);
+                }
+            }
+            Err(Error::new(ErrorKind::InvalidData, "varint is too large"))
+        }
+
+        /// Reads a zigzag encoded `i64` varint.
+        fn read_i64_varint(&mut self) -> Result<i64> {
+            let v = self.read_u64_varint()?;
+            Ok((v >> 1) as i64 ^ -((v & 1) as i64))
+        }
+    }
+
+    impl<R: Read + ?Sized> VarintReader for R {}
+
+    /// Writes LEB128 varints, like `varint_rs::VarintWriter`.
+    ///
+    /// Signed integers are zigzag encoded.
+    pub trait VarintWriter: Write {
+        /// Writes a `u32` varint.
+        #[inline]
+        fn write_u32_varint(&mut self, v: u32) -> Result<()> {
+            self.write_u64_varint(v as u64)
+        }
+
+        /// Writes a zigzag encoded `i32` varint.
+        #[inline]
+        fn write_i32_varint(&mut self, v: i32) -> Result<()> {
+            self.write_u32_varint(((v << 1) ^ (v >> 31)) as u32)
+        }
+
+        /// Writes a `u64` varint.
+        fn write_u64_varint(&mut self, mut v: u64) -> Result<()> {
+            let mut buf = [0; 10];
+            let mut len = 0;
+            loop {
+                let byte = (v & 0x7f) as u8;
+                v >>= 7;
+                if v == 0 {
+                    buf[len] = byte;
+                    len += 1;
+                    break;
+                }
+                buf[len] = byte | 0x80;
+                len += 1;
+            }
+            self.write_all(&buf[..len])
+        }
+
+        /// Writes a zigzag encoded `i64` varint.
+        #[inline]
+        fn write_i64_varint(&mut self, v: i64) -> Result<()> {
+            self.write_u64_varint(((v << 1) ^ (v >> 63)) as u64)
+        }
+    }
+
+    impl<W: Write + ?Sized> VarintWriter for W {}
+}
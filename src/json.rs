@@ -0,0 +1,148 @@
+//! Conversions between [`Value`] and [`serde_json::Value`].
+//!
+//! JSON only has a single number type and a single array type, so some type information is lost
+//! when converting to JSON:
+//!
+//! * All integer tags (`Byte`, `Short`, `Int` and `Long`) become JSON integers.
+//!   When converting back, integers that fit in an `i32` become an `Int`, other integers become a `Long`.
+//! * `Float` and `Double` become JSON floats. `NaN` and infinite values cannot be represented in JSON
+//!   and are converted to `null`. Converting back always produces a `Double`.
+//! * Booleans are converted to a `Byte` with value 0 or 1.
+//! * Lists become JSON arrays.
+//! * The typed array tags are tagged using an object with a single key, so that they survive a
+//!   round trip: `{"$byte_array": [...]}`, `{"$int_array": [...]}` and `{"$long_array": [...]}`.
+//!   Bytes in a byte array are written as signed integers, like NBT does.
+//!
+//! Converting `null` to NBT fails, since NBT has no equivalent.
+
+use std::borrow::Cow;
+
+use serde_json::{Number, Value as JsonValue};
+
+use crate::{Map, NbtError, Value};
+
+/// Key used to tag byte arrays in JSON.
+const BYTE_ARRAY_KEY: &str = "$byte_array";
+/// Key used to tag int arrays in JSON.
+const INT_ARRAY_KEY: &str = "$int_array";
+/// Key used to tag long arrays in JSON.
+const LONG_ARRAY_KEY: &str = "$long_array";
+
+/// Creates a tagged JSON object containing a single typed array.
+fn tagged_array<T>(key: &str, array: impl IntoIterator<Item = T>) -> JsonValue
+where
+    T: Into<JsonValue>,
+{
+    let array = array.into_iter().map(Into::into).collect();
+
+    let mut object = serde_json::Map::with_capacity(1);
+    object.insert(key.to_owned(), JsonValue::Array(array));
+    JsonValue::Object(object)
+}
+
+/// Converts a float to JSON, mapping non-finite values to `null`.
+fn float_to_json(v: f64) -> JsonValue {
+    Number::from_f64(v).map_or(JsonValue::Null, JsonValue::Number)
+}
+
+impl From<Value> for JsonValue {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Byte(v) => v.into(),
+            Value::Short(v) => v.into(),
+            Value::Int(v) => v.into(),
+            Value::Long(v) => v.into(),
+            Value::Float(v) => float_to_json(v as f64),
+            Value::Double(v) => float_to_json(v),
+            Value::ByteArray(v) => tagged_array(BYTE_ARRAY_KEY, v.into_iter().map(|b| b as i8)),
+            Value::String(v) => v.into(),
+            Value::List(v) => JsonValue::Array(v.into_iter().map(Into::into).collect()),
+            Value::Compound(v) => {
+                JsonValue::Object(v.into_iter().map(|(k, v)| (k, v.into())).collect())
+            }
+            Value::IntArray(v) => tagged_array(INT_ARRAY_KEY, v),
+            Value::LongArray(v) => tagged_array(LONG_ARRAY_KEY, v),
+        }
+    }
+}
+
+/// Converts the elements of a tagged typed array.
+fn array_from_json<T>(
+    key: &str,
+    array: &JsonValue,
+    convert: impl Fn(i64) -> Option<T>,
+) -> Result<Vec<T>, NbtError> {
+    let JsonValue::Array(array) = array else {
+        return Err(NbtError::Other(Cow::Owned(format!(
+            "Expected an array for `{key}`, found {array}"
+        ))));
+    };
+
+    array
+        .iter()
+        .map(|element| {
+            element.as_i64().and_then(&convert).ok_or_else(|| {
+                NbtError::Other(Cow::Owned(format!(
+                    "Element {element} of `{key}` is out of range"
+                )))
+            })
+        })
+        .collect()
+}
+
+impl TryFrom<JsonValue> for Value {
+    type Error = NbtError;
+
+    fn try_from(value: JsonValue) -> Result<Self, NbtError> {
+        Ok(match value {
+            JsonValue::Null => {
+                return Err(NbtError::Unsupported(
+                    "JSON null cannot be converted to NBT",
+                ))
+            }
+            JsonValue::Bool(v) => Value::Byte(v as i8),
+            JsonValue::Number(v) => {
+                if let Some(v) = v.as_i64() {
+                    match i32::try_from(v) {
+                        Ok(v) => Value::Int(v),
+                        Err(_) => Value::Long(v),
+                    }
+                } else if let Some(v) = v.as_f64().filter(|_| v.is_f64()) {
+                    Value::Double(v)
+                } else {
+                    return Err(NbtError::Other(Cow::Owned(format!(
+                        "JSON number {v} does not fit in a long"
+                    ))));
+                }
+            }
+            JsonValue::String(v) => Value::String(v),
+            JsonValue::Array(v) => Value::List(
+                v.into_iter()
+                    .map(Value::try_from)
+                    .collect::<Result<_, _>>()?,
+            ),
+            JsonValue::Object(v) => {
+                if v.len() == 1 {
+                    if let Some(array) = v.get(BYTE_ARRAY_KEY) {
+                        return array_from_json(BYTE_ARRAY_KEY, array, |b| {
+                            (-128..=255).contains(&b).then_some(b as u8)
+                        })
+                        .map(Value::ByteArray);
+                    } else if let Some(array) = v.get(INT_ARRAY_KEY) {
+                        return array_from_json(INT_ARRAY_KEY, array, |i| i32::try_from(i).ok())
+                            .map(Value::IntArray);
+                    } else if let Some(array) = v.get(LONG_ARRAY_KEY) {
+                        return array_from_json(LONG_ARRAY_KEY, array, Some).map(Value::LongArray);
+                    }
+                }
+
+                let mut map = Map::with_capacity(v.len());
+                for (k, v) in v {
+                    map.insert(k, Value::try_from(v)?);
+                }
+
+                Value::Compound(map)
+            }
+        })
+    }
+}
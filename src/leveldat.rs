@@ -0,0 +1,74 @@
+//! The `level.dat` file format of Minecraft: Bedrock Edition.
+//!
+//! The file consists of an 8-byte header followed by little endian NBT. The header contains the storage
+//! version of the world and the length of the NBT data, both as little endian 32-bit integers.
+
+use std::borrow::Cow;
+use std::io::Read;
+
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use serde::{Deserialize, Serialize};
+
+use crate::{from_bytes, to_bytes_in, NbtError};
+
+/// Reads a Bedrock `level.dat` file, returning the storage version from its header and the deserialized data.
+///
+/// Returns an error if the file is shorter than the length in the header,
+/// or if the NBT data does not take up exactly that length.
+///
+/// # Example
+///
+/// ```rust
+/// # use nbtx::{nbt, Value};
+/// # fn main() {
+///  let level = nbt!({ "LevelName": "My World" });
+///  let file = nbtx::to_bedrock_leveldat(10, &level).unwrap();
+///
+///  let (version, decoded) = nbtx::from_bedrock_leveldat::<Value>(&mut file.as_slice()).unwrap();
+///  assert_eq!(version, 10);
+///  assert_eq!(decoded, level);
+/// # }
+/// ```
+pub fn from_bedrock_leveldat<'de, T>(reader: &mut impl Read) -> Result<(i32, T), NbtError>
+where
+    T: Deserialize<'de>,
+{
+    let version = reader.read_i32::<LittleEndian>()?;
+    let len = reader.read_u32::<LittleEndian>()? as u64;
+
+    // The buffer grows as data arrives, rather than trusting the length up front.
+    let mut body = Vec::new();
+    let read = reader.take(len).read_to_end(&mut body)? as u64;
+    if read != len {
+        return Err(NbtError::Other(Cow::Owned(format!(
+            "level.dat header specifies {len} bytes of data, but only {read} are present"
+        ))));
+    }
+
+    let mut rest = body.as_slice();
+    let output = from_bytes::<LittleEndian, T>(&mut rest)?;
+    if !rest.is_empty() {
+        return Err(NbtError::Other(Cow::Owned(format!(
+            "level.dat header specifies {len} bytes of data, but the NBT data is {} bytes long",
+            len - rest.len() as u64
+        ))));
+    }
+
+    Ok((version, output))
+}
+
+/// Writes a Bedrock `level.dat` file with the given storage version, see [`from_bedrock_leveldat`].
+pub fn to_bedrock_leveldat<T>(version: i32, v: &T) -> Result<Vec<u8>, NbtError>
+where
+    T: ?Sized + Serialize,
+{
+    let mut body = Vec::new();
+    let len = to_bytes_in::<LittleEndian>(&mut body, v)?;
+
+    let mut out = Vec::with_capacity(8 + len);
+    out.write_i32::<LittleEndian>(version)?;
+    out.write_u32::<LittleEndian>(len as u32)?;
+    out.extend_from_slice(&body);
+
+    Ok(out)
+}
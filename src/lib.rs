@@ -1,25 +1,173 @@
 //! Implements NBT serialisation and deserialization for three different integer encodings.
+//!
+//! # Unsigned integers
+//!
+//! NBT only has signed integer types. Unsigned integers are stored in the signed tag of the same size
+//! (`u8` as a byte, `u16` as a short, `u32` as an int and `u64` as a long) by reinterpreting their bits.
+//! Values above the signed maximum therefore wrap around, for example `u8::MAX` is stored as the byte `-1`,
+//! and are converted back when deserialising into the same unsigned type.
+//!
+//! There is no 128-bit tag, so `i128` and `u128` are not supported by default. A `u128`, such as a UUID,
+//! can be stored as a long array of two longs using the [`u128_long_array`] module, see its documentation
+//! for the layout. Java Edition stores UUIDs as int arrays instead, see [`Value::as_uuid`].
+//!
+//! # Unit types
+//!
+//! NBT has no equivalent of `()`, so `()` and unit structs are stored as empty compounds.
+//! This makes it possible to use marker types as fields. When deserialising, a unit only accepts
+//! an empty compound.
+//!
+//! # Arrays
+//!
+//! NBT has a generic list tag as well as byte, int and long array tags. Serde does not tell the serializer
+//! whether a sequence should be an array, so the tags are chosen as follows:
+//!
+//! - Data serialised using [`serialize_bytes`](serde::Serializer::serialize_bytes), such as
+//!   [`serde_bytes`](https://crates.io/crates/serde_bytes) buffers, becomes a byte array.
+//! - [`Value::ByteArray`], [`Value::IntArray`] and [`Value::LongArray`] become the corresponding array.
+//! - Every other sequence becomes a list. This includes `Vec<u8>`, `Vec<i8>`, `Vec<i32>`, `Vec<i64>`
+//!   and `Vec<Value>`.
+//!
+//! Fields can be forced to be written as arrays using the [`byte_array`], [`int_array`] and
+//! [`long_array`] modules together with `#[serde(with = "...")]`.
+//!
+//! When deserialising, arrays and lists are interchangeable: a byte array can be read into a `Vec<i8>`
+//! or `Vec<u8>` and an int array into a `Vec<i32>`, just like a list of the same type.
+//!
+//! # Flatten
+//!
+//! Structs can use `#[serde(flatten)]`, for example to collect unknown entries into a [`Map`].
+//! Serde buffers the flattened entries before deserialising them, which loses the difference between
+//! lists and arrays: int and long arrays are read as lists of ints and longs. Byte arrays are unaffected.
+//!
+//! # Empty lists
+//!
+//! The element type of a list is taken from its first element. Empty lists have no element to take
+//! it from, so they are always written with [`FieldType::End`] as element type and a length of zero,
+//! which is what Minecraft itself writes. When reading, empty lists are accepted regardless of their
+//! element type, since older files also contain empty lists of other types.
+//!
+//! # Duplicate keys
+//!
+//! Compounds should not contain the same key twice, but such data does exist. Compounds can be read into
+//! any map type, such as [`HashMap`](std::collections::HashMap), [`BTreeMap`](std::collections::BTreeMap)
+//! or [`IndexMap`](https://docs.rs/indexmap/latest/indexmap/map/struct.IndexMap.html), as well as
+//! [`Value`]. For all of these, the last value of a duplicate key wins. An `IndexMap` keeps the key at the
+//! position of its first occurrence. Structs derived with serde return a "duplicate field" error instead.
+//! Use [`DeserializeOptions::deny_duplicate_keys`] to reject duplicate keys regardless of the type that
+//! is deserialized.
+//!
+//! # Root names
+//!
+//! The root compound of NBT data has a name. Structs are written with their name as the root name, so
+//! `#[serde(rename = "hello world")]` produces a root named `hello world`, as Java Edition files often require,
+//! and `#[serde(rename = "")]` produces an unnamed root. Maps and [`Value`] have no name and are written with
+//! an empty root name. Serde serializes structs that contain `#[serde(flatten)]` fields as maps, so these are
+//! written with an empty root name as well. Use [`to_bytes_named`] or [`SerializeOptions::root_name`] to choose
+//! the name in these cases. The root name is not checked when reading, use [`from_bytes_named`] to read it.
+//!
+//! # `no_std`
+//!
+//! The standard library is used through the `std` feature, which is enabled by default. Without it, this crate
+//! is `no_std` and only requires `alloc`:
+//!
+//! - Readers and writers implement the minimal traits of the [`io`] module instead of those of `std::io`.
+//!   Byte slices implement [`io::Read`] and `Vec<u8>` implements [`io::Write`], so functions such as
+//!   [`from_slice`], [`from_bytes`] and [`to_bytes`] work as usual.
+//! - [`Map`] is a [`BTreeMap`](alloc::collections::BTreeMap), so compound entries are iterated in sorted
+//!   key order.
+//! - Everything that requires the standard library is unavailable. This includes compression, the `json`,
+//!   `preserve_order`, `region` and `tokio` features, Bedrock `level.dat` files and `SystemTime` support in
+//!   [`millis_timestamp`].
 
-pub use crate::de::{from_be_bytes, from_bytes, from_le_bytes, from_net_bytes, Deserializer};
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+pub use crate::arrays::{byte_array, int_array, long_array, u128_long_array};
+#[cfg(feature = "tokio")]
+pub use crate::async_io::{
+    from_async_be_bytes, from_async_bytes, from_async_le_bytes, from_async_net_bytes,
+    to_async_be_bytes, to_async_bytes, to_async_le_bytes, to_async_net_bytes,
+};
+#[cfg(all(feature = "gzip", feature = "zlib"))]
+pub use crate::compression::from_compressed_bytes;
+#[cfg(feature = "zlib")]
+pub use crate::compression::{
+    from_deflate_bytes, from_zlib_be_bytes, from_zlib_bytes, to_deflate_bytes, to_zlib_be_bytes,
+    to_zlib_bytes, to_zlib_bytes_in,
+};
+#[cfg(feature = "gzip")]
+pub use crate::compression::{
+    from_gzip_be_bytes, from_gzip_bytes, to_gzip_be_bytes, to_gzip_bytes, to_gzip_bytes_in,
+};
+pub use crate::convert::{from_value, to_value};
+pub use crate::de::{
+    from_be_bytes, from_be_slice, from_bytes, from_bytes_dyn, from_bytes_exact, from_bytes_iter,
+    from_bytes_named, from_bytes_with_options, from_le_bytes, from_le_slice, from_net_bytes,
+    from_net_bytes_headless, from_net_slice, from_slice, DeserializeOptions, Deserializer,
+    FromBytesIter,
+};
+pub use crate::diff::Change;
+#[cfg(feature = "std")]
+pub use crate::leveldat::{from_bedrock_leveldat, to_bedrock_leveldat};
+pub use crate::schema::Schema;
 pub use crate::ser::{
-    to_be_bytes, to_be_bytes_in, to_bytes, to_bytes_in, to_le_bytes, to_le_bytes_in, to_net_bytes,
-    to_net_bytes_in, Serializer,
+    to_be_bytes, to_be_bytes_in, to_bytes, to_bytes_dyn, to_bytes_in, to_bytes_named,
+    to_bytes_sorted, to_bytes_with_options, to_le_bytes, to_le_bytes_in, to_net_bytes,
+    to_net_bytes_in, SerializeOptions, Serializer,
 };
-pub use crate::value::Value;
+pub use crate::snbt::{from_snbt, to_snbt, to_snbt_with_options, NonFiniteFloats, SnbtOptions};
+pub use crate::stats::NbtStats;
+pub use crate::stream::{detect_variant, looks_like, validate, validate_with_max_depth};
+pub use crate::time::millis_timestamp;
+pub use crate::value::{CompoundBuilder, Entry, Map, Value};
 pub use byteorder::{BigEndian, LittleEndian};
 
-use std::borrow::Cow;
-use std::fmt::{Debug, Display};
+use alloc::borrow::Cow;
+use alloc::string::ToString;
+use core::fmt::{Debug, Display};
 
 pub use error::NbtError;
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod test;
+#[cfg(all(test, not(feature = "std")))]
+mod test_no_std;
 
+mod arrays;
+#[cfg(feature = "tokio")]
+mod async_io;
+#[cfg(any(feature = "gzip", feature = "zlib"))]
+mod compression;
+mod convert;
 mod de;
+mod diff;
 mod error;
+pub mod io;
+#[cfg(feature = "json")]
+mod json;
+#[cfg(feature = "std")]
+mod leveldat;
+mod macros;
+#[cfg(feature = "region")]
+pub mod region;
+mod schema;
 mod ser;
+mod snbt;
+mod stats;
+pub mod stream;
+mod time;
+mod tree;
 mod value;
+mod walk;
+
+/// Items used by the [`nbt!`] macro, which also work without the `std` feature.
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::string::String;
+    pub use alloc::vec;
+}
 
 mod private {
     use byteorder::{BigEndian, LittleEndian};
@@ -76,7 +224,10 @@ pub enum Variant {
 /// The integer and long types are also varints.
 pub enum NetworkLittleEndian {}
 
-/// NBT field type
+/// NBT field type.
+///
+/// The discriminants are the tag IDs from the NBT specification. Use [`FieldType::try_from`]
+/// to convert a raw tag ID into a type and [`FieldType::as_u8`] for the reverse.
 // Compiler complains about unused enum variants even though they're constructed using a transmute.
 #[allow(dead_code)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -111,6 +262,41 @@ pub enum FieldType {
     LongArray = 12,
 }
 
+impl FieldType {
+    /// Returns the tag ID of this type, as defined by the NBT specification.
+    #[inline]
+    pub const fn as_u8(self) -> u8 {
+        self as u8
+    }
+
+    /// Returns the canonical name of this type from the NBT specification, such as `"TAG_Byte"`.
+    #[inline]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::End => "TAG_End",
+            Self::Byte => "TAG_Byte",
+            Self::Short => "TAG_Short",
+            Self::Int => "TAG_Int",
+            Self::Long => "TAG_Long",
+            Self::Float => "TAG_Float",
+            Self::Double => "TAG_Double",
+            Self::ByteArray => "TAG_Byte_Array",
+            Self::String => "TAG_String",
+            Self::List => "TAG_List",
+            Self::Compound => "TAG_Compound",
+            Self::IntArray => "TAG_Int_Array",
+            Self::LongArray => "TAG_Long_Array",
+        }
+    }
+}
+
+impl From<FieldType> for u8 {
+    #[inline]
+    fn from(v: FieldType) -> Self {
+        v.as_u8()
+    }
+}
+
 impl TryFrom<u8> for FieldType {
     type Error = NbtError;
 
@@ -123,7 +309,7 @@ impl TryFrom<u8> for FieldType {
         // SAFETY: Because `Self` is marked as `repr(u8)`, its layout is guaranteed to start
         // with a `u8` discriminant as its first field. Additionally, the raw discriminant is verified
         // to be in the enum's range.
-        Ok(unsafe { std::mem::transmute::<u8, FieldType>(v) })
+        Ok(unsafe { core::mem::transmute::<u8, FieldType>(v) })
     }
 }
 
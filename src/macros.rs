@@ -0,0 +1,81 @@
+/// Constructs a [`Value`](crate::Value) from an SNBT-like literal.
+///
+/// Compounds are written as `{ "key": value, ... }` and lists as `[value, ...]`.
+/// Scalars are converted using [`From`], so the tag is inferred from the type of the expression.
+/// Integer and float literals should therefore carry a suffix (`20i8`, `1.5f32`, ...) to select
+/// the right tag. Unsuffixed integers default to `Int` and unsuffixed floats to `Double`.
+///
+/// The typed array tags use the same syntax as SNBT: `[B; ...]` for a byte array,
+/// `[I; ...]` for an int array and `[L; ...]` for a long array.
+///
+/// # Example
+///
+/// ```rust
+/// # use nbtx::{nbt, Value};
+/// # fn main() {
+/// let value = nbt!({
+///     "health": 20i8,
+///     "pos": [1.0f64, 2.0, 3.0],
+///     "name": "Steve",
+///     "uuid": [I; 1, 2, 3, 4],
+///     "inventory": [{ "id": "minecraft:stone", "count": 64i8 }]
+/// });
+///
+/// assert!(value.is_compound());
+/// # }
+/// ```
+#[macro_export]
+macro_rules! nbt {
+    // Munches the elements of a list one at a time.
+    (@list [$($out: expr,)*]) => {
+        $crate::__private::vec![$($out,)*]
+    };
+    (@list [$($out: expr,)*] {$($inner: tt)*} $(, $($rest: tt)*)?) => {
+        $crate::nbt!(@list [$($out,)* $crate::nbt!({$($inner)*}),] $($($rest)*)?)
+    };
+    (@list [$($out: expr,)*] [$($inner: tt)*] $(, $($rest: tt)*)?) => {
+        $crate::nbt!(@list [$($out,)* $crate::nbt!([$($inner)*]),] $($($rest)*)?)
+    };
+    (@list [$($out: expr,)*] $value: expr $(, $($rest: tt)*)?) => {
+        $crate::nbt!(@list [$($out,)* $crate::nbt!($value),] $($($rest)*)?)
+    };
+
+    // Munches the entries of a compound one at a time.
+    (@compound $map: ident) => {};
+    (@compound $map: ident $key: literal : {$($inner: tt)*} $(, $($rest: tt)*)?) => {
+        $map.insert($crate::__private::String::from($key), $crate::nbt!({$($inner)*}));
+        $crate::nbt!(@compound $map $($($rest)*)?);
+    };
+    (@compound $map: ident $key: literal : [$($inner: tt)*] $(, $($rest: tt)*)?) => {
+        $map.insert($crate::__private::String::from($key), $crate::nbt!([$($inner)*]));
+        $crate::nbt!(@compound $map $($($rest)*)?);
+    };
+    (@compound $map: ident $key: literal : $value: expr $(, $($rest: tt)*)?) => {
+        $map.insert($crate::__private::String::from($key), $crate::nbt!($value));
+        $crate::nbt!(@compound $map $($($rest)*)?);
+    };
+
+    ([B; $($element: expr),* $(,)?]) => {
+        $crate::Value::ByteArray($crate::__private::vec![$(($element) as u8),*])
+    };
+    ([I; $($element: expr),* $(,)?]) => {
+        $crate::Value::IntArray($crate::__private::vec![$($element),*])
+    };
+    ([L; $($element: expr),* $(,)?]) => {
+        $crate::Value::LongArray($crate::__private::vec![$($element),*])
+    };
+    ([$($tt: tt)*]) => {
+        $crate::Value::List($crate::nbt!(@list [] $($tt)*))
+    };
+    ({$($tt: tt)*}) => {
+        $crate::Value::Compound({
+            #[allow(unused_mut)]
+            let mut map = $crate::Map::new();
+            $crate::nbt!(@compound map $($tt)*);
+            map
+        })
+    };
+    ($value: expr) => {
+        $crate::Value::from($value)
+    };
+}
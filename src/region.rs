@@ -0,0 +1,207 @@
+//! Reader for the region files (`.mca`) of Minecraft: Java Edition.
+//!
+//! A region file stores up to 32 by 32 chunks. It starts with a header of two 4 KiB tables: the first contains
+//! the location of every chunk in the file, as an offset and a length in 4 KiB sectors, and the second contains
+//! the time at which every chunk was last saved. Every chunk starts with its length and compression type,
+//! followed by the compressed big endian NBT data.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! # use nbtx::region::RegionFile;
+//! # fn main() -> Result<(), nbtx::NbtError> {
+//! let file = std::fs::File::open("region/r.0.0.mca")?;
+//! let mut region = RegionFile::open(file)?;
+//!
+//! for (x, z, chunk) in region.chunks() {
+//!     let chunk = chunk?;
+//!     println!("Chunk {x}, {z} has {} entries", chunk.len().unwrap_or(0));
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::borrow::Cow;
+use std::io::{Read, Seek, SeekFrom};
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+use crate::{from_be_bytes, from_gzip_be_bytes, from_zlib_be_bytes, NbtError, Value};
+
+/// Size of a sector, in bytes.
+const SECTOR_SIZE: u64 = 4096;
+
+/// Number of chunks along each side of a region.
+const REGION_SIZE: usize = 32;
+
+/// Number of chunks in a region.
+const CHUNK_COUNT: usize = REGION_SIZE * REGION_SIZE;
+
+/// A region file, see the [module documentation](self).
+#[derive(Debug)]
+pub struct RegionFile<R> {
+    reader: R,
+    /// Location of every chunk, as the offset in sectors in the upper three bytes and the length in
+    /// sectors in the lowest byte. Absent chunks have a location of zero.
+    locations: Box<[u32; CHUNK_COUNT]>,
+    /// Time at which every chunk was last saved, in seconds since the Unix epoch.
+    timestamps: Box<[u32; CHUNK_COUNT]>,
+}
+
+impl<R> RegionFile<R>
+where
+    R: Read + Seek,
+{
+    /// Reads the header of the region file.
+    ///
+    /// The header is read from the start of the reader, regardless of its current position.
+    pub fn open(mut reader: R) -> Result<Self, NbtError> {
+        reader.seek(SeekFrom::Start(0))?;
+
+        let mut locations = Box::new([0; CHUNK_COUNT]);
+        reader.read_u32_into::<BigEndian>(locations.as_mut_slice())?;
+        let mut timestamps = Box::new([0; CHUNK_COUNT]);
+        reader.read_u32_into::<BigEndian>(timestamps.as_mut_slice())?;
+
+        Ok(RegionFile {
+            reader,
+            locations,
+            timestamps,
+        })
+    }
+
+    /// Reads and decodes the chunk at the given coordinates.
+    ///
+    /// Returns `None` if the chunk is not present in this region. The coordinates are taken modulo 32,
+    /// so both the coordinates within the region and the absolute chunk coordinates can be used.
+    ///
+    /// Chunks compressed using gzip or zlib and uncompressed chunks are supported. Chunks that are
+    /// compressed using LZ4 or a custom algorithm, or that are too large to be stored in the region
+    /// file itself, return [`NbtError::Unsupported`].
+    pub fn chunk(&mut self, x: i32, z: i32) -> Option<Result<Value, NbtError>> {
+        let location = self.locations[index(x, z)];
+        if location == 0 {
+            return None;
+        }
+
+        Some(self.read_chunk(location))
+    }
+
+    /// Returns an iterator over all chunks that are present in this region.
+    ///
+    /// The chunks are yielded together with their coordinates within the region, ordered by `z` and then by `x`.
+    pub fn chunks(&mut self) -> Chunks<'_, R> {
+        Chunks {
+            region: self,
+            index: 0,
+        }
+    }
+
+    fn read_chunk(&mut self, location: u32) -> Result<Value, NbtError> {
+        let offset = (location >> 8) as u64 * SECTOR_SIZE;
+        let sectors = (location & 0xff) as u64;
+        if offset < 2 * SECTOR_SIZE {
+            return Err(NbtError::Other(Cow::Owned(format!(
+                "Chunk is located at offset {offset}, which overlaps the header"
+            ))));
+        }
+
+        self.reader.seek(SeekFrom::Start(offset))?;
+        let len = self.reader.read_u32::<BigEndian>()? as u64;
+        if len == 0 || len + 4 > sectors * SECTOR_SIZE {
+            return Err(NbtError::Other(Cow::Owned(format!(
+                "Chunk is {len} bytes long, which does not fit in its {sectors} sectors"
+            ))));
+        }
+
+        // The length includes the compression type.
+        let compression = self.reader.read_u8()?;
+        let mut data = Vec::new();
+        let read = (&mut self.reader).take(len - 1).read_to_end(&mut data)? as u64;
+        if read != len - 1 {
+            return Err(NbtError::Other(Cow::Owned(format!(
+                "Chunk is {len} bytes long, but only {} are present",
+                read + 1
+            ))));
+        }
+
+        let mut data = data.as_slice();
+        match compression {
+            1 => from_gzip_be_bytes(&mut data),
+            2 => from_zlib_be_bytes(&mut data),
+            3 => from_be_bytes(&mut data),
+            4 => Err(NbtError::Unsupported(
+                "Chunks compressed using LZ4 are not supported",
+            )),
+            127 => Err(NbtError::Unsupported(
+                "Chunks compressed using a custom algorithm are not supported",
+            )),
+            c if c & 0x80 != 0 => Err(NbtError::Unsupported(
+                "Chunks stored outside of the region file are not supported",
+            )),
+            c => Err(NbtError::Other(Cow::Owned(format!(
+                "Unknown chunk compression type {c}"
+            )))),
+        }
+    }
+}
+
+impl<R> RegionFile<R> {
+    /// Whether the chunk at the given coordinates is present in this region.
+    ///
+    /// See [`chunk`](Self::chunk) for how the coordinates are interpreted.
+    pub fn contains(&self, x: i32, z: i32) -> bool {
+        self.locations[index(x, z)] != 0
+    }
+
+    /// Returns the time at which the chunk at the given coordinates was last saved, in seconds since the Unix epoch.
+    ///
+    /// Returns `None` if the chunk is not present in this region.
+    /// See [`chunk`](Self::chunk) for how the coordinates are interpreted.
+    pub fn timestamp(&self, x: i32, z: i32) -> Option<u32> {
+        let index = index(x, z);
+        (self.locations[index] != 0).then_some(self.timestamps[index])
+    }
+
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+/// Returns the index of the chunk at the given coordinates in the header tables.
+fn index(x: i32, z: i32) -> usize {
+    let size = REGION_SIZE as i32;
+    (z.rem_euclid(size) * size + x.rem_euclid(size)) as usize
+}
+
+/// Iterator over the chunks in a region, see [`RegionFile::chunks`].
+#[derive(Debug)]
+pub struct Chunks<'a, R> {
+    region: &'a mut RegionFile<R>,
+    index: usize,
+}
+
+impl<R> Iterator for Chunks<'_, R>
+where
+    R: Read + Seek,
+{
+    /// The `x` and `z` coordinates within the region and the decoded chunk.
+    type Item = (u8, u8, Result<Value, NbtError>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.index < CHUNK_COUNT {
+            let index = self.index;
+            self.index += 1;
+
+            let location = self.region.locations[index];
+            if location != 0 {
+                let x = (index % REGION_SIZE) as u8;
+                let z = (index / REGION_SIZE) as u8;
+                return Some((x, z, self.region.read_chunk(location)));
+            }
+        }
+
+        None
+    }
+}
@@ -0,0 +1,113 @@
+use alloc::boxed::Box;
+#[cfg(not(feature = "std"))]
+use alloc::collections::BTreeMap as HashMap;
+use alloc::string::String;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+use crate::{FieldType, Value};
+
+/// Describes which values are allowed, as used by [`Value::filter_schema`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Schema {
+    /// Any value is allowed, including all of its contents.
+    Any,
+    /// Any value of the given type is allowed, including all of its contents.
+    Type(FieldType),
+    /// A compound that only contains the given keys, of which the values match the given schemas.
+    Compound(HashMap<String, Schema>),
+    /// A list of which the elements match the given schema.
+    List(Box<Schema>),
+}
+
+impl Schema {
+    /// Creates a compound schema from the given keys and schemas.
+    #[inline]
+    pub fn compound<K, I>(fields: I) -> Self
+    where
+        K: Into<String>,
+        I: IntoIterator<Item = (K, Schema)>,
+    {
+        Self::Compound(fields.into_iter().map(|(k, v)| (k.into(), v)).collect())
+    }
+
+    /// Creates a list schema of which the elements match the given schema.
+    #[inline]
+    pub fn list(element: Schema) -> Self {
+        Self::List(Box::new(element))
+    }
+
+    /// Returns whether the type of the value is allowed by this schema, without looking at its contents.
+    fn accepts(&self, value: &Value) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Type(ty) => value.field_type() == *ty,
+            Self::Compound(_) => value.is_compound(),
+            Self::List(_) => value.is_list(),
+        }
+    }
+
+    /// Removes everything that is not allowed from the contents of the value.
+    ///
+    /// The value itself must already be accepted by this schema.
+    fn filter(&self, value: &mut Value) {
+        match (self, value) {
+            (Self::Compound(fields), Value::Compound(map)) => {
+                map.retain(|k, v| match fields.get(k) {
+                    Some(schema) if schema.accepts(v) => {
+                        schema.filter(v);
+                        true
+                    }
+                    _ => false,
+                });
+            }
+            (Self::List(element), Value::List(list)) => {
+                list.retain_mut(|v| {
+                    if element.accepts(v) {
+                        element.filter(v);
+                        true
+                    } else {
+                        false
+                    }
+                });
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Value {
+    /// Removes everything from this value that is not allowed by the given schema.
+    ///
+    /// Compound entries with keys that are not in the schema are removed, as well as entries and list elements
+    /// of which the type does not match. This can be used to sanitise NBT sent by clients, by only keeping
+    /// the data that is expected.
+    ///
+    /// If this value itself does not match the schema, it is replaced by an empty compound.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use nbtx::{nbt, FieldType, Schema};
+    /// let schema = Schema::compound([
+    ///     ("Name", Schema::Type(FieldType::String)),
+    ///     ("Lore", Schema::list(Schema::Type(FieldType::String))),
+    /// ]);
+    ///
+    /// let mut display = nbt!({
+    ///     "Name": "Sword",
+    ///     "Lore": ["Sharp", 1i32],
+    ///     "Enchantments": [{ "id": "sharpness", "lvl": 1000i16 }]
+    /// });
+    /// display.filter_schema(&schema);
+    ///
+    /// assert_eq!(display, nbt!({ "Name": "Sword", "Lore": ["Sharp"] }));
+    /// ```
+    pub fn filter_schema(&mut self, schema: &Schema) {
+        if schema.accepts(self) {
+            schema.filter(self);
+        } else {
+            *self = Value::default();
+        }
+    }
+}
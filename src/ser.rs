@@ -1,12 +1,16 @@
-use std::marker::PhantomData;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
 
-use byteorder::{BigEndian, LittleEndian, WriteBytesExt};
+use byteorder::{BigEndian, LittleEndian};
 use paste::paste;
-use serde::ser::{Impossible, SerializeMap, SerializeSeq, SerializeStruct, SerializeTuple};
+use serde::ser::{
+    Impossible, SerializeMap, SerializeSeq, SerializeStruct, SerializeTuple, SerializeTupleStruct,
+};
 use serde::{ser, Serialize};
 
-use varint_rs::VarintWriter;
-
+use crate::io::{self, ReadBytesExt, VarintReader, VarintWriter, Write, WriteBytesExt};
+use crate::value::{INT_ARRAY_TOKEN, LONG_ARRAY_TOKEN};
 use crate::{EndiannessImpl, FieldType, NbtError, NetworkLittleEndian, Variant};
 
 /// Returns a `not supported` error.
@@ -16,7 +20,8 @@ macro_rules! forward_unsupported {
             #[inline]
             fn [<serialize_ $ty>](self, _v: $ty) -> Result<(), NbtError> {
                 Err(NbtError::Unsupported(concat!(
-                    "Serialization of `", stringify!($ty), "` is not supported"
+                    "Serialization of `", stringify!($ty), "` is not supported, as NBT has no 128-bit integers. ",
+                    "Use `nbtx::u128_long_array` to store a `u128` as a long array"
                 )))
             }
         )+}
@@ -30,7 +35,8 @@ macro_rules! forward_unsupported_field {
             #[inline]
             fn [<serialize_ $ty>](self, _v: $ty) -> Result<bool, NbtError> {
                 Err(NbtError::Unsupported(concat!(
-                    "Serialization of `", stringify!($ty), "` is not supported"
+                    "Serialization of `", stringify!($ty), "` is not supported, as NBT has no 128-bit integers. ",
+                    "Use `nbtx::u128_long_array` to store a `u128` as a long array"
                 )))
             }
         )+}
@@ -65,8 +71,56 @@ where
     Ok(ser.into_inner())
 }
 
+/// Serializes the given data in the given format variant, which is selected at runtime.
+///
+/// This is the same as [`to_bytes`] with the [`EndiannessImpl`] that corresponds to `variant`.
+///
+/// # Example
+///
+/// ```rust
+/// # use nbtx::{nbt, Variant};
+/// # fn main() {
+///  let value = nbt!({ "value": 1i32 });
+///  let encoded = nbtx::to_bytes_dyn(Variant::NetworkEndian, &value).unwrap();
+///  assert_eq!(encoded, nbtx::to_net_bytes(&value).unwrap());
+/// # }
+/// ```
+pub fn to_bytes_dyn(variant: Variant, v: &(impl Serialize + ?Sized)) -> Result<Vec<u8>, NbtError> {
+    match variant {
+        Variant::BigEndian => to_bytes::<BigEndian>(v),
+        Variant::LittleEndian => to_bytes::<LittleEndian>(v),
+        Variant::NetworkEndian => to_bytes::<NetworkLittleEndian>(v),
+    }
+}
+
+/// Writer that keeps track of the amount of bytes that have been written.
+struct CountingWriter<W> {
+    inner: W,
+    written: usize,
+}
+
+impl<W> Write for CountingWriter<W>
+where
+    W: Write,
+{
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.written += n;
+        Ok(n)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
 /// Serializes the given data in any endian format.
 ///
+/// On success, the amount of bytes written to the writer is returned.
+/// This is useful when the NBT data is framed inside a larger message.
+///
 /// See [`to_bytes`] for an alternative just returns a new buffer, instead of using an existing writer.
 ///
 /// # Example
@@ -82,20 +136,122 @@ where
 ///  let data = Data { value: "Hello, World!".to_owned() };
 ///  let mut writer = Cursor::new(Vec::new());
 ///
-///  nbtx::to_bytes_in::<nbtx::BigEndian>(&mut writer, &data).unwrap();
+///  let written = nbtx::to_bytes_in::<nbtx::BigEndian>(&mut writer, &data).unwrap();
+///  assert_eq!(written, writer.get_ref().len());
 /// # }
 /// ```
 pub fn to_bytes_in<E>(
     writer: &mut impl WriteBytesExt,
     v: &(impl Serialize + ?Sized),
-) -> Result<(), NbtError>
+) -> Result<usize, NbtError>
 where
     E: EndiannessImpl,
 {
+    let writer = CountingWriter {
+        inner: writer,
+        written: 0,
+    };
+
     let mut ser = Serializer::<_, E>::new(writer);
     v.serialize(&mut ser)?;
 
-    Ok(())
+    Ok(ser.into_inner().written)
+}
+
+/// Serializes the given data in any endian format, using the given name for the root compound.
+///
+/// See [`from_bytes_named`](crate::from_bytes_named) to read the root name.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() {
+///  let value = nbtx::nbt!({ "name": "Bananrama" });
+///  let encoded = nbtx::to_bytes_named::<nbtx::BigEndian>("hello world", &value).unwrap();
+/// # }
+/// ```
+pub fn to_bytes_named<E>(name: &str, v: &(impl Serialize + ?Sized)) -> Result<Vec<u8>, NbtError>
+where
+    E: EndiannessImpl,
+{
+    let mut ser = Serializer::<_, E>::new(Vec::new());
+    ser.set_root_name(name);
+    v.serialize(&mut ser)?;
+
+    Ok(ser.into_inner())
+}
+
+/// Serializes the given data in any endian format, writing the entries of maps in sorted key order.
+///
+/// Serializing the same data twice always produces the same output,
+/// regardless of the iteration order of the maps it contains.
+/// See [`Serializer::set_sort_keys`] for details.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() {
+///  let value = nbtx::nbt!({ "b": 1i32, "a": 2i32 });
+///  let encoded = nbtx::to_bytes_sorted::<nbtx::BigEndian>(&value).unwrap();
+/// # }
+/// ```
+pub fn to_bytes_sorted<E>(v: &(impl Serialize + ?Sized)) -> Result<Vec<u8>, NbtError>
+where
+    E: EndiannessImpl,
+{
+    let mut ser = Serializer::<_, E>::new(Vec::new());
+    ser.set_sort_keys(true);
+    v.serialize(&mut ser)?;
+
+    Ok(ser.into_inner())
+}
+
+/// Options for [`to_bytes_with_options`].
+///
+/// The default options are the same as those used by [`to_bytes`].
+#[derive(Debug, Clone, Default)]
+pub struct SerializeOptions {
+    /// Name written for the root compound, see [`Serializer::set_root_name`].
+    pub root_name: Option<String>,
+    /// Whether the entries of maps should be written in sorted key order, see [`Serializer::set_sort_keys`].
+    pub sort_keys: bool,
+    /// Maximum nesting depth of lists and compounds, see [`Serializer::set_max_depth`].
+    pub max_depth: Option<usize>,
+}
+
+/// Serializes the given data in any endian format, using the given options.
+///
+/// # Example
+///
+/// ```rust
+/// # use nbtx::{nbt, NbtError, SerializeOptions};
+/// # fn main() {
+///  let options = SerializeOptions { max_depth: Some(2), ..Default::default() };
+///
+///  let value = nbt!({ "list": [1i32, 2i32] });
+///  assert!(nbtx::to_bytes_with_options::<nbtx::BigEndian>(&value, &options).is_ok());
+///
+///  let value = nbt!({ "list": [[1i32]] });
+///  let result = nbtx::to_bytes_with_options::<nbtx::BigEndian>(&value, &options);
+///  assert!(matches!(result, Err(NbtError::MaxDepthExceeded { max: 2 })));
+/// # }
+/// ```
+pub fn to_bytes_with_options<E>(
+    v: &(impl Serialize + ?Sized),
+    options: &SerializeOptions,
+) -> Result<Vec<u8>, NbtError>
+where
+    E: EndiannessImpl,
+{
+    let mut ser = Serializer::<_, E>::new(Vec::new());
+    if let Some(name) = &options.root_name {
+        ser.set_root_name(name.as_str());
+    }
+    ser.set_sort_keys(options.sort_keys);
+    ser.set_max_depth(options.max_depth);
+    v.serialize(&mut ser)?;
+
+    Ok(ser.into_inner())
 }
 
 /// Serializes the given data in network little endian format.
@@ -130,6 +286,8 @@ where
 ///
 /// This is the format used by Minecraft: Bedrock Edition.
 ///
+/// On success, the amount of bytes written to the writer is returned.
+///
 /// See [`to_net_bytes`] for an alternative just returns a new buffer, instead of using an existing writer.
 ///
 /// # Example
@@ -149,7 +307,7 @@ where
 /// # }
 /// ```
 #[inline]
-pub fn to_net_bytes_in<T, W>(writer: &mut W, v: &T) -> Result<(), NbtError>
+pub fn to_net_bytes_in<T, W>(writer: &mut W, v: &T) -> Result<usize, NbtError>
 where
     W: WriteBytesExt,
     T: ?Sized + Serialize,
@@ -189,6 +347,8 @@ where
 ///
 /// This is the format used by Minecraft: Java Edition.
 ///
+/// On success, the amount of bytes written to the writer is returned.
+///
 /// See [`to_be_bytes`] for an alternative just returns a new buffer, instead of using an existing writer.
 ///
 /// # Example
@@ -208,7 +368,7 @@ where
 /// # }
 /// ```
 #[inline]
-pub fn to_be_bytes_in<T, W>(writer: &mut W, v: &T) -> Result<(), NbtError>
+pub fn to_be_bytes_in<T, W>(writer: &mut W, v: &T) -> Result<usize, NbtError>
 where
     W: WriteBytesExt,
     T: ?Sized + Serialize,
@@ -220,7 +380,7 @@ where
 ///
 /// This is the format used by Minecraft: Bedrock Edition.
 ///
-/// See [`to_be_bytes_in`] for an alternative that serializes into the given writer, instead
+/// See [`to_le_bytes_in`] for an alternative that serializes into the given writer, instead
 /// of producing a new one.
 ///
 /// # Example
@@ -248,7 +408,9 @@ where
 ///
 /// This is the format used by Minecraft: Bedrock Edition.
 ///
-/// See [`to_le_bytes_in`] for an alternative just returns a new buffer, instead of using an existing writer.
+/// On success, the amount of bytes written to the writer is returned.
+///
+/// See [`to_le_bytes`] for an alternative just returns a new buffer, instead of using an existing writer.
 ///
 /// # Example
 ///
@@ -267,7 +429,7 @@ where
 /// # }
 /// ```
 #[inline]
-pub fn to_le_bytes_in<T, W>(writer: &mut W, v: &T) -> Result<(), NbtError>
+pub fn to_le_bytes_in<T, W>(writer: &mut W, v: &T) -> Result<usize, NbtError>
 where
     W: WriteBytesExt,
     T: ?Sized + Serialize,
@@ -275,6 +437,17 @@ where
     to_bytes_in::<LittleEndian>(writer, v)
 }
 
+/// A list or array that is currently being serialised.
+#[derive(Debug)]
+struct SeqState {
+    /// Length that was written in front of the elements.
+    len: usize,
+    /// Amount of elements that have been written so far.
+    written: usize,
+    /// Element type of int and long arrays.
+    array_type: Option<FieldType>,
+}
+
 /// NBT data serializer.
 #[derive(Debug)]
 pub struct Serializer<W, E>
@@ -286,8 +459,31 @@ where
     /// Whether this is the first data to be written.
     /// This makes sure that the name and type of the root compound are written.
     is_initial: bool,
+    /// Name written for the root compound of every value.
+    /// If this is `None`, the name of the serialized struct is used instead.
+    root_name: Option<String>,
+    /// Whether the entries of maps should be written in sorted key order.
+    sort_keys: bool,
+    /// Buffered entries of the maps that are currently being serialised.
+    /// This is only used when keys are sorted.
+    ///
+    /// Every entry consists of its key and its encoded type, key and value.
+    entries: Vec<Vec<(Vec<u8>, Vec<u8>)>>,
     /// Stores the length of the list that is currently being serialised.
     len: usize,
+    /// Element types of the lists that are currently being serialised.
+    /// This is `None` for arrays and for lists of which no element has been written yet.
+    list_types: Vec<Option<FieldType>>,
+    /// Lengths of the lists and arrays that are currently being serialised.
+    /// These make sure that exactly as many elements are written as the length prefix says.
+    seqs: Vec<SeqState>,
+    /// Element type of the next sequence, if it is an int or long array.
+    /// These are written without the element type that lists have.
+    array_type: Option<FieldType>,
+    /// Amount of lists and compounds that are currently being serialised.
+    depth: usize,
+    /// Maximum value of `depth`, if any.
+    max_depth: Option<usize>,
     _marker: PhantomData<E>,
 }
 
@@ -302,11 +498,236 @@ where
         Serializer {
             writer: w,
             is_initial: true,
+            root_name: None,
+            sort_keys: false,
+            entries: Vec::new(),
             len: 0,
+            list_types: Vec::new(),
+            seqs: Vec::new(),
+            array_type: None,
+            depth: 0,
+            max_depth: None,
             _marker: PhantomData,
         }
     }
 
+    /// Sets whether the entries of maps should be written in sorted key order.
+    ///
+    /// Keys are sorted by their UTF-8 bytes, which is equal to sorting them by code point.
+    /// This makes the output deterministic, even if the iteration order of the map is not,
+    /// such as with [`HashMap`](std::collections::HashMap).
+    /// Struct fields are always written in declaration order.
+    #[inline]
+    pub fn set_sort_keys(&mut self, sort_keys: bool) {
+        self.sort_keys = sort_keys;
+    }
+
+    /// Sets the maximum nesting depth of lists and compounds.
+    ///
+    /// The root compound has a depth of 1. Serializing data that is nested deeper than this
+    /// returns [`NbtError::MaxDepthExceeded`], which protects against overflowing the stack
+    /// with deeply nested data. By default, the depth is not limited.
+    #[inline]
+    pub fn set_max_depth(&mut self, max_depth: Option<usize>) {
+        self.max_depth = max_depth;
+    }
+
+    /// Creates a serializer that writes values without the type and name of the root compound.
+    ///
+    /// This is used to write the payloads of tags whose header has already been written.
+    pub(crate) fn headless(w: W) -> Serializer<W, E> {
+        let mut ser = Serializer::new(w);
+        ser.is_initial = false;
+        ser
+    }
+
+    /// Creates a serializer for nested data that shares the settings of this serializer.
+    fn nested(&self) -> Serializer<Vec<u8>, E> {
+        let mut ser = Serializer::new(Vec::new());
+        ser.is_initial = false;
+        ser.sort_keys = self.sort_keys;
+        ser.depth = self.depth;
+        ser.max_depth = self.max_depth;
+        ser
+    }
+
+    /// Returns the type that the given list element is written as.
+    fn element_type<T>(element: &T) -> Result<FieldType, NbtError>
+    where
+        T: ?Sized + Serialize,
+    {
+        let mut ty = [0];
+        let mut probe = Serializer::<&mut [u8], E>::new(&mut ty);
+        if element.serialize(FieldTypeSerializer::new(&mut probe))? {
+            return Err(NbtError::Unsupported("Lists cannot contain `None` values"));
+        }
+
+        FieldType::try_from(ty[0])
+    }
+
+    /// Writes the type and length of a list before its first element, or makes sure that
+    /// the element has the same type as the first one otherwise.
+    fn write_element_type<T>(&mut self, element: &T) -> Result<(), NbtError>
+    where
+        T: ?Sized + Serialize,
+    {
+        if self.len != 0 {
+            let ty = Self::element_type(element)?;
+            self.writer.write_u8(ty as u8)?;
+            self.write_seq_len(self.len)?;
+            self.len = 0;
+
+            if let Some(list_type) = self.list_types.last_mut() {
+                *list_type = Some(ty);
+            }
+        } else if let Some(&Some(expected)) = self.list_types.last() {
+            let actual = Self::element_type(element)?;
+            if actual != expected {
+                return Err(NbtError::HeterogeneousList { expected, actual });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Counts an element of the current list or array, making sure that it does not have more
+    /// elements than its length prefix says.
+    fn count_element<T>(&mut self, element: &T) -> Result<(), NbtError>
+    where
+        T: ?Sized + Serialize,
+    {
+        let Some(seq) = self.seqs.last_mut() else {
+            return Ok(());
+        };
+
+        if seq.written < seq.len {
+            seq.written += 1;
+            return Ok(());
+        }
+
+        let len = seq.len;
+        let ty = match self.seq_type() {
+            Some(ty) => ty,
+            None => Self::element_type(element)?,
+        };
+        Err(NbtError::LengthMismatch {
+            expected: len,
+            actual: len + 1,
+            ty,
+        })
+    }
+
+    /// Finishes the current list or array, making sure that all elements in its length prefix have been written.
+    fn end_seq(&mut self) -> Result<(), NbtError> {
+        let ty = self.seq_type().unwrap_or(FieldType::End);
+        self.list_types.pop();
+        self.depth -= 1;
+
+        match self.seqs.pop() {
+            Some(seq) if seq.written != seq.len => Err(NbtError::LengthMismatch {
+                expected: seq.len,
+                actual: seq.written,
+                ty,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the element type of the current list or array, if it is known.
+    fn seq_type(&self) -> Option<FieldType> {
+        self.seqs.last().and_then(|seq| seq.array_type).or(self
+            .list_types
+            .last()
+            .copied()
+            .flatten())
+    }
+
+    /// Writes the length prefix of a list or array.
+    #[inline]
+    fn write_seq_len(&mut self, len: usize) -> Result<(), NbtError> {
+        match E::AS_ENUM {
+            Variant::BigEndian => self.writer.write_i32::<BigEndian>(len as i32),
+            Variant::LittleEndian => self.writer.write_i32::<LittleEndian>(len as i32),
+            Variant::NetworkEndian => self.writer.write_i32_varint(len as i32),
+        }?;
+
+        Ok(())
+    }
+
+    /// Writes the header of an empty list.
+    ///
+    /// The header of a list is normally written together with its first element, since the element type
+    /// is only known at that point. Empty lists do not have any elements to infer the type from,
+    /// so they are written with the end tag as element type, just like Minecraft does.
+    #[inline]
+    fn write_empty_list(&mut self) -> Result<(), NbtError> {
+        self.writer.write_u8(FieldType::End as u8)?;
+        self.write_seq_len(0)
+    }
+
+    /// Enters a list or compound, making sure that the maximum depth is not exceeded.
+    fn enter(&mut self) -> Result<(), NbtError> {
+        if let Some(max) = self.max_depth {
+            if self.depth >= max {
+                return Err(NbtError::MaxDepthExceeded { max });
+            }
+        }
+
+        self.depth += 1;
+        Ok(())
+    }
+
+    /// Sets the name of the root compound.
+    ///
+    /// By default, maps are written with an empty root name and structs use their
+    /// (possibly renamed) struct name. The name is used for every value written using
+    /// [`serialize_value`](Self::serialize_value).
+    #[inline]
+    pub fn set_root_name(&mut self, name: impl Into<String>) {
+        self.root_name = Some(name.into());
+    }
+
+    /// Serializes the given data as a complete root compound, appending it to the writer.
+    ///
+    /// This can be called multiple times to write several values into the same writer, all of them using
+    /// the settings of this serializer, such as [`set_sort_keys`](Self::set_sort_keys). Values written this way
+    /// can be read back using [`from_bytes_iter`](crate::from_bytes_iter). Serializing data through
+    /// `&mut Serializer` directly only writes the root header for the first value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use nbtx::{nbt, BigEndian, Serializer, Value};
+    /// # fn main() {
+    ///  let mut ser = Serializer::<_, BigEndian>::new(Vec::new());
+    ///  ser.set_sort_keys(true);
+    ///
+    ///  ser.serialize_value(&nbt!({ "id": 1i32 })).unwrap();
+    ///  ser.serialize_value(&nbt!({ "id": 2i32 })).unwrap();
+    ///
+    ///  let buffer = ser.into_inner();
+    ///  let values: Vec<Value> = nbtx::from_bytes_iter::<BigEndian, _, _>(buffer.as_slice())
+    ///     .collect::<Result<_, _>>()
+    ///     .unwrap();
+    ///  assert_eq!(values, [nbt!({ "id": 1i32 }), nbt!({ "id": 2i32 })]);
+    /// # }
+    /// ```
+    pub fn serialize_value<T>(&mut self, v: &T) -> Result<(), NbtError>
+    where
+        T: ?Sized + Serialize,
+    {
+        // Reset the state of the previous value, which might not have been finished if it failed.
+        self.is_initial = true;
+        self.entries.clear();
+        self.len = 0;
+        self.list_types.clear();
+        self.seqs.clear();
+        self.array_type = None;
+        self.depth = 0;
+
+        v.serialize(self)
+    }
+
     /// Consumes the serialiser and returns the inner writer.
     #[inline]
     pub fn into_inner(self) -> W {
@@ -324,13 +745,13 @@ where
 
     type SerializeSeq = Self;
     type SerializeTuple = Self;
-    type SerializeTupleStruct = Impossible<(), NbtError>;
+    type SerializeTupleStruct = Self;
     type SerializeTupleVariant = Impossible<(), NbtError>;
     type SerializeMap = Self;
     type SerializeStruct = Self;
     type SerializeStructVariant = Impossible<(), NbtError>;
 
-    forward_unsupported!(char, u8, u16, u32, u64, i128);
+    forward_unsupported!(i128, u128);
 
     #[inline]
     fn serialize_bool(self, v: bool) -> Result<(), NbtError> {
@@ -378,6 +799,27 @@ where
         Ok(())
     }
 
+    /// Unsigned integers are written as the signed tag of the same size, reinterpreting the bits.
+    #[inline]
+    fn serialize_u8(self, v: u8) -> Result<(), NbtError> {
+        self.serialize_i8(v as i8)
+    }
+
+    #[inline]
+    fn serialize_u16(self, v: u16) -> Result<(), NbtError> {
+        self.serialize_i16(v as i16)
+    }
+
+    #[inline]
+    fn serialize_u32(self, v: u32) -> Result<(), NbtError> {
+        self.serialize_i32(v as i32)
+    }
+
+    #[inline]
+    fn serialize_u64(self, v: u64) -> Result<(), NbtError> {
+        self.serialize_i64(v as i64)
+    }
+
     #[inline]
     fn serialize_f32(self, v: f32) -> Result<(), NbtError> {
         match E::AS_ENUM {
@@ -402,6 +844,12 @@ where
         Ok(())
     }
 
+    /// Characters are written as a string containing a single character.
+    #[inline]
+    fn serialize_char(self, v: char) -> Result<(), NbtError> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+
     #[inline]
     fn serialize_str(self, v: &str) -> Result<(), NbtError> {
         match E::AS_ENUM {
@@ -416,11 +864,7 @@ where
 
     #[inline]
     fn serialize_bytes(self, v: &[u8]) -> Result<(), NbtError> {
-        match E::AS_ENUM {
-            Variant::BigEndian => self.writer.write_i32::<BigEndian>(v.len() as i32),
-            Variant::LittleEndian => self.writer.write_i32::<LittleEndian>(v.len() as i32),
-            Variant::NetworkEndian => self.writer.write_i32_varint(v.len() as i32),
-        }?;
+        self.write_seq_len(v.len())?;
 
         self.writer.write_all(v)?;
         Ok(())
@@ -432,20 +876,22 @@ where
         ))
     }
 
-    fn serialize_some<T: Serialize + ?Sized>(self, _value: &T) -> Result<(), NbtError> {
-        Err(NbtError::Unsupported(
-            "Serializing Options is not supported",
-        ))
+    /// Options are only supported as struct fields, where `None` fields are skipped.
+    #[inline]
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), NbtError> {
+        value.serialize(self)
     }
 
+    /// Units are written as empty compounds.
+    #[inline]
     fn serialize_unit(self) -> Result<(), NbtError> {
-        Err(NbtError::Unsupported("Serializing () is not supported"))
+        SerializeMap::end(self.serialize_map(Some(0))?)
     }
 
-    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), NbtError> {
-        Err(NbtError::Unsupported(
-            "Serializing unit structs is not supported",
-        ))
+    /// Unit structs are written as empty compounds.
+    #[inline]
+    fn serialize_unit_struct(self, name: &'static str) -> Result<(), NbtError> {
+        SerializeStruct::end(self.serialize_struct(name, 0)?)
     }
 
     fn serialize_unit_variant(
@@ -461,9 +907,18 @@ where
 
     fn serialize_newtype_struct<T: Serialize + ?Sized>(
         self,
-        _name: &'static str,
-        _value: &T,
+        name: &'static str,
+        value: &T,
     ) -> Result<(), NbtError> {
+        if name == INT_ARRAY_TOKEN || name == LONG_ARRAY_TOKEN {
+            self.array_type = Some(if name == INT_ARRAY_TOKEN {
+                FieldType::Int
+            } else {
+                FieldType::Long
+            });
+            return value.serialize(self);
+        }
+
         Err(NbtError::Unsupported(
             "Serializing newtype structs is not supported",
         ))
@@ -484,6 +939,21 @@ where
     #[inline]
     fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
         if let Some(len) = len {
+            if self.array_type.is_some() {
+                return self.serialize_tuple(len);
+            }
+
+            self.enter()?;
+            if len == 0 {
+                self.write_empty_list()?;
+            }
+
+            self.list_types.push(None);
+            self.seqs.push(SeqState {
+                len,
+                written: 0,
+                array_type: None,
+            });
             self.len = len;
             Ok(self)
         } else {
@@ -493,18 +963,46 @@ where
 
     #[inline]
     fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        if let Some(ty) = self.array_type.take() {
+            // Arrays only have a length prefix, the elements are written directly.
+            self.write_seq_len(len)?;
+
+            // Arrays cannot contain nested data, so they do not count towards the maximum depth.
+            // The depth is still increased, as it is decreased again at the end of the tuple.
+            self.depth += 1;
+            self.list_types.push(None);
+            self.seqs.push(SeqState {
+                len,
+                written: 0,
+                array_type: Some(ty),
+            });
+            self.len = 0;
+            return Ok(self);
+        }
+
+        self.enter()?;
+        if len == 0 {
+            self.write_empty_list()?;
+        }
+
+        self.list_types.push(None);
+        self.seqs.push(SeqState {
+            len,
+            written: 0,
+            array_type: None,
+        });
         self.len = len;
         Ok(self)
     }
 
+    /// Tuple structs are written as lists, just like tuples.
+    #[inline]
     fn serialize_tuple_struct(
         self,
         _name: &'static str,
-        _len: usize,
+        len: usize,
     ) -> Result<Self::SerializeTupleStruct, Self::Error> {
-        Err(NbtError::Unsupported(
-            "Serializing tuple structs is not supported",
-        ))
+        self.serialize_tuple(len)
     }
 
     fn serialize_tuple_variant(
@@ -523,25 +1021,35 @@ where
         // nbt::Value does not distinguish between maps and structs.
         // Therefore, this is also necessary here
         if self.is_initial {
+            let name = self.root_name.clone().unwrap_or_default();
+
             self.writer.write_u8(FieldType::Compound as u8)?;
-            self.serialize_str("")?;
+            self.serialize_str(&name)?;
             self.is_initial = false;
         }
 
+        self.enter()?;
+        if self.sort_keys {
+            self.entries.push(Vec::new());
+        }
+
         Ok(self)
     }
 
     fn serialize_struct(
         self,
-        name: &'static str,
+        name_hint: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeStruct, Self::Error> {
         if self.is_initial {
+            let name = self.root_name.clone();
+
             self.writer.write_u8(FieldType::Compound as u8)?;
-            self.serialize_str(name)?;
+            self.serialize_str(name.as_deref().unwrap_or(name_hint))?;
             self.is_initial = false;
         }
 
+        self.enter()?;
         Ok(self)
     }
 
@@ -571,24 +1079,14 @@ where
     where
         T: ?Sized + Serialize,
     {
-        if self.len != 0 {
-            let ty_serializer = FieldTypeSerializer::new(self);
-            element.serialize(ty_serializer)?;
-
-            match F::AS_ENUM {
-                Variant::BigEndian => self.writer.write_i32::<BigEndian>(self.len as i32),
-                Variant::LittleEndian => self.writer.write_i32::<LittleEndian>(self.len as i32),
-                Variant::NetworkEndian => self.writer.write_i32_varint(self.len as i32),
-            }?;
-            self.len = 0;
-        }
-
+        self.count_element(element)?;
+        self.write_element_type(element)?;
         element.serialize(&mut **self)
     }
 
     #[inline]
     fn end(self) -> Result<(), NbtError> {
-        Ok(())
+        self.end_seq()
     }
 }
 
@@ -605,24 +1103,36 @@ where
     where
         T: ?Sized + Serialize,
     {
-        if self.len != 0 {
-            let ty_serializer = FieldTypeSerializer::new(self);
-            element.serialize(ty_serializer)?;
+        self.count_element(element)?;
+        self.write_element_type(element)?;
+        element.serialize(&mut **self)
+    }
 
-            match M::AS_ENUM {
-                Variant::BigEndian => self.writer.write_i32::<BigEndian>(self.len as i32),
-                Variant::LittleEndian => self.writer.write_i32::<LittleEndian>(self.len as i32),
-                Variant::NetworkEndian => self.writer.write_i32_varint(self.len as i32),
-            }?;
-            self.len = 0;
-        }
+    #[inline]
+    fn end(self) -> Result<(), NbtError> {
+        self.end_seq()
+    }
+}
 
-        element.serialize(&mut **self)
+impl<W, M> SerializeTupleStruct for &mut Serializer<W, M>
+where
+    W: WriteBytesExt,
+    M: EndiannessImpl,
+{
+    type Ok = ();
+    type Error = NbtError;
+
+    #[inline]
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), NbtError>
+    where
+        T: ?Sized + Serialize,
+    {
+        SerializeTuple::serialize_element(self, value)
     }
 
     #[inline]
     fn end(self) -> Result<(), NbtError> {
-        Ok(())
+        SerializeTuple::end(self)
     }
 }
 
@@ -659,6 +1169,25 @@ where
         K: ?Sized + Serialize,
         V: ?Sized + Serialize,
     {
+        if self.sort_keys {
+            let mut entry = self.nested();
+
+            let ty_serializer = FieldTypeSerializer::new(&mut entry);
+            value.serialize(ty_serializer)?;
+
+            key.serialize(&mut entry)?;
+            value.serialize(&mut entry)?;
+
+            let entry = entry.into_inner();
+            let key = entry_key::<M>(&entry)?.to_vec();
+
+            if let Some(entries) = self.entries.last_mut() {
+                entries.push((key, entry));
+            }
+
+            return Ok(());
+        }
+
         let ty_serializer = FieldTypeSerializer::new(self);
         value.serialize(ty_serializer)?;
 
@@ -668,11 +1197,38 @@ where
 
     #[inline]
     fn end(self) -> Result<(), NbtError> {
+        if self.sort_keys {
+            let mut entries = self.entries.pop().unwrap_or_default();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+            for (_, entry) in entries {
+                self.writer.write_all(&entry)?;
+            }
+        }
+
         self.writer.write_u8(FieldType::End as u8)?;
+        self.depth -= 1;
         Ok(())
     }
 }
 
+/// Returns the raw key of an encoded compound entry.
+///
+/// The entry starts with the type of the value, followed by the length-prefixed key.
+fn entry_key<E>(entry: &[u8]) -> Result<&[u8], NbtError>
+where
+    E: EndiannessImpl,
+{
+    let mut input = &entry[1..];
+    let len = match E::AS_ENUM {
+        Variant::BigEndian => input.read_u16::<BigEndian>()? as usize,
+        Variant::LittleEndian => input.read_u16::<LittleEndian>()? as usize,
+        Variant::NetworkEndian => input.read_u32_varint()? as usize,
+    };
+
+    Ok(&input[..len])
+}
+
 impl<W, M> SerializeStruct for &mut Serializer<W, M>
 where
     W: WriteBytesExt,
@@ -705,6 +1261,7 @@ where
     #[inline]
     fn end(self) -> Result<(), NbtError> {
         self.writer.write_u8(FieldType::End as u8)?;
+        self.depth -= 1;
         Ok(())
     }
 }
@@ -741,13 +1298,13 @@ where
     type Error = NbtError;
     type SerializeSeq = Self;
     type SerializeTuple = Self;
-    type SerializeTupleStruct = Impossible<bool, Self::Error>;
+    type SerializeTupleStruct = Self;
     type SerializeTupleVariant = Impossible<bool, Self::Error>;
     type SerializeMap = Self;
     type SerializeStruct = Self;
     type SerializeStructVariant = Impossible<bool, Self::Error>;
 
-    forward_unsupported_field!(char, u8, u16, u32, u64, i128);
+    forward_unsupported_field!(i128, u128);
 
     #[inline]
     fn serialize_bool(self, _v: bool) -> Result<bool, Self::Error> {
@@ -777,6 +1334,22 @@ where
         Ok(false)
     }
 
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
+        self.serialize_i8(v as i8)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
+        self.serialize_i16(v as i16)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
+        self.serialize_i32(v as i32)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
+        self.serialize_i64(v as i64)
+    }
+
     fn serialize_f32(self, _v: f32) -> Result<Self::Ok, Self::Error> {
         self.ser.writer.write_u8(FieldType::Float as u8)?;
         Ok(false)
@@ -787,6 +1360,11 @@ where
         Ok(false)
     }
 
+    fn serialize_char(self, _v: char) -> Result<Self::Ok, Self::Error> {
+        self.ser.writer.write_u8(FieldType::String as u8)?;
+        Ok(false)
+    }
+
     fn serialize_str(self, _v: &str) -> Result<Self::Ok, Self::Error> {
         self.ser.writer.write_u8(FieldType::String as u8)?;
         Ok(false)
@@ -807,13 +1385,13 @@ where
     }
 
     fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
-        Err(NbtError::Unsupported("Serializing () is not supported"))
+        self.ser.writer.write_u8(FieldType::Compound as u8)?;
+        Ok(false)
     }
 
     fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
-        Err(NbtError::Unsupported(
-            "Serializing unit structs is not supported",
-        ))
+        self.ser.writer.write_u8(FieldType::Compound as u8)?;
+        Ok(false)
     }
 
     fn serialize_unit_variant(
@@ -829,9 +1407,17 @@ where
 
     fn serialize_newtype_struct<T: Serialize + ?Sized>(
         self,
-        _name: &'static str,
+        name: &'static str,
         _value: &T,
     ) -> Result<Self::Ok, Self::Error> {
+        if name == INT_ARRAY_TOKEN {
+            self.ser.writer.write_u8(FieldType::IntArray as u8)?;
+            return Ok(false);
+        } else if name == LONG_ARRAY_TOKEN {
+            self.ser.writer.write_u8(FieldType::LongArray as u8)?;
+            return Ok(false);
+        }
+
         Err(NbtError::Unsupported(
             "Serializing newtype structs is not supported",
         ))
@@ -864,9 +1450,8 @@ where
         _name: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeTupleStruct, Self::Error> {
-        Err(NbtError::Unsupported(
-            "Serializing tuple structs is not supported",
-        ))
+        self.ser.writer.write_u8(FieldType::List as u8)?;
+        Ok(self)
     }
 
     fn serialize_tuple_variant(
@@ -954,6 +1539,28 @@ where
     }
 }
 
+impl<W, F> SerializeTupleStruct for FieldTypeSerializer<'_, W, F>
+where
+    W: WriteBytesExt,
+    F: EndiannessImpl,
+{
+    type Ok = bool;
+    type Error = NbtError;
+
+    #[inline]
+    fn serialize_field<T>(&mut self, _value: &T) -> Result<(), NbtError>
+    where
+        T: ?Sized + Serialize,
+    {
+        Ok(())
+    }
+
+    #[inline]
+    fn end(self) -> Result<bool, Self::Error> {
+        Ok(false)
+    }
+}
+
 impl<W, F> SerializeMap for FieldTypeSerializer<'_, W, F>
 where
     W: WriteBytesExt,
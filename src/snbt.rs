@@ -0,0 +1,490 @@
+//! Stringified NBT (SNBT), the textual format used by Minecraft commands.
+
+use alloc::borrow::{Cow, ToOwned};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+use core::str::FromStr;
+
+use crate::{Map, NbtError, Value};
+
+/// Converts the given value to stringified NBT (SNBT).
+///
+/// The output is compact and does not contain any whitespace.
+/// Compound entries are written in the iteration order of [`Map`](crate::Map).
+///
+/// This produces the same output as the [`Display`](fmt::Display) implementation of [`Value`].
+/// Floats and doubles that are NaN or infinite are written like Minecraft: Java Edition does,
+/// see [`NonFiniteFloats`].
+///
+/// # Example
+///
+/// ```rust
+/// # use nbtx::nbt;
+/// let value = nbt!({ "health": 20i16, "pos": [I; 1, 64, -3] });
+/// let snbt = nbtx::to_snbt(&value);
+/// # let _ = snbt;
+///
+/// let value = nbt!({ "name": "Steve" });
+/// assert_eq!(nbtx::to_snbt(&value), r#"{name:"Steve"}"#);
+/// ```
+pub fn to_snbt(value: &Value) -> String {
+    value.to_string()
+}
+
+/// How [`to_snbt_with_options`] handles floats and doubles that are NaN or infinite.
+///
+/// SNBT has no syntax for these values. Minecraft: Java Edition writes them anyway, as `NaNf`,
+/// `Infinityd` or `-Infinityf`, which cannot be parsed back as numbers. [`from_snbt`] parses such tokens
+/// as strings, so a round trip silently changes the type of the value.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum NonFiniteFloats {
+    /// Writes the values the same way as Minecraft: Java Edition.
+    ///
+    /// This is the behaviour of [`to_snbt`] and the [`Display`](fmt::Display) implementation of [`Value`].
+    #[default]
+    Write,
+    /// Returns an error instead.
+    Error,
+}
+
+/// Options for [`to_snbt_with_options`].
+///
+/// The default options are the same as those used by [`to_snbt`].
+#[derive(Debug, Clone, Default)]
+pub struct SnbtOptions {
+    /// How floats and doubles that are NaN or infinite are written.
+    pub non_finite_floats: NonFiniteFloats,
+}
+
+/// Converts the given value to stringified NBT (SNBT), using the given options.
+///
+/// # Example
+///
+/// ```rust
+/// # use nbtx::{nbt, NonFiniteFloats, SnbtOptions};
+/// let value = nbt!({ "motion": [f64::NAN, 0.0f64, 1.0f64] });
+/// assert_eq!(nbtx::to_snbt(&value), "{motion:[NaNd,0d,1d]}");
+///
+/// let options = SnbtOptions { non_finite_floats: NonFiniteFloats::Error };
+/// assert!(nbtx::to_snbt_with_options(&value, &options).is_err());
+/// ```
+pub fn to_snbt_with_options(value: &Value, options: &SnbtOptions) -> Result<String, NbtError> {
+    let mut out = String::new();
+
+    // Writing to a string cannot fail, so an error means a value could not be represented.
+    write_value(&mut out, value, options.non_finite_floats).map_err(|_| {
+        NbtError::Unsupported("SNBT cannot represent floats or doubles that are NaN or infinite")
+    })?;
+
+    Ok(out)
+}
+
+/// Parses a value from stringified NBT (SNBT).
+///
+/// This accepts the format produced by [`to_snbt`] as well as the looser format used in commands,
+/// such as whitespace between tokens, single-quoted strings and `true`/`false` (parsed as bytes).
+/// Unquoted tokens that are not a valid number are parsed as strings.
+///
+/// This is also available through the [`FromStr`] implementation of [`Value`].
+///
+/// Lists and compounds can be nested at most 512 levels deep, like in Minecraft: Java Edition.
+/// Deeper input returns [`NbtError::MaxDepthExceeded`].
+///
+/// # Example
+///
+/// ```rust
+/// # use nbtx::{nbt, Value};
+/// let value = nbtx::from_snbt(r#"{Name: "Steve", Health: 20s}"#).unwrap();
+/// assert_eq!(value, nbt!({ "Name": "Steve", "Health": 20i16 }));
+///
+/// let value: Value = "[I; 1, 2, 3]".parse().unwrap();
+/// assert_eq!(value, Value::IntArray(vec![1, 2, 3]));
+/// ```
+pub fn from_snbt(s: &str) -> Result<Value, NbtError> {
+    let mut parser = Parser {
+        input: s,
+        pos: 0,
+        depth: 0,
+    };
+
+    let value = parser.parse_value()?;
+    parser.skip_whitespace();
+    if parser.pos != s.len() {
+        return Err(parser.error("Trailing characters after value"));
+    }
+
+    Ok(value)
+}
+
+/// Whether the given byte may appear in an unquoted string.
+fn is_unquoted_char(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || matches!(b, b'_' | b'-' | b'.' | b'+')
+}
+
+/// Whether the given string can be written without quotes.
+fn is_unquoted(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(is_unquoted_char)
+}
+
+/// Writes a quoted string, escaping it where necessary.
+///
+/// Double quotes are used, unless the string contains double quotes but no single quotes.
+fn write_quoted(f: &mut dyn Write, s: &str) -> fmt::Result {
+    let quote = if s.contains('"') && !s.contains('\'') {
+        '\''
+    } else {
+        '"'
+    };
+
+    f.write_char(quote)?;
+    for c in s.chars() {
+        if c == quote || c == '\\' {
+            f.write_char('\\')?;
+        }
+        f.write_char(c)?;
+    }
+    f.write_char(quote)
+}
+
+/// Writes the elements of a sequence, separated by commas.
+fn write_seq<T>(
+    f: &mut dyn Write,
+    prefix: &str,
+    seq: &[T],
+    mut element: impl FnMut(&mut dyn Write, &T) -> fmt::Result,
+) -> fmt::Result {
+    f.write_char('[')?;
+    f.write_str(prefix)?;
+    for (i, v) in seq.iter().enumerate() {
+        if i != 0 {
+            f.write_char(',')?;
+        }
+        element(f, v)?;
+    }
+    f.write_char(']')
+}
+
+/// Writes a float or double with the given suffix.
+fn write_float<T>(f: &mut dyn Write, v: T, suffix: char, non_finite: NonFiniteFloats) -> fmt::Result
+where
+    T: fmt::Display + Into<f64> + Copy,
+{
+    let wide: f64 = v.into();
+    if wide.is_finite() {
+        return write!(f, "{v}{suffix}");
+    }
+
+    match non_finite {
+        NonFiniteFloats::Write if wide.is_nan() => write!(f, "NaN{suffix}"),
+        NonFiniteFloats::Write if wide > 0.0 => write!(f, "Infinity{suffix}"),
+        NonFiniteFloats::Write => write!(f, "-Infinity{suffix}"),
+        NonFiniteFloats::Error => Err(fmt::Error),
+    }
+}
+
+/// Writes the given value as SNBT.
+fn write_value(f: &mut dyn Write, value: &Value, non_finite: NonFiniteFloats) -> fmt::Result {
+    match value {
+        Value::Byte(v) => write!(f, "{v}b"),
+        Value::Short(v) => write!(f, "{v}s"),
+        Value::Int(v) => write!(f, "{v}"),
+        Value::Long(v) => write!(f, "{v}L"),
+        Value::Float(v) => write_float(f, *v, 'f', non_finite),
+        Value::Double(v) => write_float(f, *v, 'd', non_finite),
+        Value::ByteArray(v) => write_seq(f, "B;", v, |f, v| write!(f, "{}b", *v as i8)),
+        Value::String(v) => write_quoted(f, v),
+        Value::List(v) => write_seq(f, "", v, |f, v| write_value(f, v, non_finite)),
+        Value::Compound(v) => {
+            f.write_char('{')?;
+            for (i, (k, v)) in v.iter().enumerate() {
+                if i != 0 {
+                    f.write_char(',')?;
+                }
+
+                if is_unquoted(k) {
+                    f.write_str(k)?;
+                } else {
+                    write_quoted(f, k)?;
+                }
+
+                f.write_char(':')?;
+                write_value(f, v, non_finite)?;
+            }
+            f.write_char('}')
+        }
+        Value::IntArray(v) => write_seq(f, "I;", v, |f, v| write!(f, "{v}")),
+        Value::LongArray(v) => write_seq(f, "L;", v, |f, v| write!(f, "{v}L")),
+    }
+}
+
+impl fmt::Display for Value {
+    /// Formats the value as compact SNBT, see [`to_snbt`].
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_value(f, self, NonFiniteFloats::default())
+    }
+}
+
+impl FromStr for Value {
+    type Err = NbtError;
+
+    /// Parses a value from SNBT, see [`from_snbt`].
+    fn from_str(s: &str) -> Result<Self, NbtError> {
+        from_snbt(s)
+    }
+}
+
+/// Maximum amount of lists and compounds that can be nested in SNBT.
+const MAX_DEPTH: usize = 512;
+
+/// Recursive descent parser for SNBT.
+struct Parser<'a> {
+    input: &'a str,
+    /// Current byte offset into the input.
+    pos: usize,
+    /// Amount of lists and compounds that are currently being parsed.
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, msg: &str) -> NbtError {
+        NbtError::Other(Cow::Owned(format!(
+            "Invalid SNBT at position {}: {msg}",
+            self.pos
+        )))
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|b| b.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    /// Skips whitespace and consumes the given byte if it is next.
+    fn eat(&mut self, expected: u8) -> bool {
+        self.skip_whitespace();
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, expected: u8) -> Result<(), NbtError> {
+        if self.eat(expected) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("Expected `{}`", expected as char)))
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Value, NbtError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'{') => self.nested(Self::parse_compound),
+            Some(b'[') => self.nested(Self::parse_list),
+            Some(b'"' | b'\'') => self.parse_quoted().map(Value::String),
+            Some(_) => {
+                let token = self.parse_unquoted()?;
+                Ok(parse_scalar(token).unwrap_or_else(|| Value::String(token.to_owned())))
+            }
+            None => Err(self.error("Expected a value")),
+        }
+    }
+
+    /// Parses a list or compound, making sure that the maximum depth is not exceeded.
+    fn nested(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<Value, NbtError>,
+    ) -> Result<Value, NbtError> {
+        if self.depth >= MAX_DEPTH {
+            return Err(NbtError::MaxDepthExceeded { max: MAX_DEPTH });
+        }
+
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn parse_unquoted(&mut self) -> Result<&str, NbtError> {
+        let start = self.pos;
+        while self.peek().is_some_and(is_unquoted_char) {
+            self.pos += 1;
+        }
+
+        if start == self.pos {
+            return Err(self.error("Expected a value"));
+        }
+
+        Ok(&self.input[start..self.pos])
+    }
+
+    fn parse_quoted(&mut self) -> Result<String, NbtError> {
+        let quote = self.input[self.pos..].chars().next();
+        self.pos += 1;
+
+        let mut string = String::new();
+        let mut chars = self.input[self.pos..].char_indices();
+        while let Some((i, c)) = chars.next() {
+            if Some(c) == quote {
+                self.pos += i + 1;
+                return Ok(string);
+            }
+
+            if c == '\\' {
+                match chars.next() {
+                    Some((_, c)) if c == '\\' || c == '"' || c == '\'' => string.push(c),
+                    _ => {
+                        self.pos += i;
+                        return Err(self.error("Invalid escape sequence"));
+                    }
+                }
+            } else {
+                string.push(c);
+            }
+        }
+
+        self.pos = self.input.len();
+        Err(self.error("Unterminated string"))
+    }
+
+    fn parse_compound(&mut self) -> Result<Value, NbtError> {
+        self.expect(b'{')?;
+
+        let mut map = Map::new();
+        if self.eat(b'}') {
+            return Ok(Value::Compound(map));
+        }
+
+        loop {
+            self.skip_whitespace();
+            let key = match self.peek() {
+                Some(b'"' | b'\'') => self.parse_quoted()?,
+                _ => self.parse_unquoted()?.to_owned(),
+            };
+
+            self.expect(b':')?;
+            let value = self.parse_value()?;
+            map.insert(key, value);
+
+            if self.eat(b'}') {
+                return Ok(Value::Compound(map));
+            }
+            self.expect(b',')?;
+        }
+    }
+
+    fn parse_list(&mut self) -> Result<Value, NbtError> {
+        self.expect(b'[')?;
+
+        // Typed arrays start with a type prefix such as `B;`.
+        let bytes = self.input.as_bytes();
+        if let (Some(&prefix), Some(b';')) = (bytes.get(self.pos), bytes.get(self.pos + 1)) {
+            if matches!(prefix, b'B' | b'I' | b'L') {
+                self.pos += 2;
+                return match prefix {
+                    b'B' => self
+                        .parse_array(|v| i8::try_from(v).ok().map(|v| v as u8), Value::ByteArray),
+                    b'I' => self.parse_array(|v| i32::try_from(v).ok(), Value::IntArray),
+                    _ => self.parse_array(Some, Value::LongArray),
+                };
+            }
+        }
+
+        let mut list = Vec::new();
+        if self.eat(b']') {
+            return Ok(Value::List(list));
+        }
+
+        loop {
+            list.push(self.parse_value()?);
+
+            if self.eat(b']') {
+                return Ok(Value::List(list));
+            }
+            self.expect(b',')?;
+        }
+    }
+
+    /// Parses the elements of a typed array, after the type prefix.
+    fn parse_array<T>(
+        &mut self,
+        convert: impl Fn(i64) -> Option<T>,
+        variant: impl FnOnce(Vec<T>) -> Value,
+    ) -> Result<Value, NbtError> {
+        let mut array = Vec::new();
+        if self.eat(b']') {
+            return Ok(variant(array));
+        }
+
+        loop {
+            let element = match self.parse_value()? {
+                Value::Byte(v) => convert(v as i64),
+                Value::Short(v) => convert(v as i64),
+                Value::Int(v) => convert(v as i64),
+                Value::Long(v) => convert(v),
+                _ => None,
+            };
+
+            match element {
+                Some(element) => array.push(element),
+                None => return Err(self.error("Invalid element in typed array")),
+            }
+
+            if self.eat(b']') {
+                return Ok(variant(array));
+            }
+            self.expect(b',')?;
+        }
+    }
+}
+
+/// Whether the given string is a decimal integer with an optional sign.
+fn is_integer(s: &str) -> bool {
+    let digits = s.strip_prefix(['-', '+']).unwrap_or(s);
+    !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Whether the given string looks like a floating point number.
+///
+/// This rejects strings such as `inf` and `NaN` that Rust's float parser accepts.
+fn is_float(s: &str) -> bool {
+    s.bytes().any(|b| b.is_ascii_digit())
+        && s.bytes()
+            .all(|b| b.is_ascii_digit() || matches!(b, b'.' | b'e' | b'E' | b'-' | b'+'))
+}
+
+/// Parses an unquoted token as a number or boolean.
+///
+/// Returns `None` if the token should be interpreted as a string instead.
+fn parse_scalar(token: &str) -> Option<Value> {
+    match token {
+        "true" => return Some(Value::Byte(1)),
+        "false" => return Some(Value::Byte(0)),
+        _ => {}
+    }
+
+    if is_integer(token) {
+        return token.parse().ok().map(Value::Int);
+    }
+
+    let (body, suffix) = token.split_at(token.len() - 1);
+    match suffix {
+        "b" | "B" if is_integer(body) => body.parse().ok().map(Value::Byte),
+        "s" | "S" if is_integer(body) => body.parse().ok().map(Value::Short),
+        "l" | "L" if is_integer(body) => body.parse().ok().map(Value::Long),
+        "f" | "F" if is_float(body) => body.parse().ok().map(Value::Float),
+        "d" | "D" if is_float(body) => body.parse().ok().map(Value::Double),
+        _ if is_float(token) && token.contains(['.', 'e', 'E']) => {
+            token.parse().ok().map(Value::Double)
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,79 @@
+use crate::{FieldType, Value};
+
+/// Statistics about the contents of a value, as returned by [`Value::statistics`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NbtStats {
+    /// Amount of values of each type, indexed by tag ID.
+    counts: [usize; 13],
+    /// Total amount of values, including the value itself.
+    pub nodes: usize,
+    /// Maximum nesting depth of lists and compounds.
+    ///
+    /// This is the same depth as limited by [`SerializeOptions::max_depth`](crate::SerializeOptions::max_depth),
+    /// so a compound that only contains scalar values has a depth of 1.
+    pub max_depth: usize,
+    /// Length of the largest byte, int or long array.
+    pub largest_array: usize,
+}
+
+impl NbtStats {
+    /// Returns the amount of values of the given type.
+    ///
+    /// The elements of arrays are not counted separately, and [`FieldType::End`] is never counted.
+    #[inline]
+    pub fn count(&self, ty: FieldType) -> usize {
+        self.counts[ty.as_u8() as usize]
+    }
+}
+
+impl Value {
+    /// Collects statistics about this value and every value contained in it.
+    ///
+    /// Use [`serialized_len`](Self::serialized_len) to get the size of the value in bytes.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use nbtx::{nbt, FieldType};
+    /// let value = nbt!({
+    ///     "Sections": [{ "Y": 0i8, "BlockLight": [B; 0, 0, 0, 0] }],
+    ///     "Heightmap": [I; 64, 64]
+    /// });
+    ///
+    /// let stats = value.statistics();
+    /// assert_eq!(stats.count(FieldType::Compound), 2);
+    /// assert_eq!(stats.count(FieldType::ByteArray), 1);
+    /// assert_eq!(stats.nodes, 6);
+    /// assert_eq!(stats.max_depth, 3);
+    /// assert_eq!(stats.largest_array, 4);
+    /// ```
+    pub fn statistics(&self) -> NbtStats {
+        let mut stats = NbtStats::default();
+        collect(self, 0, &mut stats);
+        stats
+    }
+}
+
+fn collect(value: &Value, depth: usize, stats: &mut NbtStats) {
+    stats.counts[value.field_type().as_u8() as usize] += 1;
+    stats.nodes += 1;
+
+    match value {
+        Value::Compound(map) => {
+            stats.max_depth = stats.max_depth.max(depth + 1);
+            for value in map.values() {
+                collect(value, depth + 1, stats);
+            }
+        }
+        Value::List(list) => {
+            stats.max_depth = stats.max_depth.max(depth + 1);
+            for value in list {
+                collect(value, depth + 1, stats);
+            }
+        }
+        Value::ByteArray(array) => stats.largest_array = stats.largest_array.max(array.len()),
+        Value::IntArray(array) => stats.largest_array = stats.largest_array.max(array.len()),
+        Value::LongArray(array) => stats.largest_array = stats.largest_array.max(array.len()),
+        _ => {}
+    }
+}
@@ -0,0 +1,736 @@
+//! Event-based pull parser and push-based writer for NBT.
+//!
+//! Unlike the [`Deserializer`](crate::Deserializer), the [`Parser`] does not build any data
+//! structure. It yields one [`Event`] at a time and allows skipping entire subtrees without
+//! decoding them, which makes it possible to scan large files for a single tag.
+//!
+//! The [`Writer`] is its counterpart for writing: tags are written one at a time, so that large
+//! data can be generated without building a [`Value`] or other data structure for all of it first.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use byteorder::{BigEndian, LittleEndian};
+use serde::ser::Serializer as _;
+use serde::Serialize;
+
+use crate::de::{
+    read_bytes, read_f32, read_f64, read_i16, read_i32, read_i32_array, read_i64, read_i64_array,
+    read_seq_len, read_string, read_string_len, skip_compound, skip_value,
+};
+use crate::io::{Read, ReadBytesExt, WriteBytesExt};
+use crate::{EndiannessImpl, FieldType, NbtError, NetworkLittleEndian, Serializer, Value, Variant};
+
+/// An event produced by the [`Parser`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    /// Start of a named tag.
+    ///
+    /// This is produced for the root tag and every entry in a compound. It is followed by the
+    /// events of the tag's value. List elements are not named and therefore do not produce this event.
+    TagStart {
+        /// Name of the tag.
+        name: String,
+        /// Type of the tag.
+        ty: FieldType,
+    },
+    /// A value that is not a list or compound.
+    ///
+    /// This includes strings and the byte, int and long array types.
+    Primitive(Value),
+    /// Start of a list.
+    ///
+    /// This is followed by the events of `len` elements and a [`ListEnd`](Event::ListEnd).
+    ListStart {
+        /// Type of the elements in the list.
+        ty: FieldType,
+        /// Amount of elements in the list.
+        len: u32,
+    },
+    /// End of a list.
+    ListEnd,
+    /// Start of a compound.
+    ///
+    /// This is followed by the entries of the compound and a [`CompoundEnd`](Event::CompoundEnd).
+    CompoundStart,
+    /// End of a compound.
+    CompoundEnd,
+}
+
+/// Checks that the given data is well-formed NBT, without deserializing it.
+///
+/// This verifies that the root tag is a compound, that all tag types are valid, that lists and compounds
+/// are complete and that strings are valid UTF-8. The first problem that is found is returned.
+/// Scalars and arrays are skipped without being decoded, which makes this faster and use less memory
+/// than deserializing into a [`Value`] and discarding it.
+///
+/// The nesting depth is not limited, see [`validate_with_max_depth`] to limit it.
+///
+/// # Example
+///
+/// ```rust
+/// # use nbtx::{nbt, BigEndian};
+/// let buffer = nbtx::to_be_bytes(&nbt!({ "list": [{ "a": 1i32 }] })).unwrap();
+/// assert!(nbtx::validate::<BigEndian>(&mut buffer.as_slice()).is_ok());
+///
+/// // Truncated data is invalid.
+/// assert!(nbtx::validate::<BigEndian>(&mut &buffer[..buffer.len() - 1]).is_err());
+/// ```
+pub fn validate<F>(reader: &mut impl Read) -> Result<(), NbtError>
+where
+    F: EndiannessImpl,
+{
+    validate_inner::<F>(reader, None)
+}
+
+/// Checks that the given data is well-formed NBT and nested at most `max_depth` lists and compounds deep.
+///
+/// The root compound has a depth of 1. See [`validate`] for what is checked.
+pub fn validate_with_max_depth<F>(reader: &mut impl Read, max_depth: usize) -> Result<(), NbtError>
+where
+    F: EndiannessImpl,
+{
+    validate_inner::<F>(reader, Some(max_depth))
+}
+
+/// Guesses the format variant of the given data.
+///
+/// The data is validated as [`BigEndian`], [`LittleEndian`] and [`NetworkLittleEndian`], in that order,
+/// and the first variant in which it is a single well-formed root compound without trailing data is returned.
+/// `None` is returned if the data is not valid in any variant. See [`validate`] for what is checked.
+///
+/// This is a heuristic: data can be valid in more than one variant, in which case the first one is returned.
+/// This is likely for small inputs, for example an empty root compound with an empty name is encoded as the
+/// same four bytes in big endian and little endian, so it is always detected as big endian. Larger inputs are
+/// rarely valid in more than one variant.
+///
+/// # Example
+///
+/// ```rust
+/// # use nbtx::{nbt, Variant};
+/// let value = nbt!({ "name": "Steve", "health": 20i32 });
+///
+/// let buffer = nbtx::to_le_bytes(&value).unwrap();
+/// assert_eq!(nbtx::detect_variant(&buffer), Some(Variant::LittleEndian));
+///
+/// let buffer = nbtx::to_net_bytes(&value).unwrap();
+/// assert_eq!(nbtx::detect_variant(&buffer), Some(Variant::NetworkEndian));
+///
+/// assert_eq!(nbtx::detect_variant(&[1, 2, 3]), None);
+/// ```
+pub fn detect_variant(data: &[u8]) -> Option<Variant> {
+    fn is_valid<F: EndiannessImpl>(mut data: &[u8]) -> bool {
+        validate::<F>(&mut data).is_ok() && data.is_empty()
+    }
+
+    if is_valid::<BigEndian>(data) {
+        Some(Variant::BigEndian)
+    } else if is_valid::<LittleEndian>(data) {
+        Some(Variant::LittleEndian)
+    } else if is_valid::<NetworkLittleEndian>(data) {
+        Some(Variant::NetworkEndian)
+    } else {
+        None
+    }
+}
+
+/// Checks whether the start of the given data is consistent with the given variant.
+///
+/// Only the header of the root compound and of its first entry are inspected: the type of the root,
+/// the length and UTF-8 encoding of its name, the type of the first entry and the length and encoding of
+/// the name of that entry. If that entry is a list, its element type and length are inspected as well.
+/// The variants encode these lengths differently, so data in the wrong variant almost always fails this check,
+/// even though the rest of the data is not read. If the root compound is empty, nothing may follow it.
+///
+/// This is much cheaper than [`validate`], which makes it suitable for catching data that is passed to
+/// the wrong function, such as network data passed to [`from_le_bytes`](crate::from_le_bytes).
+/// Use [`detect_variant`] to find the variant of data instead.
+///
+/// # Example
+///
+/// ```rust
+/// # use nbtx::{nbt, Variant};
+/// let buffer = nbtx::to_net_bytes(&nbt!({ "name": "Steve" })).unwrap();
+///
+/// assert!(nbtx::looks_like(Variant::NetworkEndian, &buffer));
+/// assert!(!nbtx::looks_like(Variant::LittleEndian, &buffer));
+/// assert!(!nbtx::looks_like(Variant::BigEndian, &buffer));
+/// ```
+pub fn looks_like(variant: Variant, data: &[u8]) -> bool {
+    /// Skips a length-prefixed string, if it is complete and valid UTF-8.
+    fn skip_name<F: EndiannessImpl>(data: &mut &[u8]) -> Option<()> {
+        let len = read_string_len::<F>(data).ok()? as usize;
+        let name = data.get(..len)?;
+        core::str::from_utf8(name).ok()?;
+        *data = &data[len..];
+        Some(())
+    }
+
+    fn check<F: EndiannessImpl>(mut data: &[u8]) -> Option<()> {
+        let (&ty, rest) = data.split_first()?;
+        if ty != FieldType::Compound as u8 {
+            return None;
+        }
+        data = rest;
+        skip_name::<F>(&mut data)?;
+
+        let (&ty, rest) = data.split_first()?;
+        data = rest;
+        match FieldType::try_from(ty).ok()? {
+            FieldType::End => data.is_empty().then_some(()),
+            FieldType::List => {
+                skip_name::<F>(&mut data)?;
+                let (&elem_ty, mut rest) = data.split_first()?;
+                let elem_ty = FieldType::try_from(elem_ty).ok()?;
+                let len = read_seq_len::<F>(&mut rest).ok()?;
+                // Lists of end tags are always empty.
+                (elem_ty != FieldType::End || len == 0).then_some(())
+            }
+            _ => skip_name::<F>(&mut data),
+        }
+    }
+
+    match variant {
+        Variant::BigEndian => check::<BigEndian>(data).is_some(),
+        Variant::LittleEndian => check::<LittleEndian>(data).is_some(),
+        Variant::NetworkEndian => check::<NetworkLittleEndian>(data).is_some(),
+    }
+}
+
+fn validate_inner<F>(reader: &mut impl Read, max_depth: Option<usize>) -> Result<(), NbtError>
+where
+    F: EndiannessImpl,
+{
+    // Strings are decoded to validate them, other values without nested tags are skipped.
+    let is_skippable = |ty| {
+        !matches!(
+            ty,
+            FieldType::String | FieldType::List | FieldType::Compound
+        )
+    };
+
+    // The list or compound that was just started is already included in the depth of the parser.
+    let check_depth = |parser: &Parser<_, F>| match max_depth {
+        Some(max) if parser.depth() > max => Err(NbtError::MaxDepthExceeded { max }),
+        _ => Ok(()),
+    };
+
+    let mut parser = Parser::<_, F>::new(reader);
+    match parser.next_event()? {
+        Some(Event::TagStart {
+            ty: FieldType::Compound,
+            ..
+        }) => {}
+        Some(Event::TagStart { ty, .. }) => {
+            return Err(NbtError::UnexpectedType {
+                expected: FieldType::Compound,
+                actual: ty,
+            })
+        }
+        _ => unreachable!("the root event is always a tag start"),
+    }
+
+    while let Some(event) = parser.next_event()? {
+        match event {
+            Event::TagStart { ty, .. } if is_skippable(ty) => parser.skip()?,
+            Event::ListStart { ty, .. } => {
+                check_depth(&parser)?;
+                if is_skippable(ty) {
+                    // This skips the elements of the list, the list end event is then not produced.
+                    parser.skip()?;
+                }
+            }
+            Event::CompoundStart => check_depth(&parser)?,
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// A container that is currently being parsed.
+#[derive(Debug)]
+enum Frame {
+    Compound,
+    List { ty: FieldType, remaining: u32 },
+}
+
+/// Event-based pull parser for NBT.
+///
+/// Events are obtained using [`next_event`](Self::next_event).
+///
+/// # Example
+///
+/// ```rust
+/// # use nbtx::{nbt, BigEndian, FieldType, Value};
+/// # use nbtx::stream::{Event, Parser};
+/// # fn main() {
+/// # let buffer = nbtx::to_be_bytes(&nbt!({ "Entities": [{ "id": "zombie" }], "Data": [I; 1, 2] })).unwrap();
+/// let mut parser = Parser::<_, BigEndian>::new(buffer.as_slice());
+///
+/// while let Some(event) = parser.next_event().unwrap() {
+///     if let Event::TagStart { name, .. } = event {
+///         if name == "Entities" {
+///             assert_eq!(parser.next_event().unwrap(), Some(Event::ListStart { ty: FieldType::Compound, len: 1 }));
+///             break;
+///         }
+///
+///         // Tags that are not named `Entities` are not decoded at all.
+///         parser.skip().unwrap();
+///     }
+/// }
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Parser<R, F>
+where
+    R: Read,
+    F: EndiannessImpl,
+{
+    reader: R,
+    stack: Vec<Frame>,
+    /// Type of the value that should be read next, if any.
+    pending: Option<FieldType>,
+    /// Whether the root tag has been read.
+    started: bool,
+    _marker: PhantomData<F>,
+}
+
+impl<R, F> Parser<R, F>
+where
+    R: Read,
+    F: EndiannessImpl,
+{
+    /// Creates a new parser that reads from the given reader.
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            stack: Vec::new(),
+            pending: None,
+            started: false,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the amount of lists and compounds that are currently open.
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Consumes the parser and returns the underlying reader.
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Reads the next event.
+    ///
+    /// Returns `None` once the root tag has been read completely.
+    pub fn next_event(&mut self) -> Result<Option<Event>, NbtError> {
+        loop {
+            if let Some(ty) = self.pending.take() {
+                return self.read_value(ty).map(Some);
+            }
+
+            match self.stack.last_mut() {
+                None if self.started => return Ok(None),
+                None => {
+                    self.started = true;
+                    return self.read_tag_start().map(Some);
+                }
+                Some(Frame::Compound) => {
+                    let ty = FieldType::try_from(self.reader.read_u8()?)?;
+                    if ty == FieldType::End {
+                        self.stack.pop();
+                        return Ok(Some(Event::CompoundEnd));
+                    }
+
+                    let name = read_string::<F>(&mut self.reader)?;
+                    self.pending = Some(ty);
+                    return Ok(Some(Event::TagStart { name, ty }));
+                }
+                Some(Frame::List { ty, remaining }) => {
+                    if *remaining == 0 {
+                        self.stack.pop();
+                        return Ok(Some(Event::ListEnd));
+                    }
+
+                    *remaining -= 1;
+                    self.pending = Some(*ty);
+                }
+            }
+        }
+    }
+
+    /// Skips the current subtree without decoding it.
+    ///
+    /// Directly after a [`TagStart`](Event::TagStart), this skips the value of the tag.
+    /// Otherwise, this skips the remainder of the innermost list or compound, including its end event.
+    /// The next event is then the one following the skipped subtree.
+    pub fn skip(&mut self) -> Result<(), NbtError> {
+        if let Some(ty) = self.pending.take() {
+            return skip_value::<F>(&mut self.reader, ty);
+        }
+
+        match self.stack.pop() {
+            Some(Frame::Compound) => skip_compound::<F>(&mut self.reader),
+            Some(Frame::List { ty, remaining }) => {
+                for _ in 0..remaining {
+                    skip_value::<F>(&mut self.reader, ty)?;
+                }
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    fn read_tag_start(&mut self) -> Result<Event, NbtError> {
+        let ty = FieldType::try_from(self.reader.read_u8()?)?;
+        if ty == FieldType::End {
+            return Err(NbtError::UnexpectedType {
+                expected: FieldType::Compound,
+                actual: ty,
+            });
+        }
+
+        let name = read_string::<F>(&mut self.reader)?;
+        self.pending = Some(ty);
+
+        Ok(Event::TagStart { name, ty })
+    }
+
+    fn read_value(&mut self, ty: FieldType) -> Result<Event, NbtError> {
+        let r = &mut self.reader;
+
+        let value = match ty {
+            FieldType::End => {
+                return Err(NbtError::Unsupported(
+                    "Lists of end tags cannot contain elements",
+                ))
+            }
+            FieldType::Compound => {
+                self.stack.push(Frame::Compound);
+                return Ok(Event::CompoundStart);
+            }
+            FieldType::List => {
+                let ty = FieldType::try_from(r.read_u8()?)?;
+                let len = read_seq_len::<F>(r)?;
+                if ty == FieldType::End && len != 0 {
+                    return Err(NbtError::UnmatchedEnd);
+                }
+
+                self.stack.push(Frame::List { ty, remaining: len });
+                return Ok(Event::ListStart { ty, len });
+            }
+            FieldType::Byte => Value::Byte(r.read_i8()?),
+            FieldType::Short => Value::Short(read_i16::<F>(r)?),
+            FieldType::Int => Value::Int(read_i32::<F>(r)?),
+            FieldType::Long => Value::Long(read_i64::<F>(r)?),
+            FieldType::Float => Value::Float(read_f32::<F>(r)?),
+            FieldType::Double => Value::Double(read_f64::<F>(r)?),
+            FieldType::String => Value::String(read_string::<F>(r)?),
+            FieldType::ByteArray => {
+                let len = read_seq_len::<F>(r)?;
+
+                Value::ByteArray(read_bytes(r, len as usize)?)
+            }
+            FieldType::IntArray => {
+                let len = read_seq_len::<F>(r)?;
+                Value::IntArray(read_i32_array::<F>(r, len)?)
+            }
+            FieldType::LongArray => {
+                let len = read_seq_len::<F>(r)?;
+                Value::LongArray(read_i64_array::<F>(r, len)?)
+            }
+        };
+
+        Ok(Event::Primitive(value))
+    }
+}
+
+/// A container that is currently being written.
+#[derive(Debug)]
+enum WriteFrame {
+    Compound,
+    List {
+        ty: FieldType,
+        len: u32,
+        remaining: u32,
+    },
+}
+
+/// Push-based NBT writer.
+///
+/// Compounds are written using [`begin_compound`](Self::begin_compound) and [`end_compound`](Self::end_compound),
+/// with their entries in between. Entries of compounds are named, elements of lists are not: list elements are
+/// written using the methods ending in `_element`.
+///
+/// The writer makes sure that the output is well-formed. The root tag must be a compound, lists must contain
+/// exactly the announced amount of elements of the announced type and every list and compound must be ended.
+/// Misuse returns an error, after which the output is incomplete and the writer should no longer be used.
+///
+/// # Example
+///
+/// ```rust
+/// # use nbtx::{nbt, BigEndian, FieldType, Value};
+/// # use nbtx::stream::Writer;
+/// # fn main() {
+/// let mut writer = Writer::<_, BigEndian>::new(Vec::new());
+///
+/// writer.begin_compound("").unwrap();
+/// writer.write_string("name", "Steve").unwrap();
+/// writer.begin_list("Inventory", FieldType::Compound, 2).unwrap();
+/// for slot in 0..2 {
+///     writer.begin_compound_element().unwrap();
+///     writer.write_byte("Slot", slot).unwrap();
+///     writer.end_compound().unwrap();
+/// }
+/// writer.end_list().unwrap();
+/// writer.end_compound().unwrap();
+///
+/// let buffer = writer.finish().unwrap();
+/// let value: Value = nbtx::from_be_bytes(&mut buffer.as_slice()).unwrap();
+/// assert_eq!(value, nbt!({ "name": "Steve", "Inventory": [{ "Slot": 0i8 }, { "Slot": 1i8 }] }));
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Writer<W, F>
+where
+    W: WriteBytesExt,
+    F: EndiannessImpl,
+{
+    ser: Serializer<W, F>,
+    stack: Vec<WriteFrame>,
+    /// Whether the root tag has been started.
+    started: bool,
+}
+
+impl<W, F> Writer<W, F>
+where
+    W: WriteBytesExt,
+    F: EndiannessImpl,
+{
+    /// Creates a new writer that writes to the given writer.
+    pub fn new(writer: W) -> Self {
+        Self {
+            ser: Serializer::headless(writer),
+            stack: Vec::new(),
+            started: false,
+        }
+    }
+
+    /// Returns the amount of lists and compounds that are currently open.
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Makes sure that the root compound has been ended and returns the underlying writer.
+    pub fn finish(self) -> Result<W, NbtError> {
+        if !self.started || !self.stack.is_empty() {
+            return Err(NbtError::Unsupported(
+                "The root compound has not been ended",
+            ));
+        }
+
+        Ok(self.ser.into_inner())
+    }
+
+    /// Starts a compound with the given name.
+    ///
+    /// This is also used to start the root compound. It must be ended using [`end_compound`](Self::end_compound).
+    pub fn begin_compound(&mut self, name: &str) -> Result<(), NbtError> {
+        self.write_tag_start(name, FieldType::Compound)?;
+        self.stack.push(WriteFrame::Compound);
+        Ok(())
+    }
+
+    /// Starts a compound as an element of the current list.
+    pub fn begin_compound_element(&mut self) -> Result<(), NbtError> {
+        self.write_element_start(FieldType::Compound)?;
+        self.stack.push(WriteFrame::Compound);
+        Ok(())
+    }
+
+    /// Ends the current compound.
+    pub fn end_compound(&mut self) -> Result<(), NbtError> {
+        match self.stack.last() {
+            Some(WriteFrame::Compound) => {
+                self.stack.pop();
+                self.ser.serialize_u8(FieldType::End.as_u8())
+            }
+            _ => Err(NbtError::Unsupported("There is no compound to end")),
+        }
+    }
+
+    /// Starts a list with the given name, which will contain `len` elements of type `ty`.
+    ///
+    /// The elements must then be written using the methods ending in `_element`,
+    /// after which the list must be ended using [`end_list`](Self::end_list).
+    pub fn begin_list(&mut self, name: &str, ty: FieldType, len: u32) -> Result<(), NbtError> {
+        self.write_tag_start(name, FieldType::List)?;
+        self.write_list_header(ty, len)
+    }
+
+    /// Starts a list as an element of the current list, which will contain `len` elements of type `ty`.
+    pub fn begin_list_element(&mut self, ty: FieldType, len: u32) -> Result<(), NbtError> {
+        self.write_element_start(FieldType::List)?;
+        self.write_list_header(ty, len)
+    }
+
+    /// Ends the current list, making sure that all of its elements have been written.
+    pub fn end_list(&mut self) -> Result<(), NbtError> {
+        match self.stack.last() {
+            Some(&WriteFrame::List { ty, len, remaining }) => {
+                if remaining != 0 {
+                    return Err(NbtError::LengthMismatch {
+                        expected: len as usize,
+                        actual: (len - remaining) as usize,
+                        ty,
+                    });
+                }
+
+                self.stack.pop();
+                Ok(())
+            }
+            _ => Err(NbtError::Unsupported("There is no list to end")),
+        }
+    }
+
+    /// Writes a tag with the given name and value.
+    ///
+    /// The value can be of any type, including lists and compounds, which are written as a whole.
+    pub fn write_value(&mut self, name: &str, value: &Value) -> Result<(), NbtError> {
+        self.write_tag_start(name, value.field_type())?;
+        value.serialize(&mut self.ser)
+    }
+
+    /// Writes a value as an element of the current list.
+    ///
+    /// The value must have the element type of the list.
+    pub fn write_list_element(&mut self, value: &Value) -> Result<(), NbtError> {
+        self.write_element_start(value.field_type())?;
+        value.serialize(&mut self.ser)
+    }
+
+    /// Writes a byte tag with the given name.
+    pub fn write_byte(&mut self, name: &str, v: i8) -> Result<(), NbtError> {
+        self.write_tag_start(name, FieldType::Byte)?;
+        self.ser.serialize_i8(v)
+    }
+
+    /// Writes a short tag with the given name.
+    pub fn write_short(&mut self, name: &str, v: i16) -> Result<(), NbtError> {
+        self.write_tag_start(name, FieldType::Short)?;
+        self.ser.serialize_i16(v)
+    }
+
+    /// Writes an int tag with the given name.
+    pub fn write_int(&mut self, name: &str, v: i32) -> Result<(), NbtError> {
+        self.write_tag_start(name, FieldType::Int)?;
+        self.ser.serialize_i32(v)
+    }
+
+    /// Writes a long tag with the given name.
+    pub fn write_long(&mut self, name: &str, v: i64) -> Result<(), NbtError> {
+        self.write_tag_start(name, FieldType::Long)?;
+        self.ser.serialize_i64(v)
+    }
+
+    /// Writes a float tag with the given name.
+    pub fn write_float(&mut self, name: &str, v: f32) -> Result<(), NbtError> {
+        self.write_tag_start(name, FieldType::Float)?;
+        self.ser.serialize_f32(v)
+    }
+
+    /// Writes a double tag with the given name.
+    pub fn write_double(&mut self, name: &str, v: f64) -> Result<(), NbtError> {
+        self.write_tag_start(name, FieldType::Double)?;
+        self.ser.serialize_f64(v)
+    }
+
+    /// Writes a string tag with the given name.
+    pub fn write_string(&mut self, name: &str, v: &str) -> Result<(), NbtError> {
+        self.write_tag_start(name, FieldType::String)?;
+        self.ser.serialize_str(v)
+    }
+
+    /// Writes the type and name of a tag, which must be the root compound or an entry of a compound.
+    fn write_tag_start(&mut self, name: &str, ty: FieldType) -> Result<(), NbtError> {
+        match self.stack.last() {
+            None if self.started => {
+                return Err(NbtError::Unsupported(
+                    "The root compound has already been written",
+                ))
+            }
+            None if ty != FieldType::Compound => {
+                return Err(NbtError::UnexpectedType {
+                    expected: FieldType::Compound,
+                    actual: ty,
+                })
+            }
+            None => self.started = true,
+            Some(WriteFrame::Compound) => {}
+            Some(WriteFrame::List { .. }) => {
+                return Err(NbtError::Unsupported(
+                    "List elements are not named, use the methods ending in `_element` instead",
+                ))
+            }
+        }
+
+        self.ser.serialize_u8(ty.as_u8())?;
+        self.ser.serialize_str(name)
+    }
+
+    /// Makes sure that an element of the given type can be written to the current list.
+    fn write_element_start(&mut self, ty: FieldType) -> Result<(), NbtError> {
+        match self.stack.last_mut() {
+            Some(WriteFrame::List {
+                ty: expected,
+                len,
+                remaining,
+            }) => {
+                if *expected != ty {
+                    return Err(NbtError::HeterogeneousList {
+                        expected: *expected,
+                        actual: ty,
+                    });
+                }
+                if *remaining == 0 {
+                    return Err(NbtError::LengthMismatch {
+                        expected: *len as usize,
+                        actual: *len as usize + 1,
+                        ty,
+                    });
+                }
+
+                *remaining -= 1;
+                Ok(())
+            }
+            _ => Err(NbtError::Unsupported(
+                "Only list elements can be written without a name",
+            )),
+        }
+    }
+
+    /// Writes the element type and length of a list, and opens it.
+    fn write_list_header(&mut self, ty: FieldType, len: u32) -> Result<(), NbtError> {
+        if ty == FieldType::End && len != 0 {
+            return Err(NbtError::Unsupported(
+                "Lists of end tags cannot contain elements",
+            ));
+        }
+
+        self.ser.serialize_u8(ty.as_u8())?;
+        self.ser.serialize_i32(len as i32)?;
+
+        self.stack.push(WriteFrame::List {
+            ty,
+            len,
+            remaining: len,
+        });
+        Ok(())
+    }
+}
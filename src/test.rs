@@ -1,15 +1,14 @@
 #![allow(const_item_mutation)] // We make use of constant mutation on purpose in this test.
 
-use std::collections::HashMap;
 use std::io::Cursor;
 
-use byteorder::BigEndian;
+use byteorder::{BigEndian, LittleEndian};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    from_be_bytes, from_le_bytes, from_net_bytes,
-    ser::{to_be_bytes, to_bytes, to_le_bytes, to_net_bytes},
-    NbtError, NetworkLittleEndian, Value,
+    from_be_bytes, from_bytes_named, from_le_bytes, from_net_bytes, from_snbt, nbt,
+    ser::{to_be_bytes, to_bytes, to_bytes_named, to_bytes_sorted, to_le_bytes, to_net_bytes},
+    to_snbt, FieldType, Map, NbtError, NetworkLittleEndian, Value,
 };
 
 const BIG_TEST_NBT: &[u8] = include_bytes!("../test/bigtest.nbt");
@@ -43,7 +42,7 @@ fn read_write_option() {
 
 #[test]
 fn read_write_all() {
-    let value = Value::Compound(HashMap::from([
+    let value = Value::Compound(Map::from([
         ("byte".to_owned(), Value::Byte(42)),
         ("short".to_owned(), Value::Short(42)),
         ("int".to_owned(), Value::Int(42)),
@@ -58,11 +57,11 @@ fn read_write_all() {
         (
             "list".to_owned(),
             Value::List(vec![
-                Value::Compound(HashMap::from([(
+                Value::Compound(Map::from([(
                     "name".to_owned(),
                     Value::String("Compound 1".to_owned()),
                 )])),
-                Value::Compound(HashMap::from([(
+                Value::Compound(Map::from([(
                     "name".to_owned(),
                     Value::String("Compound 2".to_owned()),
                 )])),
@@ -70,7 +69,7 @@ fn read_write_all() {
         ),
         (
             "compound".to_owned(),
-            Value::Compound(HashMap::from([(
+            Value::Compound(Map::from([(
                 "name".to_owned(),
                 Value::String("Compound 3".to_owned()),
             )])),
@@ -90,6 +89,7 @@ fn read_write_all() {
 }
 
 #[test]
+#[allow(clippy::useless_asref)]
 fn read_write_bigtest() {
     #[derive(Deserialize, Serialize, Debug, PartialEq)]
     struct Food {
@@ -210,3 +210,3622 @@ fn read_write_player() {
     let value_encoded = to_be_bytes(&decoded2).unwrap();
     let _value_decoded: Value = from_be_bytes(&mut value_encoded.as_slice()).unwrap();
 }
+
+#[cfg(feature = "gzip")]
+#[test]
+fn read_write_gzip() {
+    let value = nbt!({ "name": "Bananrama" });
+
+    let encoded = crate::to_gzip_be_bytes(&value).unwrap();
+    let decoded: Value = crate::from_gzip_be_bytes(&mut encoded.as_slice()).unwrap();
+    assert_eq!(value, decoded);
+
+    // Uncompressed data should be rejected.
+    let result: Result<Value, NbtError> = crate::from_gzip_be_bytes(&mut HELLO_WORLD_NBT);
+    assert!(result.is_err());
+}
+
+#[cfg(feature = "zlib")]
+#[test]
+fn read_write_zlib() {
+    let value = nbt!({ "name": "Bananrama" });
+
+    let encoded = crate::to_zlib_be_bytes(&value).unwrap();
+    let decoded: Value = crate::from_zlib_be_bytes(&mut encoded.as_slice()).unwrap();
+    assert_eq!(value, decoded);
+
+    let encoded = crate::to_deflate_bytes::<BigEndian>(&value).unwrap();
+    let decoded: Value =
+        crate::from_deflate_bytes::<BigEndian, _>(&mut encoded.as_slice()).unwrap();
+    assert_eq!(value, decoded);
+
+    let result: Result<Value, NbtError> = crate::from_zlib_be_bytes(&mut HELLO_WORLD_NBT);
+    assert!(result.is_err());
+}
+
+#[cfg(all(feature = "gzip", feature = "zlib"))]
+#[test]
+fn read_compressed() {
+    let value = nbt!({ "name": "Bananrama" });
+
+    let encodings = [
+        crate::to_gzip_be_bytes(&value).unwrap(),
+        crate::to_zlib_be_bytes(&value).unwrap(),
+        to_be_bytes(&value).unwrap(),
+    ];
+
+    for encoded in encodings {
+        let decoded: Value =
+            crate::from_compressed_bytes::<BigEndian, _>(&mut encoded.as_slice()).unwrap();
+        assert_eq!(value, decoded);
+    }
+}
+
+#[test]
+fn read_write_root_name() {
+    let (name, value) = from_bytes_named::<BigEndian, Value>(&mut HELLO_WORLD_NBT).unwrap();
+    assert_eq!(name, "hello world");
+
+    let encoded = to_bytes_named::<BigEndian>(&name, &value).unwrap();
+    assert_eq!(encoded.as_slice(), HELLO_WORLD_NBT);
+}
+
+#[cfg(feature = "preserve_order")]
+#[test]
+fn read_write_preserve_order() {
+    let (name, value) = from_bytes_named::<BigEndian, Value>(&mut BIG_TEST_NBT).unwrap();
+    let encoded = to_bytes_named::<BigEndian>(&name, &value).unwrap();
+    assert_eq!(encoded.as_slice(), BIG_TEST_NBT);
+}
+
+#[test]
+fn write_sorted_keys() {
+    let mut forward = Map::new();
+    let mut backward = Map::new();
+    for i in 0..32 {
+        forward.insert(format!("key{i}"), nbt!({ "a": i, "b": i }));
+        backward.insert(format!("key{}", 31 - i), nbt!({ "b": 31 - i, "a": 31 - i }));
+    }
+
+    let forward = to_bytes_sorted::<NetworkLittleEndian>(&Value::Compound(forward)).unwrap();
+    let backward = to_bytes_sorted::<NetworkLittleEndian>(&Value::Compound(backward)).unwrap();
+    assert_eq!(forward, backward);
+
+    let decoded: Value = from_net_bytes(&mut forward.as_slice()).unwrap();
+    assert_eq!(decoded.as_compound().unwrap().len(), 32);
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json_conversion() {
+    let value = nbt!({
+        "int": 1i32,
+        "double": 1.5f64,
+        "string": "Hello, World!",
+        "list": [{ "name": "Compound 1" }],
+        "bytes": [B; 1, 2, 255],
+        "ints": [I; 1, 2, 3],
+        "longs": [L; 1, 2, 3]
+    });
+
+    let json = serde_json::Value::from(value.clone());
+    assert_eq!(json["bytes"]["$byte_array"][2], -1);
+
+    let converted = Value::try_from(json).unwrap();
+    assert_eq!(value, converted);
+
+    let json = serde_json::Value::from(Value::Double(f64::NAN));
+    assert!(json.is_null());
+    assert!(Value::try_from(json).is_err());
+}
+
+#[test]
+fn display_snbt() {
+    let value = nbt!({
+        "list": [1i8, 2i16, 3i32, 4i64, 1.5f32, 2.5f64],
+        "arrays": [[B; 1, 255], [I; 1, -2], [L; 3]],
+        "quoted key": "say \"hi\""
+    });
+
+    let snbt = value.to_string();
+    assert_eq!(snbt, to_snbt(&value));
+    assert!(snbt.contains("list:[1b,2s,3,4L,1.5f,2.5d]"));
+    assert!(snbt.contains("arrays:[[B;1b,-1b],[I;1,-2],[L;3L]]"));
+    assert!(snbt.contains(r#""quoted key":'say "hi"'"#));
+}
+
+#[test]
+fn parse_snbt() {
+    let value: Value = r#"{Name:"Steve",Health:20s}"#.parse().unwrap();
+    assert_eq!(value, nbt!({ "Name": "Steve", "Health": 20i16 }));
+
+    let value = nbt!({
+        "list": [1i8, 2i16, 3i32, 4i64, 1.5f32, 2.5f64],
+        "arrays": [[B; 1, 255], [I; 1, -2], [L; 3]],
+        "quoted key": "say \"hi\"",
+        "empty": { "list": [], "compound": {} }
+    });
+    assert_eq!(from_snbt(&value.to_string()).unwrap(), value);
+
+    let value: Value = " { a : [ 'x' , y2 , true , 1.5 , 1e3 ] } ".parse().unwrap();
+    assert_eq!(value, nbt!({ "a": ["x", "y2", 1i8, 1.5f64, 1000.0f64] }));
+
+    assert!(from_snbt("{a:1").is_err());
+    assert!(from_snbt("{a:1}}").is_err());
+    assert!(from_snbt("[B;1b,1.5f]").is_err());
+    assert!(from_snbt("\"unterminated").is_err());
+}
+
+#[test]
+fn numeric_coercion() {
+    assert_eq!(Value::Byte(-1).as_i64(), Some(-1));
+    assert_eq!(Value::Short(2).as_i64(), Some(2));
+    assert_eq!(Value::Int(3).as_i64(), Some(3));
+    assert_eq!(Value::Long(i64::MAX).as_i64(), Some(i64::MAX));
+    assert_eq!(Value::Float(1.0).as_i64(), None);
+
+    assert_eq!(Value::Byte(1).as_f64(), Some(1.0));
+    assert_eq!(Value::Float(1.5).as_f64(), Some(1.5));
+    assert_eq!(Value::Double(2.5).as_f64(), Some(2.5));
+    assert_eq!(Value::from("1").as_f64(), None);
+}
+
+#[test]
+fn value_from() {
+    assert_eq!(Value::from(42i32), Value::Int(42));
+    assert_eq!(Value::from(true), Value::Byte(1));
+    assert_eq!(Value::from(vec![1u8, 255]), Value::ByteArray(vec![1, 255]));
+    assert_eq!(Value::from(vec![1i8, -1]), Value::ByteArray(vec![1, 255]));
+    assert_eq!(Value::from(vec![1i32]), Value::IntArray(vec![1]));
+    assert_eq!(Value::from(vec![1i64]), Value::LongArray(vec![1]));
+    assert_eq!(
+        Value::from(vec![Value::Int(1)]),
+        Value::List(vec![Value::Int(1)])
+    );
+
+    let mut map = Map::new();
+    map.insert("k".into(), 5i32.into());
+    assert_eq!(Value::from(map), nbt!({ "k": 5 }));
+
+    let map = std::collections::HashMap::from([("k".to_owned(), Value::from("v"))]);
+    assert_eq!(Value::from(map), nbt!({ "k": "v" }));
+}
+
+#[test]
+fn value_from_iter() {
+    let list: Value = (0..3).map(Value::Int).collect();
+    assert_eq!(list, nbt!([0, 1, 2]));
+
+    let compound: Value = [("a", Value::Int(1))].into_iter().collect();
+    assert_eq!(compound, nbt!({ "a": 1 }));
+
+    let compound: Value = [("b".to_owned(), Value::from("c"))].into_iter().collect();
+    assert_eq!(compound, nbt!({ "b": "c" }));
+}
+
+#[test]
+fn stream_parser() {
+    use crate::stream::{Event, Parser};
+    use crate::{EndiannessImpl, FieldType};
+
+    fn find_target<F: EndiannessImpl>(mut encoded: &[u8]) -> Option<Event> {
+        let mut found = None;
+        let mut parser = Parser::<_, F>::new(&mut encoded);
+
+        assert!(matches!(
+            parser.next_event(),
+            Ok(Some(Event::TagStart { .. }))
+        ));
+        assert_eq!(parser.next_event().unwrap(), Some(Event::CompoundStart));
+        while let Some(event) = parser.next_event().unwrap() {
+            match event {
+                Event::TagStart { name, .. } if name == "target" => {
+                    found = parser.next_event().unwrap();
+                }
+                Event::TagStart { .. } => parser.skip().unwrap(),
+                Event::CompoundEnd => assert_eq!(parser.depth(), 0),
+                event => panic!("Unexpected event {event:?}"),
+            }
+        }
+
+        assert!(encoded.is_empty());
+        found
+    }
+
+    let mut parser = Parser::<_, BigEndian>::new(HELLO_WORLD_NBT);
+    let mut events = Vec::new();
+    while let Some(event) = parser.next_event().unwrap() {
+        events.push(event);
+    }
+    assert_eq!(
+        events,
+        [
+            Event::TagStart {
+                name: "hello world".to_owned(),
+                ty: FieldType::Compound
+            },
+            Event::CompoundStart,
+            Event::TagStart {
+                name: "name".to_owned(),
+                ty: FieldType::String
+            },
+            Event::Primitive(Value::from("Bananrama")),
+            Event::CompoundEnd,
+        ]
+    );
+
+    let value = nbt!({
+        "a": { "nested": [{ "x": 1 }, { "y": [L; 1, 2] }] },
+        "b": [B; 1, 2, 3],
+        "c": "skipped",
+        "target": 42i64,
+        "d": [[1i16], [2i16]],
+        "e": [I; -1, 300000]
+    });
+    let target = Some(Event::Primitive(Value::Long(42)));
+
+    assert_eq!(
+        find_target::<BigEndian>(&to_be_bytes(&value).unwrap()),
+        target
+    );
+    assert_eq!(
+        find_target::<LittleEndian>(&to_le_bytes(&value).unwrap()),
+        target
+    );
+    assert_eq!(
+        find_target::<NetworkLittleEndian>(&to_net_bytes(&value).unwrap()),
+        target
+    );
+}
+
+#[test]
+fn read_arrays() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Arrays {
+        bytes: Vec<i8>,
+        ints: Vec<i32>,
+        longs: [i64; 3],
+    }
+
+    let value = nbt!({
+        "bytes": [B; 1, 255],
+        "ints": [I; -1, 300000, i32::MIN],
+        "longs": [L; 1, -1, i64::MAX]
+    });
+    let expected = Arrays {
+        bytes: vec![1, -1],
+        ints: vec![-1, 300000, i32::MIN],
+        longs: [1, -1, i64::MAX],
+    };
+
+    let decoded: Arrays = from_be_bytes(&mut to_be_bytes(&value).unwrap().as_slice()).unwrap();
+    assert_eq!(decoded, expected);
+    let decoded: Arrays = from_le_bytes(&mut to_le_bytes(&value).unwrap().as_slice()).unwrap();
+    assert_eq!(decoded, expected);
+    let decoded: Arrays = from_net_bytes(&mut to_net_bytes(&value).unwrap().as_slice()).unwrap();
+    assert_eq!(decoded, expected);
+
+    let value = nbt!({ "bytes": [B; 1], "ints": [I; 1], "longs": [L; 1, 2] });
+    let result: Result<Arrays, _> = from_be_bytes(&mut to_be_bytes(&value).unwrap().as_slice());
+    assert!(result.is_err());
+}
+
+#[test]
+fn read_write_int_long_arrays() {
+    let value = nbt!({
+        "ints": [I; 1, -2, 300000],
+        "longs": [L; 1, -2, i64::MAX],
+        "empty": [I;],
+        "list": [1, 2]
+    });
+
+    // Keys are sorted so that re-encoding produces the same bytes.
+    let encoded = to_bytes_sorted::<BigEndian>(&value).unwrap();
+    let be: Value = from_be_bytes(&mut encoded.as_slice()).unwrap();
+    assert_eq!(to_bytes_sorted::<BigEndian>(&be).unwrap(), encoded);
+
+    let encoded = to_bytes_sorted::<LittleEndian>(&value).unwrap();
+    let le: Value = from_le_bytes(&mut encoded.as_slice()).unwrap();
+    assert_eq!(to_bytes_sorted::<LittleEndian>(&le).unwrap(), encoded);
+
+    let encoded = to_bytes_sorted::<NetworkLittleEndian>(&value).unwrap();
+    let net: Value = from_net_bytes(&mut encoded.as_slice()).unwrap();
+    assert_eq!(
+        to_bytes_sorted::<NetworkLittleEndian>(&net).unwrap(),
+        encoded
+    );
+
+    for decoded in [be, le, net] {
+        assert_eq!(decoded, value);
+
+        let compound = decoded.as_compound().unwrap();
+        assert!(compound["ints"].is_int_array());
+        assert!(compound["longs"].is_long_array());
+        assert!(compound["empty"].is_int_array());
+        assert!(compound["list"].is_list());
+    }
+}
+
+#[test]
+fn read_write_byte_array() {
+    const KEY: &str = "byteArrayTest (the first 1000 values of (n*n*255+n*7)%100, starting with n=0 (0, 62, 34, 16, 8, ...))";
+
+    let (name, value) = from_bytes_named::<BigEndian, Value>(&mut BIG_TEST_NBT).unwrap();
+    let bytes = value.as_compound().unwrap()[KEY].as_byte_array().unwrap();
+    assert_eq!(bytes.len(), 1000);
+    assert!(bytes
+        .iter()
+        .enumerate()
+        .all(|(n, b)| *b as usize == (n * n * 255 + n * 7) % 100));
+
+    let encoded = to_bytes_named::<BigEndian>(&name, &value).unwrap();
+    assert_eq!(encoded.len(), BIG_TEST_NBT.len());
+
+    let decoded: Value = from_be_bytes(&mut encoded.as_slice()).unwrap();
+    assert_eq!(decoded, value);
+    assert!(decoded.as_compound().unwrap()[KEY].is_byte_array());
+}
+
+#[test]
+fn read_write_char() {
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Chars {
+        ascii: char,
+        unicode: char,
+    }
+
+    let chars = Chars {
+        ascii: 'a',
+        unicode: '🦀',
+    };
+
+    let encoded = to_net_bytes(&chars).unwrap();
+    let decoded: Chars = from_net_bytes(&mut encoded.as_slice()).unwrap();
+    assert_eq!(decoded, chars);
+
+    let value: Value = from_net_bytes(&mut encoded.as_slice()).unwrap();
+    assert_eq!(value, nbt!({ "ascii": "a", "unicode": "🦀" }));
+
+    let encoded = to_net_bytes(&nbt!({ "ascii": "ab", "unicode": "b" })).unwrap();
+    let result: Result<Chars, _> = from_net_bytes(&mut encoded.as_slice());
+    assert!(result.is_err());
+}
+
+#[test]
+fn read_write_unsigned() {
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Unsigned {
+        byte: u8,
+        short: u16,
+        int: u32,
+        long: u64,
+        bytes: Vec<u8>,
+        ints: Vec<u32>,
+    }
+
+    let unsigned = Unsigned {
+        byte: u8::MAX,
+        short: 40000,
+        int: u32::MAX,
+        long: u64::MAX - 1,
+        bytes: vec![0, 200],
+        ints: vec![1, u32::MAX],
+    };
+
+    let encoded = to_le_bytes(&unsigned).unwrap();
+    let decoded: Unsigned = from_le_bytes(&mut encoded.as_slice()).unwrap();
+    assert_eq!(decoded, unsigned);
+
+    // The values wrap around to the signed tag of the same size.
+    let value: Value = from_le_bytes(&mut encoded.as_slice()).unwrap();
+    let compound = value.as_compound().unwrap();
+    assert_eq!(compound["byte"], Value::Byte(-1));
+    assert_eq!(compound["short"], Value::Short(40000u16 as i16));
+    assert_eq!(compound["int"], Value::Int(-1));
+    assert_eq!(compound["long"], Value::Long(-2));
+
+    // Arrays are reinterpreted as well.
+    let encoded = to_le_bytes(&nbt!({
+        "byte": 1i8, "short": 1i16, "int": 1, "long": 1i64,
+        "bytes": [B; 255], "ints": [I; -1]
+    }))
+    .unwrap();
+    let decoded: Unsigned = from_le_bytes(&mut encoded.as_slice()).unwrap();
+    assert_eq!(decoded.bytes, [255]);
+    assert_eq!(decoded.ints, [u32::MAX]);
+}
+
+#[test]
+fn value_conversion() {
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Data {
+        name: String,
+        optional: Option<i32>,
+        missing: Option<i32>,
+        unsigned: u32,
+        flag: bool,
+        letter: char,
+        list: Vec<i16>,
+        nested: Vec<Map>,
+    }
+
+    let data = Data {
+        name: "Steve".to_owned(),
+        optional: Some(1),
+        missing: None,
+        unsigned: u32::MAX,
+        flag: true,
+        letter: 'x',
+        list: vec![1, 2],
+        nested: vec![Map::from([("a".to_owned(), Value::IntArray(vec![1, 2]))])],
+    };
+
+    let value = crate::to_value(&data).unwrap();
+    let decoded: Value = from_be_bytes(&mut to_be_bytes(&data).unwrap().as_slice()).unwrap();
+    assert_eq!(value, decoded);
+    assert!(!value.as_compound().unwrap().contains_key("missing"));
+
+    let converted: Data = crate::from_value(value.clone()).unwrap();
+    assert_eq!(converted, data);
+
+    let converted: Value = crate::from_value(value.clone()).unwrap();
+    assert_eq!(converted, value);
+    assert_eq!(crate::to_value(&value).unwrap(), value);
+
+    // Lists are checked the same way as when serializing to bytes.
+    let list = nbt!([Value::Int(1), Value::Byte(1)]);
+    assert!(to_be_bytes(&nbt!({ "list": list.clone() })).is_err());
+    assert!(matches!(
+        crate::to_value(&list),
+        Err(NbtError::HeterogeneousList {
+            expected: FieldType::Int,
+            actual: FieldType::Byte
+        })
+    ));
+
+    // Newtype structs are rejected, just like when deserializing from bytes.
+    #[derive(Deserialize, Debug)]
+    struct Wrapper(#[allow(dead_code)] Map);
+
+    let value = nbt!({ "a": 1i32 });
+    let err =
+        from_be_bytes::<Wrapper, _>(&mut to_be_bytes(&value).unwrap().as_slice()).unwrap_err();
+    assert!(matches!(err.inner(), NbtError::Unsupported(_)));
+    let err = crate::from_value::<Wrapper>(value).unwrap_err();
+    assert!(matches!(err.inner(), NbtError::Unsupported(_)));
+}
+
+#[test]
+fn read_private_array_keys() {
+    // Compounds that contain the names used internally for arrays are still compounds.
+    let values = [
+        nbt!({ "$nbtx::private::IntArray": "x" }),
+        nbt!({ "$nbtx::private::IntArray": [I; 1, 2] }),
+        nbt!({ "x": { "$nbtx::private::LongArray": [L; 1, 2] } }),
+    ];
+
+    for value in values {
+        let encoded = to_be_bytes(&value).unwrap();
+        let decoded: Value = from_be_bytes(&mut encoded.as_slice()).unwrap();
+        assert_eq!(decoded, value);
+        assert_eq!(crate::from_value::<Value>(value.clone()).unwrap(), value);
+    }
+}
+
+#[test]
+fn nbt_macro() {
+    let value = nbt!({
+        "byte": 42i8,
+        "short": 42i16,
+        "int": 42i32,
+        "long": 42i64,
+        "float": 42.0f32,
+        "double": 42.0f64,
+        "byte_array": [B; 1, 2, 3],
+        "int_array": [I; 1, 2, 3],
+        "long_array": [L; 1, 2, 3],
+        "string": "Hello, World!",
+        "list": [
+            { "name": "Compound 1" },
+            { "name": "Compound 2" }
+        ],
+        "compound": { "name": "Compound 3" }
+    });
+
+    let expected = Value::Compound(Map::from([
+        ("byte".to_owned(), Value::Byte(42)),
+        ("short".to_owned(), Value::Short(42)),
+        ("int".to_owned(), Value::Int(42)),
+        ("long".to_owned(), Value::Long(42)),
+        ("float".to_owned(), Value::Float(42.0)),
+        ("double".to_owned(), Value::Double(42.0)),
+        ("byte_array".to_owned(), Value::ByteArray(vec![1, 2, 3])),
+        ("int_array".to_owned(), Value::IntArray(vec![1, 2, 3])),
+        ("long_array".to_owned(), Value::LongArray(vec![1, 2, 3])),
+        (
+            "string".to_owned(),
+            Value::String("Hello, World!".to_owned()),
+        ),
+        (
+            "list".to_owned(),
+            Value::List(vec![
+                Value::Compound(Map::from([(
+                    "name".to_owned(),
+                    Value::String("Compound 1".to_owned()),
+                )])),
+                Value::Compound(Map::from([(
+                    "name".to_owned(),
+                    Value::String("Compound 2".to_owned()),
+                )])),
+            ]),
+        ),
+        (
+            "compound".to_owned(),
+            Value::Compound(Map::from([(
+                "name".to_owned(),
+                Value::String("Compound 3".to_owned()),
+            )])),
+        ),
+    ]));
+    assert_eq!(value, expected);
+
+    // Expressions can be used as values.
+    let name = "Steve";
+    assert_eq!(
+        nbt!({ "name": name, "health": (10i16 * 2), "empty": [] }),
+        Value::Compound(Map::from([
+            ("name".to_owned(), Value::String("Steve".to_owned())),
+            ("health".to_owned(), Value::Short(20)),
+            ("empty".to_owned(), Value::List(Vec::new())),
+        ]))
+    );
+}
+
+#[test]
+fn snbt_max_depth() {
+    use crate::from_snbt;
+
+    let err = from_snbt(&"[".repeat(200_000)).unwrap_err();
+    assert!(
+        matches!(err, NbtError::MaxDepthExceeded { max: 512 }),
+        "{err}"
+    );
+    let err = from_snbt(&"{a:".repeat(200_000)).unwrap_err();
+    assert!(
+        matches!(err, NbtError::MaxDepthExceeded { max: 512 }),
+        "{err}"
+    );
+
+    let nested = format!("{}{}", "[".repeat(512), "]".repeat(512));
+    assert!(from_snbt(&nested).is_ok());
+    let nested = format!("{}{}", "[".repeat(513), "]".repeat(513));
+    assert!(from_snbt(&nested).is_err());
+}
+
+#[test]
+fn oversized_array_lengths() {
+    use crate::stream::Parser;
+
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Data {
+        a: Vec<i32>,
+    }
+
+    for ty in [7u8, 11, 12] {
+        // An array that claims to have 2^31 - 1 elements, but does not contain any.
+        let encoded = [10, 0, 0, ty, 0, 1, b'a', 0x7f, 0xff, 0xff, 0xff];
+
+        let err = from_be_bytes::<Value, _>(&mut encoded.as_slice()).unwrap_err();
+        assert!(matches!(err.inner(), NbtError::ByteError(_)), "{err}");
+        assert!(from_be_bytes::<Data, _>(&mut encoded.as_slice()).is_err());
+
+        let mut parser = Parser::<_, BigEndian>::new(encoded.as_slice());
+        let result = std::iter::from_fn(|| parser.next_event().transpose()).find(Result::is_err);
+        assert!(result.is_some());
+    }
+
+    // Arrays that are longer than a single chunk are still read correctly.
+    let value = nbt!({ "a": Value::IntArray((0..10_000).collect()) });
+    let encoded = to_net_bytes(&value).unwrap();
+    assert_eq!(
+        from_net_bytes::<Value, _>(&mut encoded.as_slice()).unwrap(),
+        value
+    );
+}
+
+#[test]
+fn value_iterators() {
+    let mut value = nbt!({ "a": 1, "b": 2 });
+    let mut sum = 0;
+    for (_, v) in value.entries().unwrap() {
+        sum += v.as_int().unwrap();
+    }
+    assert_eq!(sum, 3);
+
+    for (_, v) in value.entries_mut().unwrap() {
+        *v = Value::Int(v.as_int().unwrap() * 10);
+    }
+    assert_eq!(value, nbt!({ "a": 10, "b": 20 }));
+    assert!(value.iter().is_none());
+
+    let mut list = nbt!(["x", "y"]);
+    for v in list.iter_mut().unwrap() {
+        *v = Value::from(v.as_string().unwrap().to_uppercase());
+    }
+    assert_eq!(list.iter().unwrap().collect::<Vec<_>>(), ["X", "Y"]);
+    assert!(list.entries().is_none());
+    assert!(nbt!([I; 1, 2]).iter().is_none());
+}
+
+#[test]
+fn merge_values() {
+    let base = nbt!({
+        "name": "stone",
+        "states": { "a": 1, "b": { "c": 2 } },
+        "list": [1, 2]
+    });
+
+    let merged = base.clone().merged(nbt!({
+        "states": { "b": { "d": 3 }, "e": 4 },
+        "list": [3],
+        "name": { "replaced": 1i8 }
+    }));
+    assert_eq!(
+        merged,
+        nbt!({
+            "name": { "replaced": 1i8 },
+            "states": { "a": 1, "b": { "c": 2, "d": 3 }, "e": 4 },
+            "list": [3]
+        })
+    );
+
+    // Mismatched tags are replaced by the override.
+    assert_eq!(base.merged(Value::Int(1)), Value::Int(1));
+}
+
+#[test]
+fn diff_values() {
+    use crate::Change;
+
+    let old = nbt!({
+        "a/b": 1,
+        "list": [1, 2, 3],
+        "ints": [I; 1, 2],
+        "nested": { "same": "x", "removed": 1i8 }
+    });
+    let new = nbt!({
+        "a/b": 2,
+        "list": [1, 5],
+        "ints": [I; 1, 2, 3],
+        "nested": { "same": "x", "added": 1i8 }
+    });
+
+    assert_eq!(
+        old.diff_paths(&new),
+        [
+            (
+                "/a~1b".to_owned(),
+                Change::Changed {
+                    old: Value::Int(1),
+                    new: Value::Int(2)
+                }
+            ),
+            ("/ints/2".to_owned(), Change::Added(Value::Int(3))),
+            (
+                "/list/1".to_owned(),
+                Change::Changed {
+                    old: Value::Int(2),
+                    new: Value::Int(5)
+                }
+            ),
+            ("/list/2".to_owned(), Change::Removed(Value::Int(3))),
+            ("/nested/added".to_owned(), Change::Added(Value::Byte(1))),
+            (
+                "/nested/removed".to_owned(),
+                Change::Removed(Value::Byte(1))
+            ),
+        ]
+    );
+
+    assert!(old.diff_paths(&old).is_empty());
+    assert_eq!(
+        Value::Int(1).diff_paths(&Value::Long(1)),
+        [(
+            String::new(),
+            Change::Changed {
+                old: Value::Int(1),
+                new: Value::Long(1)
+            }
+        )]
+    );
+}
+
+#[test]
+fn value_ordering() {
+    use std::collections::BTreeMap;
+
+    assert_eq!(Value::Double(f64::NAN), Value::Double(f64::NAN));
+    assert_ne!(Value::Float(0.0), Value::Float(-0.0));
+    assert!(Value::Float(-0.0) < Value::Float(0.0));
+    assert!(Value::Double(1.0) < Value::Double(f64::NAN));
+
+    // Different types are ordered by their tag.
+    assert!(Value::Long(i64::MIN) > Value::Int(i32::MAX));
+    assert!(Value::String("a".to_owned()) < nbt!([]));
+
+    assert_eq!(
+        nbt!({ "a": 1, "b": 2 }).cmp(&nbt!({ "b": 2, "a": 1 })),
+        std::cmp::Ordering::Equal
+    );
+    assert!(nbt!({ "a": 1, "b": 2 }) < nbt!({ "a": 1, "b": 3 }));
+    assert!(nbt!({ "a": 1 }) < nbt!({ "a": 1, "b": 0 }));
+
+    let mut list = vec![Value::Int(3), Value::Byte(5), Value::Int(-1)];
+    list.sort();
+    assert_eq!(list, [Value::Byte(5), Value::Int(-1), Value::Int(3)]);
+
+    let mut map = BTreeMap::new();
+    map.insert(Value::Double(f64::NAN), 1);
+    assert_eq!(map.get(&Value::Double(f64::NAN)), Some(&1));
+}
+
+#[test]
+fn nan_equality() {
+    use std::collections::HashSet;
+    use std::hash::BuildHasher;
+
+    let value = nbt!({ "nan": f64::NAN, "list": [f32::NAN], "x": 1 });
+    assert_eq!(value, value.clone());
+
+    let state = std::collections::hash_map::RandomState::new();
+    assert_eq!(state.hash_one(&value), state.hash_one(value.clone()));
+
+    let mut set = HashSet::new();
+    set.insert(value.clone());
+    assert!(set.contains(&value));
+
+    let a: Value = from_be_bytes(&mut PLAYER_NAN_VALUE_NBT).unwrap();
+    let b: Value = from_be_bytes(&mut PLAYER_NAN_VALUE_NBT).unwrap();
+    assert_eq!(a, b);
+
+    // Comparisons with primitives are consistent with comparisons between values.
+    assert_eq!(Value::Float(f32::NAN), f32::NAN);
+    assert_eq!(&Value::Double(f64::NAN), f64::NAN);
+    assert_ne!(Value::Double(0.0), -0.0);
+    assert_ne!(Value::Double(0.0), Value::Double(-0.0));
+}
+
+#[test]
+fn value_type() {
+    let values = [
+        nbt!(1i8),
+        nbt!("x"),
+        nbt!([1, 2]),
+        nbt!({}),
+        Value::IntArray(vec![]),
+        Value::LongArray(vec![]),
+    ];
+
+    for value in &values {
+        assert_eq!(value.field_type() as u8, value.discriminant());
+    }
+
+    assert_eq!(values[0].field_type(), FieldType::Byte);
+    assert_eq!(values[3].field_type(), FieldType::Compound);
+    assert_eq!(values[0].type_name(), "Byte");
+    assert_eq!(values[2].type_name(), "List");
+    assert_eq!(values[4].type_name(), "IntArray");
+}
+
+#[test]
+fn serialize_max_depth() {
+    use crate::{to_bytes_with_options, SerializeOptions};
+
+    #[derive(Serialize)]
+    struct Nested {
+        inner: Option<Box<Nested>>,
+        array: Value,
+    }
+
+    let mut nested = Nested {
+        inner: None,
+        array: Value::IntArray(vec![1, 2]),
+    };
+    for _ in 0..9 {
+        nested = Nested {
+            inner: Some(Box::new(nested)),
+            array: Value::IntArray(vec![1, 2]),
+        };
+    }
+
+    for sort_keys in [false, true] {
+        let options = |max_depth| SerializeOptions {
+            sort_keys,
+            max_depth,
+            ..Default::default()
+        };
+
+        assert!(to_bytes_with_options::<BigEndian>(&nested, &options(None)).is_ok());
+        assert!(to_bytes_with_options::<BigEndian>(&nested, &options(Some(10))).is_ok());
+        assert!(matches!(
+            to_bytes_with_options::<BigEndian>(&nested, &options(Some(9))),
+            Err(NbtError::MaxDepthExceeded { max: 9 })
+        ));
+
+        let value = nbt!({ "a": [{ "b": [[1i8]] }] });
+        assert!(to_bytes_with_options::<LittleEndian>(&value, &options(Some(5))).is_ok());
+        assert!(matches!(
+            to_bytes_with_options::<LittleEndian>(&value, &options(Some(4))),
+            Err(NbtError::MaxDepthExceeded { max: 4 })
+        ));
+    }
+
+    let options = SerializeOptions {
+        root_name: Some("root".to_owned()),
+        ..Default::default()
+    };
+    let encoded = to_bytes_with_options::<BigEndian>(&nbt!({}), &options).unwrap();
+    assert_eq!(
+        encoded,
+        to_bytes_named::<BigEndian>("root", &nbt!({})).unwrap()
+    );
+}
+
+#[test]
+fn error_offset() {
+    #[derive(Deserialize, Debug)]
+    struct Data {
+        #[allow(dead_code)]
+        name: String,
+    }
+
+    // The root compound is 3 bytes, the type and name of the field are another 7 bytes.
+    let encoded = to_be_bytes(&nbt!({ "name": 1i32 })).unwrap();
+    let err = from_be_bytes::<Data, _>(&mut encoded.as_slice()).unwrap_err();
+    assert_eq!(err.offset(), Some(10));
+    assert!(matches!(
+        err.inner(),
+        NbtError::UnexpectedType {
+            expected: FieldType::String,
+            actual: FieldType::Int
+        }
+    ));
+    assert!(err.to_string().ends_with("(at byte offset 10, in `name`)"));
+
+    // Invalid UTF-8 in a string value.
+    let mut encoded = to_net_bytes(&nbt!({ "name": "ab" })).unwrap();
+    let len = encoded.len();
+    encoded[len - 2] = 0xff;
+    let err = from_net_bytes::<Data, _>(&mut encoded.as_slice()).unwrap_err();
+    assert_eq!(err.offset(), Some(len - 1));
+    assert!(matches!(err.inner(), NbtError::ByteError(_)));
+
+    // Errors in the header of the root compound.
+    let err = from_be_bytes::<Value, _>(&mut [FieldType::Int as u8].as_slice()).unwrap_err();
+    assert_eq!(err.offset(), Some(1));
+}
+
+#[test]
+fn error_path() {
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Block {
+        name: String,
+    }
+
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Section {
+        palette: Vec<Block>,
+    }
+
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Level {
+        sections: Vec<Section>,
+    }
+
+    #[derive(Deserialize, Debug)]
+    #[serde(rename_all = "PascalCase")]
+    #[allow(dead_code)]
+    struct Data {
+        level: Level,
+    }
+
+    let value = nbt!({
+        "Level": {
+            "sections": [
+                { "palette": [{ "name": "stone" }] },
+                { "palette": [{ "name": "dirt" }, { "name": 5i32 }] }
+            ]
+        }
+    });
+
+    let encoded = to_le_bytes(&value).unwrap();
+    let err = from_le_bytes::<Data, _>(&mut encoded.as_slice()).unwrap_err();
+    assert_eq!(err.path(), Some("Level.sections[1].palette[1].name"));
+    assert!(matches!(err.inner(), NbtError::UnexpectedType { .. }));
+
+    // Errors in the root compound have an empty path.
+    let encoded = to_le_bytes(&nbt!({ "Other": 1i8 })).unwrap();
+    let err = from_le_bytes::<Data, _>(&mut encoded.as_slice()).unwrap_err();
+    assert_eq!(err.path(), Some(""));
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn read_write_async() {
+    use crate::{from_async_bytes, from_async_net_bytes, to_async_bytes, to_async_net_bytes};
+
+    let value = nbt!({
+        "name": "Steve",
+        "health": 20i16,
+        "pos": [1.0f64, 64.0f64, -3.5f64],
+        "ints": [I; 1, -2, 300],
+        "longs": [L; 1, -2, 3_000_000_000],
+        "bytes": [B; 1, 2, 3],
+        "nested": { "list": [{ "a": 1i8 }, { "b": 2i64 }], "empty": {} }
+    });
+
+    // Data after the root compound must not be consumed.
+    let mut buffer = Vec::new();
+    to_async_net_bytes(&mut buffer, &value).await.unwrap();
+    buffer.extend_from_slice(b"trailing");
+
+    let mut reader = buffer.as_slice();
+    let decoded: Value = from_async_net_bytes(&mut reader).await.unwrap();
+    assert_eq!(decoded, value);
+    assert_eq!(reader, b"trailing");
+
+    let mut buffer = Vec::new();
+    to_async_bytes::<BigEndian, _, _>(&mut buffer, &value)
+        .await
+        .unwrap();
+    let decoded: Value = from_async_bytes::<BigEndian, _, _>(&mut buffer.as_slice())
+        .await
+        .unwrap();
+    assert_eq!(decoded, value);
+
+    // Truncated data is an error.
+    let truncated = &buffer[..buffer.len() - 1];
+    assert!(from_async_bytes::<BigEndian, Value, _>(&mut &truncated[..])
+        .await
+        .is_err());
+}
+
+#[test]
+fn validate_nbt() {
+    use crate::{validate, validate_with_max_depth};
+
+    let value = nbt!({
+        "name": "Steve",
+        "ints": [I; 1, 2, 3],
+        "list": [[1i8, 2i8], [3i8]],
+        "strings": ["a", "b"],
+        "nested": { "a": { "b": 1i64 } }
+    });
+
+    let be = to_be_bytes(&value).unwrap();
+    let net = to_net_bytes(&value).unwrap();
+    assert!(validate::<BigEndian>(&mut be.as_slice()).is_ok());
+    assert!(validate::<NetworkLittleEndian>(&mut net.as_slice()).is_ok());
+
+    // Every truncation of the data is invalid.
+    for len in 0..be.len() {
+        assert!(validate::<BigEndian>(&mut &be[..len]).is_err());
+    }
+
+    // Invalid tag type.
+    let mut invalid = be.clone();
+    invalid[3] = 13;
+    assert!(matches!(
+        validate::<BigEndian>(&mut invalid.as_slice()),
+        Err(NbtError::TypeOutOfRange { actual: 13 })
+    ));
+
+    // The root tag must be a compound.
+    let root = [FieldType::Int as u8, 0, 0, 0, 0, 0, 1];
+    assert!(matches!(
+        validate::<BigEndian>(&mut root.as_slice()),
+        Err(NbtError::UnexpectedType { .. })
+    ));
+
+    // Invalid UTF-8 in a string.
+    let mut invalid = to_be_bytes(&nbt!({ "a": "b" })).unwrap();
+    let len = invalid.len();
+    invalid[len - 2] = 0xff;
+    assert!(validate::<BigEndian>(&mut invalid.as_slice()).is_err());
+
+    assert!(validate_with_max_depth::<BigEndian>(&mut be.as_slice(), 3).is_ok());
+    assert!(matches!(
+        validate_with_max_depth::<BigEndian>(&mut be.as_slice(), 2),
+        Err(NbtError::MaxDepthExceeded { max: 2 })
+    ));
+
+    // Lists count toward the depth, even when their elements are skipped.
+    let scalars = to_be_bytes(&nbt!({ "l": [1i32, 2i32] })).unwrap();
+    let compounds = to_be_bytes(&nbt!({ "l": [{ "a": 1i32 }] })).unwrap();
+    for data in [scalars, compounds] {
+        assert!(validate_with_max_depth::<BigEndian>(&mut data.as_slice(), 3).is_ok());
+        assert!(matches!(
+            validate_with_max_depth::<BigEndian>(&mut data.as_slice(), 1),
+            Err(NbtError::MaxDepthExceeded { max: 1 })
+        ));
+    }
+}
+
+#[test]
+fn stream_end_lists() {
+    use crate::stream::{Event, Parser};
+    use crate::{detect_variant, looks_like, validate, Variant};
+
+    // A list of end tags that claims to have elements.
+    let data = [10, 0, 0, 9, 0, 1, b'a', 0, 0x7f, 0xff, 0xff, 0xff, 0];
+
+    let err = validate::<BigEndian>(&mut data.as_slice()).unwrap_err();
+    assert!(matches!(err, NbtError::UnmatchedEnd), "{err}");
+    assert_eq!(detect_variant(&data), None);
+    assert!(!looks_like(Variant::BigEndian, &data));
+
+    let mut parser = Parser::<_, BigEndian>::new(data.as_slice());
+    assert!(matches!(
+        parser.next_event(),
+        Ok(Some(Event::TagStart { .. }))
+    ));
+    assert!(matches!(
+        parser.next_event(),
+        Ok(Some(Event::CompoundStart))
+    ));
+    assert!(matches!(
+        parser.next_event(),
+        Ok(Some(Event::TagStart { .. }))
+    ));
+    assert!(matches!(parser.next_event(), Err(NbtError::UnmatchedEnd)));
+
+    // Empty lists of end tags are still valid.
+    let data = [10, 0, 0, 9, 0, 1, b'a', 0, 0, 0, 0, 0, 0];
+    assert!(validate::<BigEndian>(&mut data.as_slice()).is_ok());
+    assert!(looks_like(Variant::BigEndian, &data));
+}
+
+#[test]
+fn lossy_utf8() {
+    use crate::{from_bytes_with_options, DeserializeOptions};
+
+    // Replace the first byte of the string value and the key with invalid UTF-8.
+    let mut encoded = to_net_bytes(&nbt!({ "key": "value" })).unwrap();
+    let len = encoded.len();
+    encoded[len - 6] = 0xff;
+    encoded[4] = 0xfe;
+
+    assert!(from_net_bytes::<Value, _>(&mut encoded.as_slice()).is_err());
+
+    let options = DeserializeOptions {
+        lossy_utf8: true,
+        ..Default::default()
+    };
+    let value: Value =
+        from_bytes_with_options::<NetworkLittleEndian, _>(&mut encoded.as_slice(), &options)
+            .unwrap();
+    assert_eq!(value, nbt!({ "\u{fffd}ey": "\u{fffd}alue" }));
+}
+
+#[test]
+fn bytes_written() {
+    use crate::ser::{to_be_bytes_in, to_bytes_in, to_net_bytes_in};
+
+    let value = nbt!({ "a": [1i32, 2i32], "b": "c" });
+
+    // Written after a header, as if framed in a larger message.
+    let mut writer = vec![1, 2, 3];
+    let written = to_net_bytes_in(&mut writer, &value).unwrap();
+    assert_eq!(written, writer.len() - 3);
+
+    let written = to_be_bytes_in(&mut Vec::new(), &value).unwrap();
+    assert_eq!(written, to_be_bytes(&value).unwrap().len());
+
+    let written = to_bytes_in::<LittleEndian>(&mut std::io::sink(), &value).unwrap();
+    assert_eq!(written, to_le_bytes(&value).unwrap().len());
+}
+
+#[test]
+fn value_len() {
+    assert_eq!(nbt!([1, 2, 3]).len(), Some(3));
+    assert_eq!(nbt!({ "a": 1 }).len(), Some(1));
+    assert_eq!(Value::ByteArray(vec![1, 2]).len(), Some(2));
+    assert_eq!(Value::IntArray(vec![]).len(), Some(0));
+    assert_eq!(Value::LongArray(vec![1]).len(), Some(1));
+    assert_eq!(nbt!("abc").len(), None);
+    assert_eq!(nbt!(1i8).len(), None);
+
+    assert!(nbt!({}).is_empty());
+    assert!(nbt!([]).is_empty());
+    assert!(!nbt!([1]).is_empty());
+    assert!(!nbt!("").is_empty());
+    assert!(!nbt!(0).is_empty());
+}
+
+#[test]
+fn value_compound_entries() {
+    let mut value = nbt!({ "a": 1, "b": 2 });
+
+    assert!(value.contains_key("a"));
+    assert!(!value.contains_key("c"));
+
+    assert_eq!(value.insert("c", 3i8), None);
+    assert_eq!(value.insert("a", "x"), Some(Value::Int(1)));
+    assert_eq!(value.remove("b"), Some(Value::Int(2)));
+    assert_eq!(value.remove("b"), None);
+    assert_eq!(value, nbt!({ "a": "x", "c": 3i8 }));
+
+    let mut list = nbt!([1]);
+    assert!(!list.contains_key("a"));
+    assert_eq!(list.insert("a", 1), None);
+    assert_eq!(list.remove("a"), None);
+    assert_eq!(list, nbt!([1]));
+}
+
+#[test]
+fn value_retain() {
+    let mut value = nbt!({ "a": 1, "b": 2, "c": 3 });
+    value.retain_compound(|k, v| k != "a" && v != 3);
+    assert_eq!(value, nbt!({ "b": 2 }));
+
+    let mut list = nbt!([1, 2, 3, 4]);
+    list.retain_list(|v| v.as_int().is_some_and(|v| v % 2 == 0));
+    assert_eq!(list, nbt!([2, 4]));
+
+    // Mismatched variants are left unchanged.
+    list.retain_compound(|_, _| false);
+    assert_eq!(list, nbt!([2, 4]));
+    value.retain_list(|_| false);
+    assert_eq!(value, nbt!({ "b": 2 }));
+}
+
+#[test]
+fn value_take() {
+    let mut value = nbt!([1, 2]);
+    assert_eq!(value.take(), nbt!([1, 2]));
+    assert_eq!(value, nbt!({}));
+    assert_eq!(Value::default(), nbt!({}));
+}
+
+#[test]
+fn array_tags() {
+    struct Bytes(Vec<u8>);
+
+    impl Serialize for Bytes {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_bytes(&self.0)
+        }
+    }
+
+    #[derive(Serialize)]
+    struct Data {
+        bytes: Bytes,
+        unsigned: Vec<u8>,
+        signed: Vec<i8>,
+        ints: Vec<i32>,
+        longs: Vec<i64>,
+        values: Vec<Value>,
+        int_array: Value,
+        long_array: Value,
+    }
+
+    let data = Data {
+        bytes: Bytes(vec![1, 2]),
+        unsigned: vec![1, 2],
+        signed: vec![1, 2],
+        ints: vec![1, 2],
+        longs: vec![1, 2],
+        values: vec![Value::Int(1)],
+        int_array: Value::IntArray(vec![1, 2]),
+        long_array: Value::LongArray(vec![1, 2]),
+    };
+
+    let encoded = to_be_bytes(&data).unwrap();
+    let value: Value = from_be_bytes(&mut encoded.as_slice()).unwrap();
+    let map = value.as_compound().unwrap();
+
+    assert_eq!(map["bytes"], Value::ByteArray(vec![1, 2]));
+    assert_eq!(map["unsigned"], nbt!([1i8, 2i8]));
+    assert_eq!(map["signed"], nbt!([1i8, 2i8]));
+    assert_eq!(map["ints"], nbt!([1, 2]));
+    assert_eq!(map["longs"], nbt!([1i64, 2i64]));
+    assert_eq!(map["values"], nbt!([1]));
+    assert_eq!(map["int_array"], Value::IntArray(vec![1, 2]));
+    assert_eq!(map["long_array"], Value::LongArray(vec![1, 2]));
+
+    // Arrays and lists can both be deserialized into vectors.
+    #[derive(Deserialize)]
+    struct Vectors {
+        bytes: Vec<i8>,
+        unsigned: Vec<u8>,
+        int_array: Vec<i32>,
+        ints: Vec<i32>,
+    }
+
+    let vectors: Vectors = from_be_bytes(&mut encoded.as_slice()).unwrap();
+    assert_eq!(vectors.bytes, [1, 2]);
+    assert_eq!(vectors.unsigned, [1, 2]);
+    assert_eq!(vectors.int_array, [1, 2]);
+    assert_eq!(vectors.ints, [1, 2]);
+}
+
+#[test]
+fn array_modules() {
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Data {
+        #[serde(with = "crate::byte_array")]
+        bytes: Vec<u8>,
+        #[serde(with = "crate::int_array")]
+        ints: Vec<i32>,
+        #[serde(with = "crate::long_array")]
+        longs: Vec<i64>,
+        #[serde(with = "crate::int_array")]
+        empty: Vec<i32>,
+    }
+
+    let data = Data {
+        bytes: vec![0, 255],
+        ints: vec![1, -2, i32::MAX],
+        longs: vec![3, i64::MIN],
+        empty: vec![],
+    };
+
+    let expected = nbt!({
+        "bytes": Value::ByteArray(vec![0, 255]),
+        "ints": Value::IntArray(vec![1, -2, i32::MAX]),
+        "longs": Value::LongArray(vec![3, i64::MIN]),
+        "empty": Value::IntArray(vec![])
+    });
+
+    let encoded = to_be_bytes(&data).unwrap();
+    assert_eq!(
+        from_be_bytes::<Value, _>(&mut encoded.as_slice()).unwrap(),
+        expected
+    );
+    let encoded = to_le_bytes(&data).unwrap();
+    assert_eq!(
+        from_le_bytes::<Value, _>(&mut encoded.as_slice()).unwrap(),
+        expected
+    );
+
+    let encoded = to_net_bytes(&data).unwrap();
+    let decoded: Data = from_net_bytes(&mut encoded.as_slice()).unwrap();
+    assert_eq!(decoded, data);
+
+    let value = crate::to_value(&data).unwrap();
+    assert_eq!(value, expected);
+    assert_eq!(crate::from_value::<Data>(value).unwrap(), data);
+
+    // Lists are accepted when deserializing as well.
+    let list = nbt!({ "bytes": [1i8], "ints": [1], "longs": [1i64], "empty": [] });
+    let decoded: Data = crate::from_value(list).unwrap();
+    assert_eq!(decoded.ints, [1]);
+}
+
+#[test]
+fn empty_list() {
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Empty {
+        empty: Vec<i32>,
+    }
+
+    // Empty lists are written with the end tag as element type, like vanilla does.
+    let raw: &[u8] = &[
+        10, 0, 0, 9, 0, 5, b'e', b'm', b'p', b't', b'y', 0, 0, 0, 0, 0, 0,
+    ];
+
+    let value: Value = from_be_bytes(&mut &raw[..]).unwrap();
+    assert_eq!(value, nbt!({ "empty": [] }));
+    assert_eq!(to_be_bytes(&value).unwrap(), raw);
+
+    let decoded: Empty = from_be_bytes(&mut &raw[..]).unwrap();
+    assert_eq!(decoded, Empty { empty: vec![] });
+    assert_eq!(to_bytes_named::<BigEndian>("", &decoded).unwrap(), raw);
+
+    let encoded = to_le_bytes(&decoded).unwrap();
+    assert_eq!(
+        from_le_bytes::<Empty, _>(&mut encoded.as_slice()).unwrap(),
+        decoded
+    );
+    let encoded = to_net_bytes(&value).unwrap();
+    assert_eq!(
+        from_net_bytes::<Value, _>(&mut encoded.as_slice()).unwrap(),
+        value
+    );
+}
+
+#[test]
+fn empty_list_minecraft() {
+    // The inventory in this file is an empty list of bytes.
+    let value: Value = from_be_bytes(&mut PLAYER_NAN_VALUE_NBT).unwrap();
+    let inventory = &value.as_compound().unwrap()["Inventory"];
+    assert_eq!(inventory, &Value::List(vec![]));
+
+    let encoded = to_be_bytes(&value).unwrap();
+    let decoded: Value = from_be_bytes(&mut encoded.as_slice()).unwrap();
+    assert_eq!(decoded, value);
+
+    // It is written back with the end type instead.
+    let key = b"Inventory";
+    let start = encoded
+        .windows(key.len())
+        .position(|window| window == key)
+        .unwrap()
+        + key.len();
+    assert_eq!(
+        encoded[start..start + 5],
+        [FieldType::End as u8, 0, 0, 0, 0]
+    );
+}
+
+#[test]
+fn heterogeneous_list() {
+    let value = nbt!({ "list": [Value::Int(1), Value::String("x".to_owned())] });
+    let result = to_be_bytes(&value);
+    assert!(matches!(
+        result,
+        Err(NbtError::HeterogeneousList {
+            expected: FieldType::Int,
+            actual: FieldType::String
+        })
+    ));
+
+    // Nested lists are checked separately from the list that contains them.
+    let value = nbt!({ "list": [[1i32, 2i32], ["a", "b"]] });
+    let encoded = to_net_bytes(&value).unwrap();
+    assert_eq!(
+        from_net_bytes::<Value, _>(&mut encoded.as_slice()).unwrap(),
+        value
+    );
+
+    let value = nbt!({ "list": [[1i32], 2i32] });
+    assert!(matches!(
+        to_le_bytes(&value),
+        Err(NbtError::HeterogeneousList {
+            expected: FieldType::List,
+            actual: FieldType::Int
+        })
+    ));
+}
+
+#[test]
+fn value_serialized_len() {
+    let values = [
+        nbt!({
+            "byte": 1i8,
+            "short": 2i16,
+            "int": -300i32,
+            "long": i64::MIN,
+            "float": 1.5f32,
+            "double": 2.5f64,
+            "string": "Hello, World!",
+            "bytes": Value::ByteArray(vec![1, 2, 3]),
+            "ints": Value::IntArray(vec![0, -1, i32::MAX]),
+            "longs": Value::LongArray(vec![64, i64::MAX]),
+            "list": [[1i32], [2i32, 3i32]],
+            "empty": [],
+            "nested": { "a": {}, "b": "c" }
+        }),
+        nbt!({}),
+        nbt!(5i32),
+        nbt!("x".repeat(200)),
+    ];
+
+    for value in values {
+        assert_eq!(
+            value.serialized_len::<BigEndian>(),
+            to_be_bytes(&value).unwrap().len()
+        );
+        assert_eq!(
+            value.serialized_len::<LittleEndian>(),
+            to_le_bytes(&value).unwrap().len()
+        );
+        assert_eq!(
+            value.serialized_len::<NetworkLittleEndian>(),
+            to_net_bytes(&value).unwrap().len()
+        );
+    }
+
+    let value: Value = from_be_bytes(&mut BIG_TEST_NBT).unwrap();
+    assert_eq!(
+        value.serialized_len::<NetworkLittleEndian>(),
+        to_net_bytes(&value).unwrap().len()
+    );
+}
+
+#[test]
+fn compound_builder() {
+    let value = crate::CompoundBuilder::new()
+        .with("name", "Steve")
+        .with("health", 20i8)
+        .with("health", 19i8)
+        .with("nested", crate::CompoundBuilder::new().build())
+        .build();
+
+    assert_eq!(
+        value,
+        nbt!({ "name": "Steve", "health": 19i8, "nested": {} })
+    );
+    assert_eq!(crate::CompoundBuilder::new().build(), Value::default());
+}
+
+#[test]
+fn field_type_ids() {
+    for id in 0..=12u8 {
+        let ty = FieldType::try_from(id).unwrap();
+        assert_eq!(ty.as_u8(), id);
+        assert_eq!(u8::from(ty), id);
+    }
+
+    assert!(matches!(
+        FieldType::try_from(13),
+        Err(NbtError::TypeOutOfRange { actual: 13 })
+    ));
+    assert_eq!(FieldType::Compound.name(), "TAG_Compound");
+    assert_eq!(FieldType::IntArray.name(), "TAG_Int_Array");
+    assert_eq!(
+        nbt!([1i64]).field_type().as_u8(),
+        nbt!([1i64]).discriminant()
+    );
+}
+
+#[test]
+fn value_get_path_mut() {
+    let mut value = nbt!({ "list": [{ "a": 1i32 }], "a/b": {}, "int": 5i32 });
+
+    *value.get_path_mut("/list/0/a").unwrap() = nbt!(2i32);
+    *value.get_path_mut("/a~1b/c/d").unwrap() = nbt!("e");
+    assert_eq!(
+        value,
+        nbt!({ "list": [{ "a": 2i32 }], "a/b": { "c": { "d": "e" } }, "int": 5i32 })
+    );
+
+    assert_eq!(value.get_path_mut("/new").unwrap(), &nbt!({}));
+    assert_eq!(value.get_path_mut("").unwrap().len(), Some(4));
+
+    assert!(matches!(
+        value.get_path_mut("/int/x"),
+        Err(NbtError::UnexpectedType {
+            expected: FieldType::Compound,
+            actual: FieldType::Int
+        })
+    ));
+    assert!(value.get_path_mut("/list/1").is_err());
+    assert!(value.get_path_mut("/list/x").is_err());
+    assert!(value.get_path_mut("list").is_err());
+}
+
+#[test]
+fn filter_schema() {
+    use crate::Schema;
+
+    let schema = Schema::compound([
+        ("name", Schema::Type(FieldType::String)),
+        ("data", Schema::Any),
+        (
+            "items",
+            Schema::list(Schema::compound([("id", Schema::Type(FieldType::Short))])),
+        ),
+    ]);
+
+    let mut value = nbt!({
+        "name": "Steve",
+        "data": { "anything": [1i32, 2i32] },
+        "items": [{ "id": 1i16, "count": 64i8 }, 5i32, { "id": "stone" }],
+        "op": 1i8,
+        "name2": "x"
+    });
+    value.filter_schema(&schema);
+
+    assert_eq!(
+        value,
+        nbt!({
+            "name": "Steve",
+            "data": { "anything": [1i32, 2i32] },
+            "items": [{ "id": 1i16 }, {}]
+        })
+    );
+
+    let mut value = nbt!({ "name": 1i32 });
+    value.filter_schema(&schema);
+    assert_eq!(value, nbt!({}));
+
+    let mut value = nbt!([1i32]);
+    value.filter_schema(&schema);
+    assert_eq!(value, nbt!({}));
+}
+
+#[test]
+fn value_uuid() {
+    let uuid = u128::MAX - 1;
+    let value = Value::from_uuid(uuid);
+    assert_eq!(value, Value::IntArray(vec![-1, -1, -1, -2]));
+    assert_eq!(value.as_uuid(), Some(uuid));
+
+    assert_eq!(Value::from_uuid(1 << 96).as_uuid(), Some(1 << 96));
+    assert_eq!(Value::IntArray(vec![1, 2, 3]).as_uuid(), None);
+    assert_eq!(nbt!([1i32, 2i32, 3i32, 4i32]).as_uuid(), None);
+}
+
+#[test]
+fn read_slice() {
+    let value = nbt!({ "name": "Steve", "list": [1i32, 2i32] });
+
+    let mut data = to_le_bytes(&value).unwrap();
+    data.extend_from_slice(b"tail");
+    let (decoded, rest) = crate::from_le_slice::<Value>(&data).unwrap();
+    assert_eq!(decoded, value);
+    assert_eq!(rest, b"tail");
+
+    let mut data = to_net_bytes(&value).unwrap();
+    data.extend_from_slice(&to_net_bytes(&value).unwrap());
+    let (first, rest) = crate::from_net_slice::<Value>(&data).unwrap();
+    let (second, rest) = crate::from_net_slice::<Value>(rest).unwrap();
+    assert_eq!(first, second);
+    assert!(rest.is_empty());
+
+    let data = to_be_bytes(&value).unwrap();
+    assert!(crate::from_be_slice::<Value>(&data[..data.len() - 1]).is_err());
+}
+
+#[test]
+fn snbt_non_finite() {
+    use crate::{to_snbt_with_options, NonFiniteFloats, SnbtOptions};
+
+    let value = nbt!([f32::NAN, f32::INFINITY, f32::NEG_INFINITY, 1.5f32]);
+    assert_eq!(to_snbt(&value), "[NaNf,Infinityf,-Infinityf,1.5f]");
+    assert_eq!(to_snbt(&nbt!([f64::INFINITY])), "[Infinityd]");
+
+    // These cannot be parsed back as numbers.
+    assert_eq!(from_snbt("NaNf").unwrap(), nbt!("NaNf"));
+
+    let options = SnbtOptions {
+        non_finite_floats: NonFiniteFloats::Error,
+    };
+    assert!(matches!(
+        to_snbt_with_options(&value, &options),
+        Err(NbtError::Unsupported(_))
+    ));
+    assert!(to_snbt_with_options(&nbt!({ "d": { "x": f64::NAN } }), &options).is_err());
+    assert_eq!(
+        to_snbt_with_options(&nbt!([1.5f32]), &options).unwrap(),
+        "[1.5f]"
+    );
+
+    // The NaN values from this file are written the same way as Java does.
+    let player: Value = from_be_bytes(&mut PLAYER_NAN_VALUE_NBT).unwrap();
+    assert!(to_snbt(&player).contains("NaNd"));
+    assert!(to_snbt_with_options(&player, &options).is_err());
+}
+
+#[test]
+fn bedrock_leveldat() {
+    use crate::{from_bedrock_leveldat, to_bedrock_leveldat};
+
+    let level = nbt!({ "LevelName": "Bedrock level", "StorageVersion": 10i32 });
+    let file = to_bedrock_leveldat(10, &level).unwrap();
+    assert_eq!(file[..4], 10i32.to_le_bytes());
+    assert_eq!(file[4..8], (file.len() as u32 - 8).to_le_bytes());
+    assert_eq!(file[8..], to_le_bytes(&level).unwrap());
+
+    let (version, decoded) = from_bedrock_leveldat::<Value>(&mut file.as_slice()).unwrap();
+    assert_eq!(version, 10);
+    assert_eq!(decoded, level);
+
+    // The length in the header must match the data.
+    let result = from_bedrock_leveldat::<Value>(&mut &file[..file.len() - 1]);
+    assert!(result.is_err());
+
+    let mut longer = file.clone();
+    longer[4] += 1;
+    longer.push(0);
+    let result = from_bedrock_leveldat::<Value>(&mut longer.as_slice());
+    assert!(result.is_err());
+}
+
+#[test]
+fn read_bytes_iter() {
+    use crate::from_bytes_iter;
+
+    let mut data = Vec::new();
+    for i in 0..3 {
+        data.extend(to_net_bytes(&nbt!({ "index": i })).unwrap());
+    }
+
+    let values = from_bytes_iter::<NetworkLittleEndian, Value, _>(data.as_slice())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(
+        values,
+        [
+            nbt!({ "index": 0 }),
+            nbt!({ "index": 1 }),
+            nbt!({ "index": 2 })
+        ]
+    );
+
+    assert_eq!(from_bytes_iter::<BigEndian, Value, _>(&[][..]).count(), 0);
+
+    // Truncated data is an error, after which the iterator ends.
+    let mut iter = from_bytes_iter::<NetworkLittleEndian, Value, _>(&data[..data.len() - 1]);
+    assert!(iter.next().unwrap().is_ok());
+    assert!(iter.next().unwrap().is_ok());
+    assert!(iter.next().unwrap().is_err());
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn write_byte_array() {
+    /// Records the size of every write.
+    struct Writes(Vec<usize>);
+
+    impl std::io::Write for Writes {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.push(buf.len());
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let value = nbt!({ "bytes": Value::ByteArray(vec![7; 4096]) });
+    let mut writes = Writes(Vec::new());
+    let written = crate::to_le_bytes_in(&mut writes, &value).unwrap();
+
+    // The contents of the array are written at once, rather than byte by byte.
+    assert!(writes.0.contains(&4096));
+    assert!(writes.0.len() < 16);
+    assert_eq!(written, writes.0.iter().sum::<usize>());
+}
+
+#[test]
+fn read_byte_limit() {
+    use crate::{from_bytes_with_options, DeserializeOptions};
+
+    let value = nbt!({ "name": "Steve", "data": Value::ByteArray(vec![0; 1000]) });
+    let encoded = to_be_bytes(&value).unwrap();
+
+    let options = DeserializeOptions {
+        max_bytes: Some(encoded.len()),
+        ..Default::default()
+    };
+    let decoded: Value =
+        from_bytes_with_options::<BigEndian, _>(&mut encoded.as_slice(), &options).unwrap();
+    assert_eq!(decoded, value);
+
+    for max_bytes in [0, 2, 100, encoded.len() - 1] {
+        let options = DeserializeOptions {
+            max_bytes: Some(max_bytes),
+            ..Default::default()
+        };
+        let err = from_bytes_with_options::<BigEndian, Value>(&mut encoded.as_slice(), &options)
+            .unwrap_err();
+        assert!(
+            matches!(err.inner(), NbtError::LimitExceeded { limit } if *limit == max_bytes),
+            "{err}"
+        );
+    }
+
+    // Truncated data is still reported as such when it is within the limit.
+    let options = DeserializeOptions {
+        max_bytes: Some(encoded.len()),
+        ..Default::default()
+    };
+    let err =
+        from_bytes_with_options::<BigEndian, Value>(&mut &encoded[..100], &options).unwrap_err();
+    assert!(!matches!(err.inner(), NbtError::LimitExceeded { .. }));
+
+    // Array lengths are checked against the limit before anything is allocated.
+    let options = DeserializeOptions {
+        max_bytes: Some(100),
+        ..Default::default()
+    };
+    for ty in [7, 11, 12] {
+        let data = [10, 0, 0, ty, 0, 1, b'a', 0x7f, 0xff, 0xff, 0xff];
+        let err = from_bytes_with_options::<BigEndian, Value>(&mut data.as_slice(), &options)
+            .unwrap_err();
+        assert!(
+            matches!(err.inner(), NbtError::LimitExceeded { limit: 100 }),
+            "{err}"
+        );
+    }
+}
+
+#[test]
+fn value_eq_vec() {
+    let bytes = Value::ByteArray(vec![1, 255]);
+    assert_eq!(bytes, vec![1u8, 255]);
+    assert_eq!(&bytes, &vec![1u8, 255]);
+    assert_eq!(bytes, vec![1i8, -1]);
+    assert_ne!(bytes, vec![1i8]);
+
+    let mut ints = Value::IntArray(vec![1, 2]);
+    assert_eq!(ints, vec![1, 2]);
+    assert_eq!(&mut ints, &vec![1, 2]);
+    assert_ne!(ints, vec![1i64, 2]);
+
+    let longs = Value::LongArray(vec![3]);
+    assert_eq!(&longs, vec![3i64]);
+
+    let list = nbt!([1i32, 2i32]);
+    assert_eq!(list, vec![Value::Int(1), Value::Int(2)]);
+    assert_ne!(list, vec![1, 2]);
+}
+
+#[test]
+fn value_eq_bool() {
+    assert_eq!(Value::Byte(1), true);
+    assert_eq!(Value::Byte(-5), true);
+    assert_eq!(&Value::Byte(0), false);
+    assert_ne!(&mut Value::Byte(0), true);
+
+    // Only bytes are compared.
+    assert_ne!(Value::Int(1), true);
+    assert_ne!(Value::Int(0), false);
+
+    let value = nbt!({ "OnGround": true });
+    assert_eq!(value.as_compound().unwrap()["OnGround"], true);
+}
+
+#[test]
+fn value_as_bool() {
+    assert_eq!(Value::Byte(1).as_bool(), Some(true));
+    assert_eq!(Value::Byte(2).as_bool(), Some(true));
+    assert_eq!(Value::Byte(0).as_bool(), Some(false));
+    assert_eq!(Value::Short(1).as_bool(), None);
+    assert_eq!(nbt!("true").as_bool(), None);
+}
+
+#[test]
+fn value_compact() {
+    let mut value = nbt!({
+        "bytes": [1i8, -1i8],
+        "ints": [1i32, 2i32],
+        "longs": [3i64],
+        "mixed": [1i32, 2i64],
+        "shorts": [1i16],
+        "empty": [],
+        "nested": [[5i32], [6i32, 7i32]]
+    });
+    value.compact();
+
+    assert_eq!(
+        value,
+        nbt!({
+            "bytes": Value::ByteArray(vec![1, 255]),
+            "ints": Value::IntArray(vec![1, 2]),
+            "longs": Value::LongArray(vec![3]),
+            "mixed": [1i32, 2i64],
+            "shorts": [1i16],
+            "empty": [],
+            "nested": [Value::IntArray(vec![5]), Value::IntArray(vec![6, 7])]
+        })
+    );
+}
+
+#[test]
+fn value_canonical_bytes() {
+    let mut a = Map::new();
+    let mut b = Map::new();
+    for i in 0..32 {
+        a.insert(format!("key{i}"), nbt!({ "index": i, "name": "x" }));
+        b.insert(
+            format!("key{}", 31 - i),
+            nbt!({ "name": "x", "index": 31 - i }),
+        );
+    }
+
+    let a = Value::Compound(a);
+    let b = Value::Compound(b);
+    assert_eq!(a, b);
+    assert_eq!(
+        a.canonical_bytes::<NetworkLittleEndian>().unwrap(),
+        b.canonical_bytes::<NetworkLittleEndian>().unwrap()
+    );
+    assert_ne!(
+        a.canonical_bytes::<BigEndian>().unwrap(),
+        a.canonical_bytes::<LittleEndian>().unwrap()
+    );
+
+    let decoded: Value =
+        from_be_bytes(&mut a.canonical_bytes::<BigEndian>().unwrap().as_slice()).unwrap();
+    assert_eq!(decoded, a);
+}
+
+#[test]
+fn unit_as_compound() {
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Marker;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Data {
+        unit: (),
+        marker: Marker,
+        value: i32,
+    }
+
+    let data = Data {
+        unit: (),
+        marker: Marker,
+        value: 5,
+    };
+    let expected = nbt!({ "unit": {}, "marker": {}, "value": 5i32 });
+
+    let encoded = to_be_bytes(&data).unwrap();
+    let value: Value = from_be_bytes(&mut encoded.as_slice()).unwrap();
+    assert_eq!(value, expected);
+    let decoded: Data = from_be_bytes(&mut encoded.as_slice()).unwrap();
+    assert_eq!(decoded, data);
+
+    let encoded = to_net_bytes(&Marker).unwrap();
+    let decoded: Marker = from_net_bytes(&mut encoded.as_slice()).unwrap();
+    assert_eq!(decoded, Marker);
+
+    assert_eq!(crate::to_value(&data).unwrap(), expected);
+    assert_eq!(crate::from_value::<Data>(expected).unwrap(), data);
+
+    // Only empty compounds are accepted.
+    let encoded =
+        to_be_bytes(&nbt!({ "unit": { "a": 1i32 }, "marker": {}, "value": 5i32 })).unwrap();
+    assert!(from_be_bytes::<Data, _>(&mut encoded.as_slice()).is_err());
+    assert!(
+        crate::from_value::<Data>(nbt!({ "unit": 1i32, "marker": {}, "value": 5i32 })).is_err()
+    );
+}
+
+#[test]
+fn tuple_struct() {
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Pos(f64, f64, f64);
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Entity {
+        pos: Pos,
+    }
+
+    let entity = Entity {
+        pos: Pos(0.5, 64.0, -3.25),
+    };
+    let expected = nbt!({ "pos": [0.5f64, 64.0f64, -3.25f64] });
+
+    let encoded = to_be_bytes(&entity).unwrap();
+    let decoded: Entity = from_be_bytes(&mut encoded.as_slice()).unwrap();
+    assert_eq!(decoded, entity);
+
+    let encoded = to_net_bytes(&entity).unwrap();
+    let value: Value = from_net_bytes(&mut encoded.as_slice()).unwrap();
+    assert_eq!(value, expected);
+
+    assert_eq!(crate::to_value(&entity).unwrap(), expected);
+    assert_eq!(crate::from_value::<Entity>(expected).unwrap(), entity);
+
+    // The length must match.
+    let encoded = to_be_bytes(&nbt!({ "pos": [0.5f64, 64.0f64] })).unwrap();
+    assert!(from_be_bytes::<Entity, _>(&mut encoded.as_slice()).is_err());
+
+    // Tuple structs are lists, so their fields must have the same type.
+    #[derive(Serialize)]
+    struct Mixed(i32, String);
+
+    #[derive(Serialize)]
+    struct Data {
+        mixed: Mixed,
+    }
+
+    let data = Data {
+        mixed: Mixed(1, "a".to_owned()),
+    };
+    assert!(matches!(
+        to_le_bytes(&data),
+        Err(NbtError::HeterogeneousList { .. })
+    ));
+}
+
+#[test]
+fn flatten() {
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Position {
+        x: i32,
+        y: i32,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Entity {
+        id: String,
+        #[serde(flatten)]
+        pos: Position,
+        #[serde(flatten)]
+        extra: Map,
+    }
+
+    let value = nbt!({
+        "id": "zombie",
+        "x": 1i32,
+        "y": 2i32,
+        "health": 20i16,
+        "tags": ["a"],
+        "bytes": Value::ByteArray(vec![1, 2]),
+        "nested": { "x": 1i8 }
+    });
+
+    let encoded = to_be_bytes(&value).unwrap();
+    let entity: Entity = from_be_bytes(&mut encoded.as_slice()).unwrap();
+    assert_eq!(entity.id, "zombie");
+    assert_eq!(entity.pos, Position { x: 1, y: 2 });
+    assert_eq!(
+        Value::Compound(entity.extra.clone()),
+        nbt!({
+            "health": 20i16,
+            "tags": ["a"],
+            "bytes": Value::ByteArray(vec![1, 2]),
+            "nested": { "x": 1i8 }
+        })
+    );
+    assert_eq!(crate::from_value::<Entity>(value.clone()).unwrap(), entity);
+
+    // Flattened fields are written back into the same compound.
+    let encoded = to_be_bytes(&entity).unwrap();
+    let decoded: Value = from_be_bytes(&mut encoded.as_slice()).unwrap();
+    assert_eq!(decoded, value);
+
+    // Serde buffers flattened entries without their tag type, so int arrays are read as lists.
+    let encoded = to_be_bytes(&nbt!({ "id": "a", "x": 0i32, "y": 0i32, "ints": [I; 1] })).unwrap();
+    let entity: Entity = from_be_bytes(&mut encoded.as_slice()).unwrap();
+    assert_eq!(entity.extra["ints"], nbt!([1i32]));
+}
+
+#[test]
+fn read_exact() {
+    use crate::from_bytes_exact;
+
+    let value = nbt!({ "list": [1i32, 2i32] });
+    let encoded = to_net_bytes(&value).unwrap();
+    let decoded: Value =
+        from_bytes_exact::<NetworkLittleEndian, _>(&mut encoded.as_slice()).unwrap();
+    assert_eq!(decoded, value);
+
+    let mut doubled = encoded.clone();
+    doubled.extend_from_slice(&encoded);
+    let mut reader = doubled.as_slice();
+    let result = from_bytes_exact::<NetworkLittleEndian, Value>(&mut reader);
+    assert!(matches!(result, Err(NbtError::TrailingData)));
+    // Only a single byte of the trailing data is read.
+    assert_eq!(reader.len(), encoded.len() - 1);
+
+    // Errors while decoding are still reported as usual.
+    let result = from_bytes_exact::<NetworkLittleEndian, Value>(&mut &encoded[..3]);
+    assert!(result.unwrap_err().offset().is_some());
+}
+
+#[test]
+fn read_headless() {
+    use crate::{from_bytes_with_options, from_net_bytes_headless, DeserializeOptions};
+
+    let value = nbt!({ "name": "Steve", "health": 20i32 });
+    let named = to_net_bytes(&value).unwrap();
+
+    // Remove the empty root name, which is a single zero byte in the network variant.
+    let mut headless = named.clone();
+    assert_eq!(headless.remove(1), 0);
+
+    let decoded: Value = from_net_bytes_headless(&mut headless.as_slice()).unwrap();
+    assert_eq!(decoded, value);
+
+    let options = DeserializeOptions {
+        headless: true,
+        ..Default::default()
+    };
+    let encoded = to_be_bytes(&value).unwrap();
+    let mut headless = vec![encoded[0]];
+    headless.extend_from_slice(&encoded[3..]);
+    let decoded: Value =
+        from_bytes_with_options::<BigEndian, _>(&mut headless.as_slice(), &options).unwrap();
+    assert_eq!(decoded, value);
+}
+
+#[test]
+fn value_walk() {
+    let mut value = nbt!({
+        "Level": { "Sections": [{ "Y": 0i8 }, { "Y": 1i8 }] },
+        "a/b": [I; 1, 2]
+    });
+
+    let mut paths = Vec::new();
+    value.walk(|path, _| paths.push(path.to_owned()));
+    paths.sort();
+    assert_eq!(
+        paths,
+        [
+            "",
+            "/Level",
+            "/Level/Sections",
+            "/Level/Sections[0]",
+            "/Level/Sections[0]/Y",
+            "/Level/Sections[1]",
+            "/Level/Sections[1]/Y",
+            "/a~1b",
+        ]
+    );
+
+    let mut paths = Vec::new();
+    nbt!([[1i8], [2i8]]).walk(|path, _| paths.push(path.to_owned()));
+    assert_eq!(paths, ["", "[0]", "[0][0]", "[1]", "[1][0]"]);
+
+    value.walk_mut(|path, value| {
+        if let Value::Byte(y) = value {
+            assert!(path.ends_with("/Y"));
+            *y += 10;
+        }
+    });
+    assert_eq!(
+        value,
+        nbt!({
+            "Level": { "Sections": [{ "Y": 10i8 }, { "Y": 11i8 }] },
+            "a/b": [I; 1, 2]
+        })
+    );
+}
+
+#[test]
+fn value_statistics() {
+    use crate::NbtStats;
+
+    let value = nbt!({
+        "name": "Steve",
+        "pos": [0.5f64, 64.0f64, 0.5f64],
+        "Inventory": [{ "id": "minecraft:stone", "Count": 1i8 }],
+        "uuid": [I; 1, 2, 3, 4],
+        "seed": [L; 1]
+    });
+
+    let stats = value.statistics();
+    assert_eq!(stats.count(FieldType::Compound), 2);
+    assert_eq!(stats.count(FieldType::List), 2);
+    assert_eq!(stats.count(FieldType::String), 2);
+    assert_eq!(stats.count(FieldType::Double), 3);
+    assert_eq!(stats.count(FieldType::Byte), 1);
+    assert_eq!(stats.count(FieldType::IntArray), 1);
+    assert_eq!(stats.count(FieldType::LongArray), 1);
+    assert_eq!(stats.count(FieldType::End), 0);
+    assert_eq!(stats.nodes, 12);
+    assert_eq!(stats.max_depth, 3);
+    assert_eq!(stats.largest_array, 4);
+
+    assert_eq!(Value::Int(5).statistics().max_depth, 0);
+    assert_eq!(nbt!({}).statistics().max_depth, 1);
+    assert_eq!(Value::default().statistics().nodes, 1);
+    assert_ne!(Value::default().statistics(), NbtStats::default());
+}
+
+#[test]
+fn runtime_variant() {
+    use crate::{from_bytes_dyn, to_bytes_dyn, Variant};
+
+    let value = nbt!({ "name": "Steve", "pos": [I; 1, 2, 3], "seed": 123456789i64 });
+    for variant in [
+        Variant::BigEndian,
+        Variant::LittleEndian,
+        Variant::NetworkEndian,
+    ] {
+        let encoded = to_bytes_dyn(variant, &value).unwrap();
+        let decoded: Value = from_bytes_dyn(variant, &mut encoded.as_slice()).unwrap();
+        assert_eq!(decoded, value);
+    }
+
+    assert_eq!(
+        to_bytes_dyn(Variant::BigEndian, &value).unwrap(),
+        to_be_bytes(&value).unwrap()
+    );
+    assert_eq!(
+        to_bytes_dyn(Variant::LittleEndian, &value).unwrap(),
+        to_le_bytes(&value).unwrap()
+    );
+}
+
+#[test]
+fn detect_variant() {
+    use crate::{detect_variant, Variant};
+
+    let value = nbt!({
+        "Level": { "Time": 100i64, "LevelName": "world" },
+        "list": [1i32, 2i32, 3i32]
+    });
+
+    assert_eq!(
+        detect_variant(&to_be_bytes(&value).unwrap()),
+        Some(Variant::BigEndian)
+    );
+    assert_eq!(
+        detect_variant(&to_le_bytes(&value).unwrap()),
+        Some(Variant::LittleEndian)
+    );
+    assert_eq!(
+        detect_variant(&to_net_bytes(&value).unwrap()),
+        Some(Variant::NetworkEndian)
+    );
+    assert_eq!(
+        detect_variant(include_bytes!("../test/bigtest.nbt")),
+        Some(Variant::BigEndian)
+    );
+
+    // An empty root compound is the same in big and little endian.
+    let empty = to_le_bytes(&nbt!({})).unwrap();
+    assert_eq!(empty, to_be_bytes(&nbt!({})).unwrap());
+    assert_eq!(detect_variant(&empty), Some(Variant::BigEndian));
+    assert_eq!(
+        detect_variant(&to_net_bytes(&nbt!({})).unwrap()),
+        Some(Variant::NetworkEndian)
+    );
+
+    // Trailing data is not allowed.
+    let mut encoded = to_le_bytes(&value).unwrap();
+    encoded.push(0);
+    assert_eq!(detect_variant(&encoded), None);
+
+    // Tiny inputs are ambiguous.
+    assert_eq!(detect_variant(&[10, 0, 0, 0]), Some(Variant::BigEndian));
+    assert_eq!(detect_variant(&[]), None);
+}
+
+#[test]
+fn u128_long_array() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Entity {
+        #[serde(with = "crate::u128_long_array")]
+        uuid: u128,
+    }
+
+    let entity = Entity {
+        uuid: 0x0123_4567_89ab_cdef_fedc_ba98_7654_3210,
+    };
+    let encoded = to_be_bytes(&entity).unwrap();
+    let value: Value = from_be_bytes(&mut encoded.as_slice()).unwrap();
+    assert_eq!(
+        value,
+        nbt!({ "uuid": [L; 0x0123_4567_89ab_cdefi64, 0xfedc_ba98_7654_3210u64 as i64] })
+    );
+
+    let decoded: Entity = from_be_bytes(&mut encoded.as_slice()).unwrap();
+    assert_eq!(decoded, entity);
+
+    // Lists of longs are accepted, but must have two elements.
+    let decoded: Entity = crate::from_value(nbt!({ "uuid": [0i64, 5i64] })).unwrap();
+    assert_eq!(decoded.uuid, 5);
+    let encoded = to_be_bytes(&nbt!({ "uuid": [L; 1, 2, 3] })).unwrap();
+    assert!(from_be_bytes::<Entity, _>(&mut encoded.as_slice()).is_err());
+
+    // Without the module, 128-bit integers are rejected with a hint.
+    #[derive(Serialize)]
+    struct Raw {
+        uuid: u128,
+    }
+    let err = to_be_bytes(&Raw { uuid: 1 }).unwrap_err();
+    assert!(err.to_string().contains("u128_long_array"), "{err}");
+}
+
+#[test]
+fn millis_timestamp() {
+    use std::time::{Duration, SystemTime};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Level {
+        #[serde(rename = "LastPlayed", with = "crate::millis_timestamp")]
+        last_played: SystemTime,
+        #[serde(rename = "PlayTime", with = "crate::millis_timestamp")]
+        play_time: Duration,
+    }
+
+    let level = Level {
+        last_played: SystemTime::UNIX_EPOCH - Duration::from_millis(1500),
+        play_time: Duration::from_millis(123_456),
+    };
+    let encoded = to_le_bytes(&level).unwrap();
+    let value: Value = from_le_bytes(&mut encoded.as_slice()).unwrap();
+    assert_eq!(
+        value,
+        nbt!({ "LastPlayed": -1500i64, "PlayTime": 123_456i64 })
+    );
+
+    let decoded: Level = from_le_bytes(&mut encoded.as_slice()).unwrap();
+    assert_eq!(decoded, level);
+
+    // Durations cannot be negative.
+    let result = crate::from_value::<Level>(nbt!({ "LastPlayed": 0i64, "PlayTime": -1i64 }));
+    assert!(result.is_err());
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn millis_timestamp_chrono() {
+    use chrono::{DateTime, Utc};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Level {
+        #[serde(rename = "LastPlayed", with = "crate::millis_timestamp")]
+        last_played: DateTime<Utc>,
+    }
+
+    let level = Level {
+        last_played: DateTime::from_timestamp_millis(1_700_000_000_123).unwrap(),
+    };
+    let value = crate::to_value(&level).unwrap();
+    assert_eq!(value, nbt!({ "LastPlayed": 1_700_000_000_123i64 }));
+    assert_eq!(crate::from_value::<Level>(value).unwrap(), level);
+}
+
+#[test]
+fn serializer_reuse() {
+    use crate::{from_bytes_iter, Serializer};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Item {
+        id: String,
+        count: i8,
+    }
+
+    let items = [
+        Item {
+            id: "minecraft:stone".to_owned(),
+            count: 64,
+        },
+        Item {
+            id: "minecraft:dirt".to_owned(),
+            count: 1,
+        },
+    ];
+
+    let mut ser = Serializer::<_, NetworkLittleEndian>::new(Vec::new());
+    ser.set_max_depth(Some(1));
+    for item in &items {
+        ser.serialize_value(item).unwrap();
+    }
+    let encoded = ser.into_inner();
+
+    let mut expected = to_net_bytes(&items[0]).unwrap();
+    expected.extend(to_net_bytes(&items[1]).unwrap());
+    assert_eq!(encoded, expected);
+
+    let decoded: Vec<Item> = from_bytes_iter::<NetworkLittleEndian, _, _>(encoded.as_slice())
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(decoded, items);
+
+    // The settings apply to every value.
+    let mut ser = Serializer::<_, NetworkLittleEndian>::new(Vec::new());
+    ser.set_max_depth(Some(1));
+    ser.serialize_value(&items[0]).unwrap();
+    assert!(matches!(
+        ser.serialize_value(&nbt!({ "list": [1i32] })),
+        Err(NbtError::MaxDepthExceeded { max: 1 })
+    ));
+
+    // So does the root name.
+    let mut ser = Serializer::<_, NetworkLittleEndian>::new(Vec::new());
+    ser.set_root_name("Data");
+    ser.serialize_value(&items[0]).unwrap();
+    ser.serialize_value(&nbt!({ "id": 1i32 })).unwrap();
+    let encoded = ser.into_inner();
+
+    let mut reader = encoded.as_slice();
+    for _ in 0..2 {
+        let mut deserializer =
+            crate::Deserializer::<NetworkLittleEndian, _>::new(&mut reader).unwrap();
+        Value::deserialize(&mut deserializer).unwrap();
+        assert_eq!(deserializer.root_name(), "Data");
+    }
+    assert!(reader.is_empty());
+}
+
+#[test]
+fn error_clone() {
+    let mut buffer: &[u8] = &[10, 0];
+    let err = from_be_bytes::<Value, _>(&mut buffer).unwrap_err();
+
+    let cloned = err.clone();
+    assert_eq!(cloned.to_string(), err.to_string());
+    assert_eq!(cloned.offset(), err.offset());
+
+    let io = NbtError::from(std::io::Error::new(
+        std::io::ErrorKind::UnexpectedEof,
+        "stream ended",
+    ));
+    assert_eq!(io.clone().to_string(), "stream ended");
+}
+
+#[test]
+fn structured_errors() {
+    // A tuple of three ints cannot be read from a list of two.
+    let encoded = to_be_bytes(&nbt!({ "pos": [1i32, 2i32] })).unwrap();
+    let result = from_be_bytes::<std::collections::HashMap<String, (i32, i32, i32)>, _>(
+        &mut encoded.as_slice(),
+    );
+    assert!(matches!(
+        result.unwrap_err().inner(),
+        NbtError::LengthMismatch {
+            expected: 3,
+            actual: 2,
+            ty: FieldType::Int
+        }
+    ));
+
+    // A list of end tags that claims to have elements.
+    let mut buffer: &[u8] = &[10, 0, 0, 9, 0, 1, b'a', 0, 0, 0, 0, 1, 0];
+    let result = from_be_bytes::<Value, _>(&mut buffer);
+    assert!(matches!(
+        result.unwrap_err().inner(),
+        NbtError::UnmatchedEnd
+    ));
+
+    let mut buffer: &[u8] = &[10, 0, 0, 13];
+    let result = from_be_bytes::<Value, _>(&mut buffer);
+    assert!(matches!(
+        result.unwrap_err().inner(),
+        NbtError::TypeOutOfRange { actual: 13 }
+    ));
+}
+
+#[test]
+fn error_into_io() {
+    use std::io::{self, ErrorKind};
+
+    fn read(mut data: &[u8]) -> io::Result<Value> {
+        Ok(from_be_bytes(&mut data)?)
+    }
+
+    // Truncated data keeps the kind of the original IO error.
+    let err = read(&[10, 0]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+
+    let err = read(&[10, 0, 0, 13]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    let source = err.into_inner().unwrap().downcast::<NbtError>().unwrap();
+    assert!(matches!(
+        source.inner(),
+        NbtError::TypeOutOfRange { actual: 13 }
+    ));
+
+    let err = io::Error::from(NbtError::from(io::Error::from(ErrorKind::BrokenPipe)));
+    assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+}
+
+#[test]
+fn value_sort_lists() {
+    let mut value = nbt!({
+        "Enchantments": [
+            { "id": "sharpness", "lvl": 5i16 },
+            { "id": "looting", "lvl": 3i16 }
+        ],
+        "nested": [[3i32, 1i32], [2i32, 0i32]],
+        "heights": [I; 3, 1, 2]
+    });
+    value.sort_lists();
+    assert_eq!(
+        value,
+        nbt!({
+            "Enchantments": [
+                { "id": "looting", "lvl": 3i16 },
+                { "id": "sharpness", "lvl": 5i16 }
+            ],
+            "nested": [[0i32, 2i32], [1i32, 3i32]],
+            "heights": [I; 3, 1, 2]
+        })
+    );
+
+    // Sort by a single key only.
+    value.sort_lists_by(|a, b| {
+        let level = |v: &Value| v.as_compound().and_then(|c| c.get("lvl")).cloned();
+        level(b).cmp(&level(a))
+    });
+    let enchantments = value.as_compound().unwrap()["Enchantments"]
+        .as_list()
+        .unwrap();
+    assert_eq!(enchantments[0].as_compound().unwrap()["id"], "sharpness");
+}
+
+#[test]
+fn value_try_from() {
+    let n: i32 = Value::Int(5).try_into().unwrap();
+    assert_eq!(n, 5);
+
+    let s: String = nbt!("Steve").try_into().unwrap();
+    assert_eq!(s, "Steve");
+
+    let flag: bool = Value::Byte(1).try_into().unwrap();
+    assert!(flag);
+
+    let result: Result<i64, _> = Value::Int(5).try_into();
+    assert!(matches!(
+        result,
+        Err(NbtError::UnexpectedType {
+            expected: FieldType::Long,
+            actual: FieldType::Int
+        })
+    ));
+
+    let mut value = nbt!({ "heights": [I; 1, 2] });
+    let heights: &Vec<i32> = (&value.as_compound().unwrap()["heights"])
+        .try_into()
+        .unwrap();
+    assert_eq!(heights, &[1, 2]);
+
+    let map: &mut crate::Map = (&mut value).try_into().unwrap();
+    map.insert("time".to_owned(), Value::Long(100));
+    assert_eq!(value, nbt!({ "heights": [I; 1, 2], "time": 100i64 }));
+
+    fn read_time(value: Value) -> Result<i64, NbtError> {
+        let compound = crate::Map::try_from(value)?;
+        compound
+            .get("time")
+            .cloned()
+            .unwrap_or(Value::Long(0))
+            .try_into()
+    }
+    assert_eq!(read_time(nbt!({ "time": 7i64 })).unwrap(), 7);
+    assert!(read_time(Value::Int(1)).is_err());
+}
+
+#[test]
+fn value_entry() {
+    let mut value = nbt!({ "Count": 1i8 });
+
+    *value.entry("Count").unwrap().or_insert(Value::Byte(0)) = Value::Byte(5);
+    value
+        .entry("Damage")
+        .unwrap()
+        .or_insert_with(|| Value::Short(0));
+    value.entry("tag").unwrap().or_default();
+
+    assert_eq!(value, nbt!({ "Count": 5i8, "Damage": 0i16, "tag": {} }));
+
+    assert!(matches!(
+        nbt!([1i32]).entry("Count"),
+        Err(NbtError::UnexpectedType {
+            expected: FieldType::Compound,
+            actual: FieldType::List
+        })
+    ));
+}
+
+#[test]
+fn map_fields() {
+    use std::collections::{BTreeMap, HashMap};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Block {
+        name: String,
+        states: BTreeMap<String, Value>,
+        extra: HashMap<String, i32>,
+    }
+
+    let block = Block {
+        name: "minecraft:stone".to_owned(),
+        states: BTreeMap::from([
+            ("b".to_owned(), Value::Byte(1)),
+            ("a".to_owned(), nbt!({ "c": [I; 1] })),
+        ]),
+        extra: HashMap::from([("x".to_owned(), 1)]),
+    };
+    let encoded = to_le_bytes(&block).unwrap();
+    let decoded: Block = from_le_bytes(&mut encoded.as_slice()).unwrap();
+    assert_eq!(decoded, block);
+
+    // A compound with the key `a` twice: the last value wins in maps and values.
+    let mut duplicate = vec![10, 0, 0];
+    for value in [1, 2] {
+        duplicate.extend([3, 0, 1, b'a', 0, 0, 0, value]);
+    }
+    duplicate.push(0);
+
+    let map: BTreeMap<String, i32> = from_be_bytes(&mut duplicate.as_slice()).unwrap();
+    assert_eq!(map, BTreeMap::from([("a".to_owned(), 2)]));
+    let map: HashMap<String, i32> = from_be_bytes(&mut duplicate.as_slice()).unwrap();
+    assert_eq!(map, HashMap::from([("a".to_owned(), 2)]));
+    let value: Value = from_be_bytes(&mut duplicate.as_slice()).unwrap();
+    assert_eq!(value, nbt!({ "a": 2i32 }));
+
+    // Structs reject duplicate fields.
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Single {
+        a: i32,
+    }
+    let err = from_be_bytes::<Single, _>(&mut duplicate.as_slice()).unwrap_err();
+    assert!(err.to_string().contains("duplicate field"), "{err}");
+}
+
+#[cfg(feature = "preserve_order")]
+#[test]
+fn index_map_fields() {
+    use indexmap::IndexMap;
+
+    let map = IndexMap::from([
+        ("z".to_owned(), 1i32),
+        ("a".to_owned(), 2i32),
+        ("m".to_owned(), 3i32),
+    ]);
+    let encoded = to_net_bytes(&map).unwrap();
+    let decoded: IndexMap<String, i32> = from_net_bytes(&mut encoded.as_slice()).unwrap();
+    assert!(decoded.iter().eq(map.iter()));
+
+    // Duplicate keys keep the position of the first occurrence and the value of the last.
+    let mut duplicate = vec![10, 0, 0];
+    for (key, value) in [(b'a', 1), (b'b', 2), (b'a', 3)] {
+        duplicate.extend([3, 0, 1, key, 0, 0, 0, value]);
+    }
+    duplicate.push(0);
+    let decoded: IndexMap<String, i32> = from_be_bytes(&mut duplicate.as_slice()).unwrap();
+    assert_eq!(
+        decoded.into_iter().collect::<Vec<_>>(),
+        [("a".to_owned(), 3), ("b".to_owned(), 2)]
+    );
+}
+
+#[test]
+fn deny_duplicate_keys() {
+    use crate::{from_bytes_with_options, DeserializeOptions};
+
+    let options = DeserializeOptions {
+        deny_duplicate_keys: true,
+        ..Default::default()
+    };
+
+    // `{ "inner": { "a": 1, "a": 2 } }`
+    let mut duplicate = vec![10, 0, 0, 10, 0, 5];
+    duplicate.extend_from_slice(b"inner");
+    for value in [1, 2] {
+        duplicate.extend([3, 0, 1, b'a', 0, 0, 0, value]);
+    }
+    duplicate.extend([0, 0]);
+
+    let value: Value = from_be_bytes(&mut duplicate.as_slice()).unwrap();
+    assert_eq!(value, nbt!({ "inner": { "a": 2i32 } }));
+
+    let err = from_bytes_with_options::<BigEndian, Value>(&mut duplicate.as_slice(), &options)
+        .unwrap_err();
+    assert!(matches!(err.inner(), NbtError::DuplicateKey { key } if key == "a"));
+    assert_eq!(err.path(), Some("inner.a"));
+
+    // Equal keys in different compounds are allowed.
+    let value = nbt!({ "a": { "a": 1i32 }, "b": [{ "a": 1i32 }, { "a": 2i32 }] });
+    let encoded = to_be_bytes(&value).unwrap();
+    let decoded: Value =
+        from_bytes_with_options::<BigEndian, _>(&mut encoded.as_slice(), &options).unwrap();
+    assert_eq!(decoded, value);
+}
+
+#[test]
+fn stream_writer() {
+    use crate::stream::Writer;
+
+    fn write<F: crate::EndiannessImpl>() -> Vec<u8> {
+        let mut writer = Writer::<_, F>::new(Vec::new());
+        writer.begin_compound("").unwrap();
+        writer.write_byte("byte", -1).unwrap();
+        writer.write_short("short", 300).unwrap();
+        writer.write_int("int", -70000).unwrap();
+        writer.write_long("long", 1 << 40).unwrap();
+        writer.write_float("float", 0.5).unwrap();
+        writer.write_double("double", -0.25).unwrap();
+        writer.write_string("string", "Steve").unwrap();
+        writer
+            .write_value("heights", &Value::IntArray(vec![1, 2]))
+            .unwrap();
+
+        writer.begin_list("sections", FieldType::List, 2).unwrap();
+        for y in 0..2 {
+            writer.begin_list_element(FieldType::Int, 1).unwrap();
+            writer.write_list_element(&Value::Int(y)).unwrap();
+            writer.end_list().unwrap();
+        }
+        writer.end_list().unwrap();
+
+        writer.begin_list("empty", FieldType::End, 0).unwrap();
+        writer.end_list().unwrap();
+
+        writer.begin_compound("nested").unwrap();
+        writer.write_value("tag", &nbt!({ "a": [1i8] })).unwrap();
+        writer.end_compound().unwrap();
+
+        writer.end_compound().unwrap();
+        assert_eq!(writer.depth(), 0);
+        writer.finish().unwrap()
+    }
+
+    let expected = nbt!({
+        "byte": -1i8,
+        "short": 300i16,
+        "int": -70000i32,
+        "long": 1i64 << 40,
+        "float": 0.5f32,
+        "double": -0.25f64,
+        "string": "Steve",
+        "heights": [I; 1, 2],
+        "sections": [[0i32], [1i32]],
+        "empty": [],
+        "nested": { "tag": { "a": [1i8] } }
+    });
+
+    let encoded = write::<BigEndian>();
+    assert_eq!(
+        from_be_bytes::<Value, _>(&mut encoded.as_slice()).unwrap(),
+        expected
+    );
+    let encoded = write::<LittleEndian>();
+    assert_eq!(
+        from_le_bytes::<Value, _>(&mut encoded.as_slice()).unwrap(),
+        expected
+    );
+    let encoded = write::<NetworkLittleEndian>();
+    assert_eq!(
+        from_net_bytes::<Value, _>(&mut encoded.as_slice()).unwrap(),
+        expected
+    );
+}
+
+#[test]
+fn stream_writer_misuse() {
+    use crate::stream::Writer;
+
+    let new = || Writer::<_, BigEndian>::new(Vec::new());
+
+    // The root must be a compound.
+    assert!(matches!(
+        new().write_int("", 1),
+        Err(NbtError::UnexpectedType {
+            expected: FieldType::Compound,
+            actual: FieldType::Int
+        })
+    ));
+
+    // Lists must contain the announced elements.
+    let mut writer = new();
+    writer.begin_compound("").unwrap();
+    writer.begin_list("list", FieldType::Int, 2).unwrap();
+    assert!(writer.write_int("named", 1).is_err());
+    assert!(matches!(
+        writer.write_list_element(&Value::Byte(1)),
+        Err(NbtError::HeterogeneousList { .. })
+    ));
+    writer.write_list_element(&Value::Int(1)).unwrap();
+    assert!(matches!(
+        writer.end_list(),
+        Err(NbtError::LengthMismatch {
+            expected: 2,
+            actual: 1,
+            ty: FieldType::Int
+        })
+    ));
+    writer.write_list_element(&Value::Int(2)).unwrap();
+    assert!(writer.write_list_element(&Value::Int(3)).is_err());
+    assert!(writer.end_compound().is_err());
+    writer.end_list().unwrap();
+    assert!(writer.write_list_element(&Value::Int(3)).is_err());
+
+    // Everything must be ended.
+    let mut writer = new();
+    assert!(writer.end_compound().is_err());
+    writer.begin_compound("").unwrap();
+    assert!(new().finish().is_err());
+    assert!(writer.finish().is_err());
+
+    // Only one root compound is written.
+    let mut writer = new();
+    writer.begin_compound("").unwrap();
+    writer.end_compound().unwrap();
+    assert!(writer.begin_compound("").is_err());
+    assert_eq!(writer.finish().unwrap(), [10, 0, 0, 0]);
+}
+
+#[test]
+fn missing_fields_default() {
+    fn default_health() -> f32 {
+        20.0
+    }
+
+    #[derive(Debug, PartialEq, Default, Serialize, Deserialize)]
+    struct Abilities {
+        flying: bool,
+        #[serde(rename = "walkSpeed")]
+        walk_speed: f32,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Player {
+        name: String,
+        #[serde(default)]
+        score: i32,
+        #[serde(default = "default_health", rename = "Health")]
+        health: f32,
+        #[serde(default)]
+        abilities: Abilities,
+        #[serde(default)]
+        tags: Vec<String>,
+        level: Option<i32>,
+    }
+
+    let encoded = to_le_bytes(&nbt!({ "name": "Steve" })).unwrap();
+    let player: Player = from_le_bytes(&mut encoded.as_slice()).unwrap();
+    assert_eq!(
+        player,
+        Player {
+            name: "Steve".to_owned(),
+            score: 0,
+            health: 20.0,
+            abilities: Abilities::default(),
+            tags: Vec::new(),
+            level: None,
+        }
+    );
+
+    let encoded = to_le_bytes(&nbt!({
+        "name": "Alex",
+        "score": 5i32,
+        "Health": 3.5f32,
+        "abilities": { "flying": 1i8, "walkSpeed": 0.1f32 },
+        "tags": ["a"],
+        "level": 2i32
+    }))
+    .unwrap();
+    let player: Player = from_le_bytes(&mut encoded.as_slice()).unwrap();
+    assert_eq!(player.score, 5);
+    assert_eq!(player.health, 3.5);
+    assert!(player.abilities.flying);
+    assert_eq!(player.tags, ["a"]);
+    assert_eq!(player.level, Some(2));
+
+    // Struct-level defaults fill in every missing field.
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(default)]
+    struct Settings {
+        difficulty: i8,
+        hardcore: bool,
+    }
+    impl Default for Settings {
+        fn default() -> Self {
+            Settings {
+                difficulty: 2,
+                hardcore: false,
+            }
+        }
+    }
+    let encoded = to_be_bytes(&nbt!({ "hardcore": 1i8 })).unwrap();
+    let settings: Settings = from_be_bytes(&mut encoded.as_slice()).unwrap();
+    assert_eq!(
+        settings,
+        Settings {
+            difficulty: 2,
+            hardcore: true
+        }
+    );
+
+    // The same applies when converting from a `Value`.
+    let player: Player = crate::from_value(nbt!({ "name": "Steve" })).unwrap();
+    assert_eq!(player.health, 20.0);
+
+    // Fields without a default are still required.
+    let encoded = to_le_bytes(&nbt!({ "score": 1i32 })).unwrap();
+    let err = from_le_bytes::<Player, _>(&mut encoded.as_slice()).unwrap_err();
+    assert!(err.to_string().contains("missing field `name`"), "{err}");
+}
+
+#[test]
+fn numeric_lists_stay_lists() {
+    let value = nbt!({
+        "bytes": [1i8],
+        "shorts": [1i16, 2i16],
+        "ints": [1i32, 2i32, 3i32],
+        "longs": [1i64],
+        "floats": [1.0f32],
+        "array": [I; 1, 2, 3]
+    });
+
+    let encoded = to_be_bytes(&value).unwrap();
+    let decoded: Value = from_be_bytes(&mut encoded.as_slice()).unwrap();
+    assert_eq!(decoded, value);
+    let encoded = to_net_bytes(&value).unwrap();
+    let decoded: Value = from_net_bytes(&mut encoded.as_slice()).unwrap();
+    assert_eq!(decoded, value);
+
+    let compound = decoded.as_compound().unwrap();
+    assert_eq!(compound["bytes"], Value::List(vec![Value::Byte(1)]));
+    assert_eq!(
+        compound["ints"],
+        Value::List(vec![Value::Int(1), Value::Int(2), Value::Int(3)])
+    );
+    assert_eq!(compound["array"], Value::IntArray(vec![1, 2, 3]));
+
+    // Re-encoding produces lists again, byte for byte.
+    assert_eq!(
+        to_bytes_sorted::<NetworkLittleEndian>(&decoded).unwrap(),
+        to_bytes_sorted::<NetworkLittleEndian>(&value).unwrap()
+    );
+
+    let converted: Value = crate::from_value(value.clone()).unwrap();
+    assert_eq!(converted, value);
+
+    // Lists of numbers can be deserialized into arrays instead.
+    let options = crate::DeserializeOptions {
+        lists_as_arrays: true,
+        ..Default::default()
+    };
+    let value = nbt!({
+        "bytes": [1i8, -1i8],
+        "shorts": [1i16, 2i16],
+        "ints": [1i32, 2i32, 3i32],
+        "longs": [1i64],
+        "empty": Value::List(Vec::new()),
+        "nested": [[1i32], [2i32, 3i32]],
+        "array": [I; 1, 2, 3]
+    });
+    let expected = nbt!({
+        "bytes": Value::ByteArray(vec![1, 255]),
+        "shorts": [1i16, 2i16],
+        "ints": [I; 1, 2, 3],
+        "longs": [L; 1],
+        "empty": Value::List(Vec::new()),
+        "nested": [[I; 1], [I; 2, 3]],
+        "array": [I; 1, 2, 3]
+    });
+    let encoded = to_net_bytes(&value).unwrap();
+    let decoded: Value =
+        crate::from_bytes_with_options::<NetworkLittleEndian, _>(&mut encoded.as_slice(), &options)
+            .unwrap();
+    assert_eq!(decoded, expected);
+
+    let encoded = to_le_bytes(&value).unwrap();
+    let decoded: Value =
+        crate::from_bytes_with_options::<LittleEndian, _>(&mut encoded.as_slice(), &options)
+            .unwrap();
+    assert_eq!(decoded, expected);
+
+    let mut compacted = value.clone();
+    compacted.compact();
+    assert_eq!(compacted, expected);
+}
+
+#[test]
+fn value_typed_lists() {
+    let value = nbt!({
+        "pos": [0.5f64, 64.0f64, -2.0f64],
+        "tags": ["a", "b"],
+        "ints": [1i32, 2i32],
+        "array": [I; 3, 4],
+        "bytes": [B; 1, 255],
+        "mixed": [[1i32], [2i32]],
+        "empty": []
+    });
+    let compound = value.as_compound().unwrap();
+
+    assert_eq!(compound["pos"].get_f64_list(), Some(vec![0.5, 64.0, -2.0]));
+    assert_eq!(
+        compound["tags"].get_string_list(),
+        Some(vec!["a".to_owned(), "b".to_owned()])
+    );
+    assert_eq!(compound["ints"].get_i32_list(), Some(vec![1, 2]));
+    assert_eq!(compound["array"].get_i32_list(), Some(vec![3, 4]));
+    assert_eq!(compound["bytes"].get_i8_list(), Some(vec![1, -1]));
+    assert_eq!(compound["empty"].get_i64_list(), Some(vec![]));
+
+    assert_eq!(compound["ints"].get_i64_list(), None);
+    assert_eq!(compound["array"].get_i64_list(), None);
+    assert_eq!(compound["mixed"].get_i32_list(), None);
+    assert_eq!(Value::Int(1).get_i32_list(), None);
+    assert_eq!(
+        Value::List(vec![Value::Short(1), Value::Int(2)]).get_i16_list(),
+        None
+    );
+}
+
+#[test]
+fn value_array_list_conversion() {
+    let list = nbt!([1i32, 2i32, 3i32]);
+    let array = list.clone().list_to_int_array().unwrap();
+    assert_eq!(array, Value::IntArray(vec![1, 2, 3]));
+    assert_eq!(array.int_array_to_list().unwrap(), list);
+
+    let list = nbt!([1i64, -1i64]);
+    let array = list.clone().list_to_long_array().unwrap();
+    assert_eq!(array, Value::LongArray(vec![1, -1]));
+    assert_eq!(array.long_array_to_list().unwrap(), list);
+
+    assert_eq!(
+        nbt!([]).list_to_byte_array(),
+        Ok(Value::ByteArray(Vec::new()))
+    );
+
+    // Mismatches return the original value.
+    assert_eq!(nbt!([1i8]).list_to_int_array(), Err(nbt!([1i8])));
+    assert_eq!(
+        Value::IntArray(vec![1]).list_to_int_array(),
+        Err(Value::IntArray(vec![1]))
+    );
+    assert_eq!(
+        Value::IntArray(vec![1]).long_array_to_list(),
+        Err(Value::IntArray(vec![1]))
+    );
+    let mixed = Value::List(vec![Value::Long(1), Value::Int(2)]);
+    assert_eq!(mixed.clone().list_to_long_array(), Err(mixed));
+}
+
+#[test]
+fn legacy_java_strings() {
+    use crate::{from_bytes_with_options, DeserializeOptions};
+
+    // Root name "a\0", key "\u{1f600}" and value "b\0c", encoded as modified UTF-8.
+    let mut encoded = vec![10, 0, 3, b'a', 0xc0, 0x80, 8, 0, 6];
+    encoded.extend_from_slice(&[0xed, 0xa0, 0xbd, 0xed, 0xb8, 0x80]);
+    encoded.extend_from_slice(&[0, 4, b'b', 0xc0, 0x80, b'c', 0]);
+
+    assert!(from_be_bytes::<Value, _>(&mut encoded.as_slice()).is_err());
+
+    let options = DeserializeOptions {
+        legacy_java: true,
+        ..Default::default()
+    };
+    let mut reader = encoded.as_slice();
+    let mut de = crate::Deserializer::<BigEndian, _>::with_options(&mut reader, &options).unwrap();
+    assert_eq!(de.root_name(), "a\0");
+    let value = Value::deserialize(&mut de).unwrap();
+    assert_eq!(value, nbt!({ "\u{1f600}": "b\0c" }));
+
+    // Regular UTF-8 is still accepted.
+    let encoded = to_be_bytes(&nbt!({ "\u{1f600}": "é" })).unwrap();
+    let value: Value =
+        from_bytes_with_options::<BigEndian, _>(&mut encoded.as_slice(), &options).unwrap();
+    assert_eq!(value, nbt!({ "\u{1f600}": "é" }));
+
+    // Unpaired surrogates and other invalid bytes are still rejected, unless lossy.
+    let encoded = [10, 0, 0, 8, 0, 1, b'k', 0, 4, 0xed, 0xa0, 0xbd, 0xff, 0];
+    assert!(
+        from_bytes_with_options::<BigEndian, Value>(&mut encoded.as_slice(), &options).is_err()
+    );
+
+    let options = DeserializeOptions {
+        legacy_java: true,
+        lossy_utf8: true,
+        ..Default::default()
+    };
+    let value: Value =
+        from_bytes_with_options::<BigEndian, _>(&mut encoded.as_slice(), &options).unwrap();
+    assert_eq!(value, nbt!({ "k": "\u{fffd}\u{fffd}" }));
+}
+
+#[cfg(feature = "region")]
+#[test]
+fn region_file() {
+    use crate::region::RegionFile;
+    use std::io::Cursor;
+
+    let chunks = [
+        (
+            0,
+            0,
+            2,
+            crate::to_zlib_be_bytes(&nbt!({ "x": 0i32 })).unwrap(),
+        ),
+        (
+            31,
+            1,
+            1,
+            crate::to_gzip_be_bytes(&nbt!({ "x": 31i32 })).unwrap(),
+        ),
+        (5, 31, 3, to_be_bytes(&nbt!({ "x": 5i32 })).unwrap()),
+        (6, 31, 4, vec![0; 16]),
+    ];
+
+    let mut file = vec![0; 8192];
+    for (x, z, compression, data) in &chunks {
+        let index = (z * 32 + x) * 4;
+        let sector = file.len() / 4096;
+        file[index..index + 4].copy_from_slice(&((sector << 8 | 1) as u32).to_be_bytes());
+        file[4096 + index..4096 + index + 4].copy_from_slice(&1_700_000_000u32.to_be_bytes());
+
+        file.extend_from_slice(&(data.len() as u32 + 1).to_be_bytes());
+        file.push(*compression);
+        file.extend_from_slice(data);
+        file.resize(file.len().next_multiple_of(4096), 0);
+    }
+
+    let mut region = RegionFile::open(Cursor::new(file)).unwrap();
+    assert!(region.contains(31, 1));
+    assert!(!region.contains(1, 31));
+    assert_eq!(region.timestamp(0, 0), Some(1_700_000_000));
+    assert_eq!(region.timestamp(1, 0), None);
+
+    assert_eq!(region.chunk(0, 0).unwrap().unwrap(), nbt!({ "x": 0i32 }));
+    // Absolute chunk coordinates are converted to coordinates within the region.
+    assert_eq!(region.chunk(-1, 33).unwrap().unwrap(), nbt!({ "x": 31i32 }));
+    assert!(region.chunk(1, 0).is_none());
+    assert!(matches!(
+        region.chunk(6, 31),
+        Some(Err(NbtError::Unsupported(_)))
+    ));
+
+    let present: Vec<_> = region
+        .chunks()
+        .map(|(x, z, chunk)| (x, z, chunk.ok()))
+        .collect();
+    assert_eq!(
+        present,
+        [
+            (0, 0, Some(nbt!({ "x": 0i32 }))),
+            (31, 1, Some(nbt!({ "x": 31i32 }))),
+            (5, 31, Some(nbt!({ "x": 5i32 }))),
+            (6, 31, None),
+        ]
+    );
+
+    // Truncated headers are rejected.
+    assert!(RegionFile::open(Cursor::new(vec![0; 4096])).is_err());
+}
+
+#[test]
+fn value_display_tree() {
+    let value = nbt!({
+        "Level": {
+            "Tags": ["a", "b\"c"],
+        }
+    });
+    assert_eq!(
+        value.display_tree(),
+        "TAG_Compound(None): 1 entry\n\
+         {\n  \
+           TAG_Compound(\"Level\"): 1 entry\n  \
+           {\n    \
+             TAG_List(\"Tags\"): 2 entries of TAG_String\n    \
+             {\n      \
+               TAG_String(None): \"a\"\n      \
+               TAG_String(None): \"b\\\"c\"\n    \
+             }\n  \
+           }\n\
+         }\n"
+    );
+
+    assert_eq!(Value::Int(3).display_tree(), "TAG_Int(None): 3\n");
+    assert_eq!(
+        Value::LongArray(vec![1]).display_tree(),
+        "TAG_Long_Array(None): [1 long]\n"
+    );
+    assert_eq!(
+        nbt!([]).display_tree(),
+        "TAG_List(None): 0 entries of TAG_End\n{\n}\n"
+    );
+}
+
+#[test]
+fn seq_size_hint() {
+    struct Hint(Option<usize>);
+
+    impl<'de> serde::Deserialize<'de> for Hint {
+        fn deserialize<D: serde::Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+            struct HintVisitor;
+
+            impl<'de> serde::de::Visitor<'de> for HintVisitor {
+                type Value = Hint;
+
+                fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    f.write_str("a sequence")
+                }
+
+                fn visit_seq<A: serde::de::SeqAccess<'de>>(
+                    self,
+                    mut seq: A,
+                ) -> Result<Hint, A::Error> {
+                    let hint = seq.size_hint();
+                    while seq.next_element::<i32>()?.is_some() {}
+                    Ok(Hint(hint))
+                }
+            }
+
+            de.deserialize_seq(HintVisitor)
+        }
+    }
+
+    #[derive(Deserialize)]
+    struct Data {
+        list: Hint,
+    }
+
+    let encoded = to_be_bytes(&nbt!({ "list": [1i32, 2i32, 3i32] })).unwrap();
+    let data: Data = from_be_bytes(&mut encoded.as_slice()).unwrap();
+    assert_eq!(data.list.0, Some(3));
+
+    // A huge length in the data does not allocate up front.
+    let encoded = [10, 0, 0, 9, 0, 1, b'a', 10, 0x7f, 0xff, 0xff, 0xff];
+    assert!(from_be_bytes::<Value, _>(&mut encoded.as_slice()).is_err());
+}
+
+#[test]
+fn max_string_len() {
+    use crate::{from_bytes_with_options, DeserializeOptions};
+
+    let encoded = to_net_bytes(&nbt!({ "name": "a".repeat(100) })).unwrap();
+
+    let options = DeserializeOptions {
+        max_string_len: 99,
+        ..Default::default()
+    };
+    let err =
+        from_bytes_with_options::<NetworkLittleEndian, Value>(&mut encoded.as_slice(), &options)
+            .unwrap_err();
+    assert!(matches!(
+        err.inner(),
+        NbtError::StringTooLong { len: 100, max: 99 }
+    ));
+    assert_eq!(err.path(), Some("name"));
+
+    // Keys are limited as well.
+    let options = DeserializeOptions {
+        max_string_len: 3,
+        ..Default::default()
+    };
+    let err =
+        from_bytes_with_options::<NetworkLittleEndian, Value>(&mut encoded.as_slice(), &options)
+            .unwrap_err();
+    assert!(matches!(
+        err.inner(),
+        NbtError::StringTooLong { len: 4, max: 3 }
+    ));
+
+    // The default limit is far above the length of regular strings, but rejects absurd lengths.
+    assert_eq!(
+        DeserializeOptions::default().max_string_len,
+        16 * 1024 * 1024
+    );
+    let mut encoded = vec![10, 0, 8, 1, b'a'];
+    encoded.extend_from_slice(&[0x80, 0x80, 0x80, 0x80, 0x02]);
+    let err = from_net_bytes::<Value, _>(&mut encoded.as_slice()).unwrap_err();
+    assert!(matches!(err.inner(), NbtError::StringTooLong { .. }));
+}
+
+#[test]
+fn looks_like_variant() {
+    use crate::{looks_like, Variant};
+
+    let value = nbt!({ "name": "Steve" });
+    let encodings = [
+        (Variant::BigEndian, to_be_bytes(&value).unwrap()),
+        (Variant::LittleEndian, to_le_bytes(&value).unwrap()),
+        (Variant::NetworkEndian, to_net_bytes(&value).unwrap()),
+    ];
+
+    for (variant, encoded) in &encodings {
+        for (other, _) in &encodings {
+            assert_eq!(looks_like(*other, encoded), variant == other);
+        }
+    }
+
+    // Only the start of the data is inspected.
+    assert!(looks_like(Variant::BigEndian, &BIG_TEST_NBT[..32]));
+    assert!(looks_like(Variant::BigEndian, &[10, 0, 0, 0]));
+    assert!(!looks_like(Variant::NetworkEndian, &[10, 0, 0, 8, 0, 4]));
+    assert!(!looks_like(Variant::BigEndian, &[]));
+    assert!(!looks_like(Variant::BigEndian, &[8, 0, 0]));
+}
+
+#[test]
+fn seq_len_enforced() {
+    use serde::ser::SerializeSeq;
+    use std::collections::HashMap;
+
+    /// Reports a length that differs from the amount of elements it yields.
+    struct Liar {
+        len: usize,
+        items: Vec<i32>,
+    }
+
+    impl Serialize for Liar {
+        fn serialize<S: serde::Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+            let mut seq = ser.serialize_seq(Some(self.len))?;
+            for item in &self.items {
+                seq.serialize_element(item)?;
+            }
+            seq.end()
+        }
+    }
+
+    /// Writes the inner value as an int array.
+    struct AsIntArray(Liar);
+
+    impl Serialize for AsIntArray {
+        fn serialize<S: serde::Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+            ser.serialize_newtype_struct(crate::value::INT_ARRAY_TOKEN, &self.0)
+        }
+    }
+
+    let cases = [(3, vec![1, 2]), (1, vec![1, 2]), (0, vec![1]), (2, vec![])];
+    for (len, items) in cases {
+        let actual = if items.len() > len {
+            len + 1
+        } else {
+            items.len()
+        };
+        let expected_err = |err: NbtError, ty| {
+            assert!(
+                matches!(err, NbtError::LengthMismatch { expected, actual: a, ty: t } if expected == len && a == actual && t == ty),
+                "{err:?}"
+            );
+        };
+
+        let list = HashMap::from([(
+            "a",
+            Liar {
+                len,
+                items: items.clone(),
+            },
+        )]);
+        let err = to_be_bytes(&list).unwrap_err();
+        expected_err(
+            err,
+            if items.is_empty() {
+                FieldType::End
+            } else {
+                FieldType::Int
+            },
+        );
+
+        let array = HashMap::from([("a", AsIntArray(Liar { len, items }))]);
+        let err = to_net_bytes(&array).unwrap_err();
+        expected_err(err, FieldType::Int);
+    }
+
+    // Correct lengths are still accepted.
+    let array = HashMap::from([(
+        "a",
+        AsIntArray(Liar {
+            len: 2,
+            items: vec![1, 2],
+        }),
+    )]);
+    let encoded = to_be_bytes(&array).unwrap();
+    let decoded: Value = from_be_bytes(&mut encoded.as_slice()).unwrap();
+    assert_eq!(decoded, nbt!({ "a": [I; 1, 2] }));
+}
+
+#[test]
+fn cow_str_fields() {
+    use std::borrow::Cow;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Item<'a> {
+        #[serde(borrow)]
+        name: Cow<'a, str>,
+        owned: Cow<'a, str>,
+    }
+
+    let item = Item {
+        name: Cow::Borrowed("minecraft:stone"),
+        owned: Cow::Owned("block".to_owned()),
+    };
+    let encoded = to_le_bytes(&item).unwrap();
+    let decoded: Item = from_le_bytes(&mut encoded.as_slice()).unwrap();
+    assert_eq!(decoded, item);
+    assert!(matches!(decoded.name, Cow::Owned(_)));
+
+    // Strings are borrowed when reading from a slice.
+    let (decoded, rest) = crate::from_le_slice::<Item>(&encoded).unwrap();
+    assert_eq!(decoded, item);
+    assert!(matches!(decoded.name, Cow::Borrowed("minecraft:stone")));
+    assert!(matches!(decoded.owned, Cow::Owned(_)));
+    assert!(rest.is_empty());
+
+    #[derive(Deserialize, Debug)]
+    struct Borrowed<'a> {
+        name: &'a str,
+        names: Vec<&'a str>,
+    }
+
+    let encoded = to_net_bytes(&nbt!({ "name": "minecraft:stone", "names": ["a", "b"] })).unwrap();
+    let (decoded, _) = crate::from_net_slice::<Borrowed>(&encoded).unwrap();
+    assert_eq!(decoded.name, "minecraft:stone");
+    assert_eq!(decoded.names, ["a", "b"]);
+    assert!(from_net_bytes::<Borrowed, _>(&mut encoded.as_slice()).is_err());
+
+    // Invalid UTF-8 and truncated strings are still rejected.
+    #[derive(Deserialize, Debug)]
+    struct Name<'a> {
+        name: &'a str,
+    }
+
+    let mut encoded = to_be_bytes(&nbt!({ "name": "abc" })).unwrap();
+    assert_eq!(
+        crate::from_be_slice::<Name>(&encoded).unwrap().0.name,
+        "abc"
+    );
+    assert!(crate::from_be_slice::<Name>(&encoded[..encoded.len() - 2]).is_err());
+    let at = encoded.len() - 4;
+    encoded[at] = 0xff;
+    assert!(crate::from_be_slice::<Name>(&encoded).is_err());
+}
+
+#[test]
+fn value_scalar_bytes() {
+    assert_eq!(Value::Byte(-1).scalar_bytes(), Some(vec![0xff]));
+    assert_eq!(
+        Value::Int(0x01020304).scalar_bytes(),
+        Some(vec![4, 3, 2, 1])
+    );
+    assert_eq!(
+        Value::Long(-2).scalar_bytes(),
+        Some((-2i64).to_le_bytes().to_vec())
+    );
+    assert_eq!(
+        Value::Double(0.5).scalar_bytes(),
+        Some(0.5f64.to_le_bytes().to_vec())
+    );
+
+    // The bytes are the same as the payload written in the little endian variant.
+    let encoded = to_le_bytes(&nbt!({ "": 0x01020304i32 })).unwrap();
+    assert_eq!(
+        encoded[6..10],
+        Value::Int(0x01020304).scalar_bytes().unwrap()
+    );
+
+    assert_eq!(nbt!([1i32]).scalar_bytes(), None);
+    assert_eq!(Value::IntArray(vec![1]).scalar_bytes(), None);
+    assert_eq!(nbt!({}).scalar_bytes(), None);
+}
+
+#[test]
+fn ignored_fields_skipped() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Partial {
+        name: String,
+        id: i32,
+    }
+
+    let value = nbt!({
+        "before": { "nested": [[1i32, 2i32], [3i32]], "s": "string" },
+        "name": "Steve",
+        "bytes": [B; 1, 2, 3],
+        "ints": [I; 1, 2],
+        "longs": [L; 1, 2],
+        "empty": [],
+        "id": 7i32,
+        "after": [{ "a": 1.5f64 }, { "b": 2i64 }],
+        "float": 1.0f32,
+    });
+    let expected = Partial {
+        name: "Steve".to_owned(),
+        id: 7,
+    };
+
+    let be = to_be_bytes(&value).unwrap();
+    let le = to_le_bytes(&value).unwrap();
+    let net = to_net_bytes(&value).unwrap();
+    assert_eq!(
+        from_be_bytes::<Partial, _>(&mut be.as_slice()).unwrap(),
+        expected
+    );
+    assert_eq!(
+        from_le_bytes::<Partial, _>(&mut le.as_slice()).unwrap(),
+        expected
+    );
+    assert_eq!(
+        from_net_bytes::<Partial, _>(&mut net.as_slice()).unwrap(),
+        expected
+    );
+
+    // The whole input is consumed.
+    let mut reader = be.as_slice();
+    from_be_bytes::<serde::de::IgnoredAny, _>(&mut reader).unwrap();
+    assert!(reader.is_empty());
+
+    // Truncated data is still detected in skipped values.
+    let truncated = &be[..be.len() - 6];
+    assert!(from_be_bytes::<serde::de::IgnoredAny, _>(&mut &truncated[..]).is_err());
+
+    // Lists of end tags cannot have any elements.
+    let end_list = [10, 0, 0, 9, 0, 1, b'a', 0, 0x7f, 0xff, 0xff, 0xff, 0];
+    let err = from_be_bytes::<serde::de::IgnoredAny, _>(&mut end_list.as_slice()).unwrap_err();
+    assert!(matches!(err.inner(), NbtError::UnmatchedEnd), "{err}");
+}
+
+#[test]
+fn value_byte_methods() {
+    let value = nbt!({ "name": "Steve", "pos": [I; 1, 2, 3] });
+
+    let le = value.to_le_bytes().unwrap();
+    let be = value.to_be_bytes().unwrap();
+    let net = value.to_net_bytes().unwrap();
+    assert_eq!(le, to_le_bytes(&value).unwrap());
+    assert_eq!(be, to_be_bytes(&value).unwrap());
+    assert_eq!(net, to_net_bytes(&value).unwrap());
+
+    assert_eq!(Value::from_le_bytes(&le).unwrap(), value);
+    assert_eq!(Value::from_be_bytes(&be).unwrap(), value);
+    assert_eq!(Value::from_net_bytes(&net).unwrap(), value);
+
+    assert!(Value::from_be_bytes(&le).is_err());
+}
+
+#[test]
+fn value_min_max_element() {
+    let list = nbt!([3.5f64, -1.0f64, 2.0f64]);
+    assert_eq!(list.min_element(), Some(Value::Double(-1.0)));
+    assert_eq!(list.max_element(), Some(Value::Double(3.5)));
+
+    let strings = nbt!(["b", "c", "a"]);
+    assert_eq!(strings.min_element(), Some(Value::String("a".to_owned())));
+    assert_eq!(strings.max_element(), Some(Value::String("c".to_owned())));
+
+    // Byte array elements are compared as signed bytes.
+    let bytes = Value::ByteArray(vec![1, 255, 127]);
+    assert_eq!(bytes.min_element(), Some(Value::Byte(-1)));
+    assert_eq!(bytes.max_element(), Some(Value::Byte(127)));
+
+    let longs = Value::LongArray(vec![5, i64::MIN, i64::MAX]);
+    assert_eq!(longs.min_element(), Some(Value::Long(i64::MIN)));
+    assert_eq!(longs.max_element(), Some(Value::Long(i64::MAX)));
+
+    assert_eq!(Value::IntArray(Vec::new()).min_element(), None);
+    assert_eq!(Value::Int(1).max_element(), None);
+    assert_eq!(nbt!({ "a": 1i32 }).max_element(), None);
+}
+
+#[test]
+fn value_coerce_numeric() {
+    let coerce = |mut value: Value, target| value.coerce_numeric(target).map(|_| value);
+
+    // Widening.
+    assert_eq!(
+        coerce(Value::Byte(-5), FieldType::Int).unwrap(),
+        Value::Int(-5)
+    );
+    assert_eq!(
+        coerce(Value::Short(300), FieldType::Long).unwrap(),
+        Value::Long(300)
+    );
+    assert_eq!(
+        coerce(Value::Int(3), FieldType::Double).unwrap(),
+        Value::Double(3.0)
+    );
+    assert_eq!(
+        coerce(Value::Float(0.5), FieldType::Double).unwrap(),
+        Value::Double(0.5)
+    );
+
+    // Narrowing with overflow checks.
+    assert_eq!(
+        coerce(Value::Int(127), FieldType::Byte).unwrap(),
+        Value::Byte(127)
+    );
+    assert!(coerce(Value::Int(128), FieldType::Byte).is_err());
+    assert!(coerce(Value::Long(i64::MAX), FieldType::Int).is_err());
+    assert_eq!(
+        coerce(Value::Double(0.25), FieldType::Float).unwrap(),
+        Value::Float(0.25)
+    );
+    assert!(coerce(Value::Double(1e300), FieldType::Float).is_err());
+    assert!(coerce(Value::Double(f64::INFINITY), FieldType::Float).is_ok());
+
+    // Floating point values must be whole numbers to become integers.
+    assert_eq!(
+        coerce(Value::Double(42.0), FieldType::Short).unwrap(),
+        Value::Short(42)
+    );
+    assert!(coerce(Value::Float(1.5), FieldType::Int).is_err());
+    assert!(coerce(Value::Double(f64::NAN), FieldType::Long).is_err());
+    assert!(coerce(Value::Double(9.3e18), FieldType::Long).is_err());
+
+    // Failed conversions leave the value unchanged.
+    let mut value = Value::Int(1000);
+    assert!(value.coerce_numeric(FieldType::Byte).is_err());
+    assert_eq!(value, Value::Int(1000));
+
+    assert!(matches!(
+        coerce(Value::String("1".to_owned()), FieldType::Int),
+        Err(NbtError::UnexpectedType {
+            expected: FieldType::Int,
+            actual: FieldType::String
+        })
+    ));
+    assert!(matches!(
+        coerce(Value::Int(1), FieldType::String),
+        Err(NbtError::Unsupported(_))
+    ));
+}
+
+#[test]
+fn renamed_root_names() {
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    #[serde(rename = "Level")]
+    struct Level {
+        seed: i64,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    #[serde(rename = "")]
+    struct Unnamed {
+        seed: i64,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Plain {
+        seed: i64,
+    }
+
+    // The root name is taken from the struct name, including renames.
+    let encoded = to_be_bytes(&Level { seed: 1 }).unwrap();
+    assert_eq!(encoded[..8], [10, 0, 5, b'L', b'e', b'v', b'e', b'l']);
+    let (name, decoded) = from_bytes_named::<BigEndian, Level>(&mut encoded.as_slice()).unwrap();
+    assert_eq!(name, "Level");
+    assert_eq!(decoded, Level { seed: 1 });
+
+    let encoded = to_net_bytes(&Unnamed { seed: 1 }).unwrap();
+    assert_eq!(encoded[..3], [10, 0, 4]);
+    let (name, decoded) =
+        from_bytes_named::<NetworkLittleEndian, Unnamed>(&mut encoded.as_slice()).unwrap();
+    assert_eq!(name, "");
+    assert_eq!(decoded, Unnamed { seed: 1 });
+
+    let encoded = to_le_bytes(&Plain { seed: 1 }).unwrap();
+    let (name, _) = from_bytes_named::<LittleEndian, Plain>(&mut encoded.as_slice()).unwrap();
+    assert_eq!(name, "Plain");
+
+    // The root name is not checked when reading, so the names are interchangeable.
+    let encoded = to_be_bytes(&Level { seed: 2 }).unwrap();
+    let decoded: Unnamed = from_be_bytes(&mut encoded.as_slice()).unwrap();
+    assert_eq!(decoded, Unnamed { seed: 2 });
+
+    // An explicit root name takes precedence over the struct name.
+    let encoded = to_bytes_named::<BigEndian>("Data", &Level { seed: 3 }).unwrap();
+    let (name, _) = from_bytes_named::<BigEndian, Level>(&mut encoded.as_slice()).unwrap();
+    assert_eq!(name, "Data");
+}
+
+#[test]
+fn struct_root_name_serialized() {
+    use crate::{to_bytes_sorted, to_bytes_with_options, SerializeOptions, Serializer};
+
+    #[derive(Serialize)]
+    #[serde(rename = "hello world")]
+    struct HelloWorld {
+        name: String,
+    }
+
+    let value = HelloWorld {
+        name: "Bananrama".to_owned(),
+    };
+
+    // Every way of serializing a struct writes its name.
+    assert_eq!(to_be_bytes(&value).unwrap(), HELLO_WORLD_NBT);
+    assert_eq!(
+        to_bytes_sorted::<BigEndian>(&value).unwrap(),
+        HELLO_WORLD_NBT
+    );
+    assert_eq!(
+        to_bytes_with_options::<BigEndian>(&value, &SerializeOptions::default()).unwrap(),
+        HELLO_WORLD_NBT
+    );
+
+    let mut ser = Serializer::<_, BigEndian>::new(Vec::new());
+    ser.serialize_value(&value).unwrap();
+    ser.serialize_value(&value).unwrap();
+    assert_eq!(
+        ser.into_inner(),
+        [HELLO_WORLD_NBT, HELLO_WORLD_NBT].concat()
+    );
+
+    // Serde serializes structs with flattened fields as maps, which do not have a name.
+    #[derive(Serialize)]
+    #[serde(rename = "hello world")]
+    struct Flattened {
+        #[serde(flatten)]
+        inner: HelloWorld,
+    }
+
+    let flattened = Flattened { inner: value };
+    let encoded = to_be_bytes(&flattened).unwrap();
+    let (name, _) = from_bytes_named::<BigEndian, Value>(&mut encoded.as_slice()).unwrap();
+    assert_eq!(name, "");
+
+    let encoded = to_bytes_named::<BigEndian>("hello world", &flattened).unwrap();
+    assert_eq!(encoded, HELLO_WORLD_NBT);
+}
@@ -0,0 +1,101 @@
+//! Tests for the readers and writers used without the `std` feature.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    from_be_slice, from_bytes, from_bytes_exact, from_bytes_iter, from_le_slice, from_net_slice,
+    nbt, to_be_bytes, to_be_bytes_in, to_le_bytes, to_net_bytes, BigEndian, NbtError,
+    NetworkLittleEndian, Value,
+};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Player {
+    name: String,
+    health: f32,
+    position: (f64, f64, f64),
+    inventory: Vec<i16>,
+    seed: i64,
+}
+
+fn player() -> Player {
+    Player {
+        name: "Steve".to_owned(),
+        health: 20.0,
+        position: (1.5, -64.0, 300.25),
+        inventory: vec![1, -2, 3],
+        seed: -1_234_567_890_123,
+    }
+}
+
+#[test]
+fn no_std_round_trip() {
+    let encoded = to_be_bytes(&player()).unwrap();
+    let (decoded, rest) = from_be_slice::<Player>(&encoded).unwrap();
+    assert_eq!(decoded, player());
+    assert!(rest.is_empty());
+
+    let encoded = to_le_bytes(&player()).unwrap();
+    let (decoded, rest) = from_le_slice::<Player>(&encoded).unwrap();
+    assert_eq!(decoded, player());
+    assert!(rest.is_empty());
+
+    let encoded = to_net_bytes(&player()).unwrap();
+    let (decoded, rest) = from_net_slice::<Player>(&encoded).unwrap();
+    assert_eq!(decoded, player());
+    assert!(rest.is_empty());
+}
+
+#[test]
+fn no_std_varints() {
+    let encoded = to_net_bytes(&nbt!({ "a": -1i32, "b": 300i32 })).unwrap();
+    let (decoded, _) = from_net_slice::<Value>(&encoded).unwrap();
+    assert_eq!(decoded, nbt!({ "a": -1i32, "b": 300i32 }));
+
+    // Keys are written in sorted order, since the map is a `BTreeMap`.
+    assert_eq!(
+        encoded,
+        [0x0a, 0x00, 0x03, 0x01, b'a', 0x01, 0x03, 0x01, b'b', 0xd8, 0x04, 0x00]
+    );
+}
+
+#[test]
+fn no_std_slice_writer() {
+    let mut buf = [0u8; 64];
+    let mut writer = &mut buf[..];
+    let written = to_be_bytes_in(&mut writer, &nbt!({ "a": 1i32 })).unwrap();
+    assert_eq!(
+        &buf[..written],
+        [0x0a, 0x00, 0x00, 0x03, 0x00, 0x01, b'a', 0x00, 0x00, 0x00, 0x01, 0x00]
+    );
+
+    let mut small = [0u8; 4];
+    let mut writer = &mut small[..];
+    assert!(to_be_bytes_in(&mut writer, &nbt!({ "a": 1i32 })).is_err());
+}
+
+#[test]
+fn no_std_reader() {
+    let encoded = to_be_bytes(&player()).unwrap();
+
+    let mut reader = &encoded[..encoded.len() - 1];
+    let err = from_bytes::<BigEndian, Player>(&mut reader).unwrap_err();
+    assert!(matches!(err.inner(), NbtError::ByteError(_)));
+
+    let mut trailing = encoded.clone();
+    trailing.extend_from_slice(&[1, 2, 3]);
+    let mut reader = trailing.as_slice();
+    assert!(matches!(
+        from_bytes_exact::<BigEndian, Player>(&mut reader),
+        Err(NbtError::TrailingData)
+    ));
+
+    let stream = [
+        to_net_bytes(&nbt!({ "a": 1i32 })).unwrap(),
+        to_net_bytes(&nbt!({ "a": 2i32 })).unwrap(),
+    ]
+    .concat();
+    let values = from_bytes_iter::<NetworkLittleEndian, Value, _>(stream.as_slice())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(values, [nbt!({ "a": 1i32 }), nbt!({ "a": 2i32 })]);
+}
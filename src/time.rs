@@ -0,0 +1,121 @@
+//! Modules for use with `#[serde(with = "...")]` that store times as longs.
+
+/// Serializes a point in time or a duration as a long containing milliseconds.
+///
+/// Minecraft stores timestamps such as `LastPlayed` as the amount of milliseconds since the Unix epoch.
+/// This module can be used for any type that implements [`Millis`](millis_timestamp::Millis):
+///
+/// - [`SystemTime`](std::time::SystemTime), as milliseconds since the Unix epoch. Times before the epoch are negative.
+///   This requires the `std` feature.
+/// - [`Duration`](std::time::Duration), as milliseconds. Negative durations are rejected when deserializing.
+/// - `chrono::DateTime<Utc>`, as milliseconds since the Unix epoch. This requires the `chrono` feature.
+///
+/// Precision below a millisecond is discarded when serializing.
+///
+/// # Example
+///
+/// ```rust
+/// # use std::time::{Duration, SystemTime};
+/// # use serde::{Deserialize, Serialize};
+/// # use nbtx::{nbt, Value};
+/// #[derive(Serialize, Deserialize)]
+/// struct Level {
+///     #[serde(rename = "LastPlayed", with = "nbtx::millis_timestamp")]
+///     last_played: SystemTime,
+///     #[serde(rename = "PlayTime", with = "nbtx::millis_timestamp")]
+///     play_time: Duration,
+/// }
+///
+/// let level = Level {
+///     last_played: SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+///     play_time: Duration::from_secs(90),
+/// };
+/// let value = nbtx::to_value(&level).unwrap();
+/// assert_eq!(value, nbt!({ "LastPlayed": 1_700_000_000_000i64, "PlayTime": 90_000i64 }));
+/// ```
+pub mod millis_timestamp {
+    use core::time::Duration;
+    #[cfg(feature = "std")]
+    use std::time::SystemTime;
+
+    use serde::de::{Error as _, Unexpected};
+    use serde::ser::Error as _;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    /// A type that can be converted to and from an amount of milliseconds.
+    pub trait Millis: Sized {
+        /// Returns the amount of milliseconds, or `None` if it does not fit in an `i64`.
+        fn to_millis(&self) -> Option<i64>;
+
+        /// Creates a value from an amount of milliseconds, or returns `None` if it is out of range.
+        fn from_millis(millis: i64) -> Option<Self>;
+    }
+
+    #[cfg(feature = "std")]
+    impl Millis for SystemTime {
+        fn to_millis(&self) -> Option<i64> {
+            match self.duration_since(SystemTime::UNIX_EPOCH) {
+                Ok(since) => since.to_millis(),
+                Err(err) => err.duration().to_millis().map(|millis| -millis),
+            }
+        }
+
+        fn from_millis(millis: i64) -> Option<Self> {
+            let duration = Duration::from_millis(millis.unsigned_abs());
+            if millis < 0 {
+                SystemTime::UNIX_EPOCH.checked_sub(duration)
+            } else {
+                SystemTime::UNIX_EPOCH.checked_add(duration)
+            }
+        }
+    }
+
+    impl Millis for Duration {
+        #[inline]
+        fn to_millis(&self) -> Option<i64> {
+            i64::try_from(self.as_millis()).ok()
+        }
+
+        #[inline]
+        fn from_millis(millis: i64) -> Option<Self> {
+            u64::try_from(millis).ok().map(Duration::from_millis)
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    impl Millis for chrono::DateTime<chrono::Utc> {
+        #[inline]
+        fn to_millis(&self) -> Option<i64> {
+            Some(self.timestamp_millis())
+        }
+
+        #[inline]
+        fn from_millis(millis: i64) -> Option<Self> {
+            chrono::DateTime::from_timestamp_millis(millis)
+        }
+    }
+
+    /// Serializes the value as a long containing milliseconds.
+    pub fn serialize<T, S>(v: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Millis,
+        S: Serializer,
+    {
+        let millis = v
+            .to_millis()
+            .ok_or_else(|| S::Error::custom("time does not fit in a long of milliseconds"))?;
+        serializer.serialize_i64(millis)
+    }
+
+    /// Deserializes a long containing milliseconds.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Millis,
+        D: Deserializer<'de>,
+    {
+        let millis = i64::deserialize(deserializer)?;
+        T::from_millis(millis).ok_or_else(|| {
+            D::Error::invalid_value(Unexpected::Signed(millis), &"a time in milliseconds")
+        })
+    }
+}
@@ -0,0 +1,99 @@
+use alloc::format;
+use alloc::string::String;
+use core::fmt::Write;
+
+use crate::{FieldType, Value};
+
+impl Value {
+    /// Formats this value as an indented tree with the tag type of every value.
+    ///
+    /// This is the format traditionally used by NBT tools and the NBT specification. Unlike SNBT,
+    /// it cannot be parsed back and is only meant for inspecting data. Values without a name, such as
+    /// the root and list elements, are shown with the name `None`. Compound entries are listed in the order
+    /// of the [`Map`](crate::Map), and the contents of arrays are summarised by their length.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use nbtx::nbt;
+    /// let value = nbt!({ "Level": { "xPos": 3i32, "Tags": ["a", "b"], "Heights": [I; 64, 64] } });
+    /// println!("{}", value.display_tree());
+    /// ```
+    ///
+    /// prints
+    ///
+    /// ```text
+    /// TAG_Compound(None): 1 entry
+    /// {
+    ///   TAG_Compound("Level"): 3 entries
+    ///   {
+    ///     TAG_Int("xPos"): 3
+    ///     TAG_List("Tags"): 2 entries of TAG_String
+    ///     {
+    ///       TAG_String(None): "a"
+    ///       TAG_String(None): "b"
+    ///     }
+    ///     TAG_Int_Array("Heights"): [2 ints]
+    ///   }
+    /// }
+    /// ```
+    pub fn display_tree(&self) -> String {
+        let mut out = String::new();
+        write_tree(&mut out, None, self, 0);
+        out
+    }
+}
+
+/// Returns `"1 <singular>"` or `"<n> <plural>"`.
+fn count(n: usize, singular: &str, plural: &str) -> String {
+    if n == 1 {
+        format!("1 {singular}")
+    } else {
+        format!("{n} {plural}")
+    }
+}
+
+fn write_tree(out: &mut String, name: Option<&str>, value: &Value, depth: usize) {
+    let indent = "  ".repeat(depth);
+    let _ = write!(out, "{indent}{}(", value.field_type().name());
+    match name {
+        Some(name) => {
+            let _ = write!(out, "{name:?}");
+        }
+        None => out.push_str("None"),
+    }
+    out.push_str("): ");
+
+    let _ = match value {
+        Value::Byte(v) => writeln!(out, "{v}"),
+        Value::Short(v) => writeln!(out, "{v}"),
+        Value::Int(v) => writeln!(out, "{v}"),
+        Value::Long(v) => writeln!(out, "{v}"),
+        Value::Float(v) => writeln!(out, "{v}"),
+        Value::Double(v) => writeln!(out, "{v}"),
+        Value::String(v) => writeln!(out, "{v:?}"),
+        Value::ByteArray(v) => writeln!(out, "[{}]", count(v.len(), "byte", "bytes")),
+        Value::IntArray(v) => writeln!(out, "[{}]", count(v.len(), "int", "ints")),
+        Value::LongArray(v) => writeln!(out, "[{}]", count(v.len(), "long", "longs")),
+        Value::List(list) => {
+            let ty = list.first().map_or(FieldType::End, Value::field_type);
+            let _ = writeln!(
+                out,
+                "{} of {}\n{indent}{{",
+                count(list.len(), "entry", "entries"),
+                ty.name()
+            );
+            for element in list {
+                write_tree(out, None, element, depth + 1);
+            }
+            writeln!(out, "{indent}}}")
+        }
+        Value::Compound(map) => {
+            let _ = writeln!(out, "{}\n{indent}{{", count(map.len(), "entry", "entries"));
+            for (key, value) in map {
+                write_tree(out, Some(key), value, depth + 1);
+            }
+            writeln!(out, "{indent}}}")
+        }
+    };
+}
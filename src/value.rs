@@ -1,15 +1,91 @@
+use alloc::borrow::{Cow, ToOwned};
+#[cfg(not(feature = "std"))]
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+#[cfg(feature = "std")]
 use std::collections::HashMap;
-use std::fmt;
-use std::hash::{Hash, Hasher};
 
-use serde::de::{MapAccess, SeqAccess, Visitor};
+use core::marker::PhantomData;
+
+use serde::de::{
+    DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess, Unexpected, VariantAccess,
+    Visitor,
+};
 use serde::ser::{SerializeMap, SerializeSeq};
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
+use crate::{EndiannessImpl, FieldType, NbtError, Variant};
+
+/// Key-value map used by [`Value::Compound`].
+///
+/// This is a [`HashMap`] by default. When the `preserve_order` feature is enabled, this is an
+/// [`IndexMap`](https://docs.rs/indexmap/latest/indexmap/map/struct.IndexMap.html) instead, which keeps the keys in insertion order.
+/// This makes it possible to re-encode data in exactly the same order as it was read.
+#[cfg(all(feature = "std", not(feature = "preserve_order")))]
+pub type Map = HashMap<String, Value>;
+
+/// Key-value map used by [`Value::Compound`].
+///
+/// Without the `std` feature, this is a [`BTreeMap`], which keeps the keys in sorted order.
+#[cfg(not(feature = "std"))]
+pub type Map = BTreeMap<String, Value>;
+
+/// Key-value map used by [`Value::Compound`].
+///
+/// This is a [`HashMap`](std::collections::HashMap) by default. When the `preserve_order` feature
+/// is enabled, this is an [`IndexMap`](indexmap::IndexMap) instead, which keeps the keys in insertion order.
+/// This makes it possible to re-encode data in exactly the same order as it was read.
+#[cfg(feature = "preserve_order")]
+pub type Map = indexmap::IndexMap<String, Value>;
+
+/// Entry of a [`Map`], as returned by [`Value::entry`].
+///
+/// This is the entry type of [`HashMap`], or of
+/// [`IndexMap`](https://docs.rs/indexmap/latest/indexmap/map/struct.IndexMap.html) when the
+/// `preserve_order` feature is enabled.
+#[cfg(all(feature = "std", not(feature = "preserve_order")))]
+pub type Entry<'a> = std::collections::hash_map::Entry<'a, String, Value>;
+
+/// Entry of a [`Map`], as returned by [`Value::entry`].
+///
+/// Without the `std` feature, this is the entry type of [`BTreeMap`].
+#[cfg(not(feature = "std"))]
+pub type Entry<'a> = alloc::collections::btree_map::Entry<'a, String, Value>;
+
+/// Entry of a [`Map`], as returned by [`Value::entry`].
+///
+/// This is the entry type of [`HashMap`](std::collections::HashMap), or of [`IndexMap`](indexmap::IndexMap)
+/// when the `preserve_order` feature is enabled.
+#[cfg(feature = "preserve_order")]
+pub type Entry<'a> = indexmap::map::Entry<'a, String, Value>;
+
+/// Newtype struct name used by [`Value`] to request array type information from the deserializer.
+pub(crate) const VALUE_TOKEN: &str = "$nbtx::private::Value";
+/// Newtype struct name used to mark a sequence as an [`IntArray`](Value::IntArray).
+pub(crate) const INT_ARRAY_TOKEN: &str = "$nbtx::private::IntArray";
+/// Newtype struct name used to mark a sequence as a [`LongArray`](Value::LongArray).
+pub(crate) const LONG_ARRAY_TOKEN: &str = "$nbtx::private::LongArray";
+
 /// General NBT value type that can represent any value.
 ///
 /// In case the structure of some piece of NBT data is not known, this
 /// type can be used to deserialise it.
+///
+/// # Equality and ordering
+///
+/// Values implement [`Eq`] and [`Ord`], so they can be used as keys in a [`BTreeMap`](std::collections::BTreeMap)
+/// and lists of values can be sorted. Values of different types are ordered by their [`discriminant`](Self::discriminant).
+///
+/// [`Float`](Self::Float) and [`Double`](Self::Double) are compared using [`f32::total_cmp`] and [`f64::total_cmp`].
+/// This ordering is total but differs from IEEE 754 comparisons: `NaN` is equal to itself and `-0.0` is less than `0.0`.
+///
+/// Compounds are compared as if their entries were sorted by key.
 #[derive(Debug, Clone)]
 pub enum Value {
     /// A signed byte.
@@ -26,95 +102,1290 @@ pub enum Value {
     Double(f64),
     /// A byte array.
     ///
-    /// This type is not used when deserialising due to issues with `serde`.
+    /// Byte arrays are preserved when deserialising and serialising.
     /// In case you are defining your own types, you can use [`serde_bytes`](https://crates.io/crates/serde_bytes)
     /// to make use of the byte array type.
     ByteArray(Vec<u8>),
     /// A UTF-8 string.
     String(String),
     /// List of an arbitrary NBT value.
+    ///
+    /// When deserialising, every element is read as the variant of the list's element type, so a list of ints
+    /// always contains [`Int`](Self::Int)s, regardless of how many elements it has. By default, lists are never
+    /// converted into arrays, even if they only contain numbers, so that they are written back as lists.
+    /// Use [`compact`](Self::compact) or [`DeserializeOptions::lists_as_arrays`](crate::DeserializeOptions::lists_as_arrays)
+    /// to convert lists of numbers into arrays.
     List(Vec<Value>),
     /// Key-value map.
-    Compound(HashMap<String, Value>),
+    Compound(Map),
     /// An array of integers.
+    ///
+    /// This is distinct from a [`List`](Value::List) of [`Int`](Value::Int)s and is preserved
+    /// when deserialising and serialising.
     IntArray(Vec<i32>),
     /// An array of longs.
+    ///
+    /// This is distinct from a [`List`](Value::List) of [`Long`](Value::Long)s and is preserved
+    /// when deserialising and serialising.
     LongArray(Vec<i64>),
 }
 
-impl Value {
+impl Value {
+    /// Returns the NBT tag type of this value.
+    #[inline]
+    pub fn field_type(&self) -> FieldType {
+        match self {
+            Self::Byte(_) => FieldType::Byte,
+            Self::Short(_) => FieldType::Short,
+            Self::Int(_) => FieldType::Int,
+            Self::Long(_) => FieldType::Long,
+            Self::Float(_) => FieldType::Float,
+            Self::Double(_) => FieldType::Double,
+            Self::ByteArray(_) => FieldType::ByteArray,
+            Self::String(_) => FieldType::String,
+            Self::List(_) => FieldType::List,
+            Self::Compound(_) => FieldType::Compound,
+            Self::IntArray(_) => FieldType::IntArray,
+            Self::LongArray(_) => FieldType::LongArray,
+        }
+    }
+
+    /// Returns a human-readable name of the tag type of this value, such as `"Byte"` or `"IntArray"`.
+    #[inline]
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Self::Byte(_) => "Byte",
+            Self::Short(_) => "Short",
+            Self::Int(_) => "Int",
+            Self::Long(_) => "Long",
+            Self::Float(_) => "Float",
+            Self::Double(_) => "Double",
+            Self::ByteArray(_) => "ByteArray",
+            Self::String(_) => "String",
+            Self::List(_) => "List",
+            Self::Compound(_) => "Compound",
+            Self::IntArray(_) => "IntArray",
+            Self::LongArray(_) => "LongArray",
+        }
+    }
+
+    /// Returns the raw NBT tag ID of this value.
+    ///
+    /// This is the same as `self.field_type() as u8`.
+    #[inline]
+    pub fn discriminant(&self) -> u8 {
+        match self {
+            Self::Byte(_) => 1,
+            Self::Short(_) => 2,
+            Self::Int(_) => 3,
+            Self::Long(_) => 4,
+            Self::Float(_) => 5,
+            Self::Double(_) => 6,
+            Self::ByteArray(_) => 7,
+            Self::String(_) => 8,
+            Self::List(_) => 9,
+            Self::Compound(_) => 10,
+            Self::IntArray(_) => 11,
+            Self::LongArray(_) => 12,
+        }
+    }
+}
+
+macro_rules! impl_access_fns {
+    ($($tag: ident = $ty: ty),+) => {
+        $(paste::paste! {
+            #[inline]
+            #[doc = concat!(
+                "Returns the inner value if the tag is of a, ", stringify!($tag), ", otherwise returns self.
+                This method is the same as [`as_", stringify!([<$tag:snake>]), "`](Self::as_", stringify!([<$tag:snake>]), ") but instead takes ownership of the value."
+            )]
+            pub fn [<into_ $tag:snake>](self) -> Result<$ty, Self> {
+                match self {
+                    Self::$tag(val) => Ok(val),
+                    _ => Err(self)
+                }
+            }
+
+            #[inline]
+            #[doc = concat!(
+                "Returns a reference to the inner value of the tag is the requested type is present.
+                Use [`into_", stringify!([<$tag:snake>]), "`](Self::into_", stringify!([<$tag:snake>]), ")."
+            )]
+            pub fn [<as_ $tag:snake>](&self) -> Option<&$ty> {
+                match self {
+                    Self::$tag(val) => Some(val),
+                    _ => None
+                }
+            }
+
+            #[inline]
+            #[doc = concat!(
+                "Returns whether the inner value is of type `", stringify!($tag), "`."
+            )]
+            pub fn [<is_ $tag:snake>](&self) -> bool {
+                matches!(self, Self::$tag(_))
+            }
+        })+
+    }
+}
+
+macro_rules! impl_list_fns {
+    ($($name: ident: $tag: ident = $ty: ty $(, $array: ident => $convert: expr)?);+) => {
+        $(
+            #[doc = concat!(
+                "Returns the elements of a [`List`](Self::List) of [`", stringify!($tag), "`](Self::",
+                stringify!($tag), ")s as a `Vec<", stringify!($ty), ">`.\n\n",
+                "Returns `None` if this is not a list or if any element is of a different type.",
+                $(" A [`", stringify!($array), "`](Self::", stringify!($array), ") is accepted as well.",)?
+            )]
+            pub fn $name(&self) -> Option<Vec<$ty>> {
+                match self {
+                    Self::List(list) => list
+                        .iter()
+                        .map(|v| match v {
+                            Self::$tag(v) => Some(v.clone()),
+                            _ => None,
+                        })
+                        .collect(),
+                    $(Self::$array(array) => Some(array.iter().map($convert).collect()),)?
+                    _ => None,
+                }
+            }
+        )+
+    }
+}
+
+impl Value {
+    impl_list_fns!(
+        get_i8_list: Byte = i8, ByteArray => |v| *v as i8;
+        get_i16_list: Short = i16;
+        get_i32_list: Int = i32, IntArray => |v| *v;
+        get_i64_list: Long = i64, LongArray => |v| *v;
+        get_f32_list: Float = f32;
+        get_f64_list: Double = f64;
+        get_string_list: String = String
+    );
+
+    impl_access_fns!(
+        Byte = i8,
+        Short = i16,
+        Int = i32,
+        Long = i64,
+        Float = f32,
+        Double = f64,
+        String = String,
+        List = Vec<Self>,
+        Compound = Map,
+        ByteArray = Vec<u8>,
+        IntArray = Vec<i32>,
+        LongArray = Vec<i64>
+    );
+
+    /// Returns the value as an `i64` if it is any integer type.
+    ///
+    /// Unlike [`as_long`](Self::as_long), this also accepts [`Byte`](Self::Byte),
+    /// [`Short`](Self::Short) and [`Int`](Self::Int), which is useful when a field is stored
+    /// with different types across versions.
+    #[inline]
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Self::Byte(v) => Some(*v as i64),
+            Self::Short(v) => Some(*v as i64),
+            Self::Int(v) => Some(*v as i64),
+            Self::Long(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as an `f64` if it is any numeric type.
+    ///
+    /// This accepts [`Float`](Self::Float) and [`Double`](Self::Double) as well as all integer types.
+    /// Large [`Long`](Self::Long) values may lose precision.
+    #[inline]
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Float(v) => Some(*v as f64),
+            Self::Double(v) => Some(*v),
+            _ => self.as_i64().map(|v| v as f64),
+        }
+    }
+
+    /// Returns the little endian bytes of the value if it is a number.
+    ///
+    /// This is the encoding used by [`LittleEndian`](crate::LittleEndian), which is useful to show the
+    /// underlying representation of a value. Strings, lists, compounds and arrays return `None`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use nbtx::Value;
+    /// assert_eq!(Value::Short(0x1234).scalar_bytes(), Some(vec![0x34, 0x12]));
+    /// assert_eq!(Value::Float(1.0).scalar_bytes(), Some(vec![0x00, 0x00, 0x80, 0x3f]));
+    /// assert_eq!(Value::String("a".to_owned()).scalar_bytes(), None);
+    /// ```
+    pub fn scalar_bytes(&self) -> Option<Vec<u8>> {
+        Some(match self {
+            Self::Byte(v) => v.to_le_bytes().to_vec(),
+            Self::Short(v) => v.to_le_bytes().to_vec(),
+            Self::Int(v) => v.to_le_bytes().to_vec(),
+            Self::Long(v) => v.to_le_bytes().to_vec(),
+            Self::Float(v) => v.to_le_bytes().to_vec(),
+            Self::Double(v) => v.to_le_bytes().to_vec(),
+            _ => return None,
+        })
+    }
+
+    /// Converts a numeric value to the given numeric type in place, such as a [`Byte`](Self::Byte) to an [`Int`](Self::Int).
+    ///
+    /// This is meant for migrating data between versions in which the type of a field changed. Integers can be
+    /// converted to any integer type that can hold their value, and to floating point types, which may lose
+    /// precision for large [`Long`](Self::Long)s. Floating point values can only be converted to an integer type
+    /// if they are whole numbers within its range, and a [`Double`](Self::Double) can only be converted to a
+    /// [`Float`](Self::Float) if it does not overflow. Values that cannot be converted are left unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NbtError::UnexpectedType`] if this value is not numeric, [`NbtError::Unsupported`] if the target
+    /// type is not numeric and [`NbtError::Other`] if the value does not fit in the target type.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use nbtx::{FieldType, Value};
+    /// let mut value = Value::Byte(1);
+    /// value.coerce_numeric(FieldType::Int).unwrap();
+    /// assert_eq!(value, Value::Int(1));
+    ///
+    /// let mut value = Value::Int(1000);
+    /// assert!(value.coerce_numeric(FieldType::Byte).is_err());
+    /// assert_eq!(value, Value::Int(1000));
+    /// ```
+    pub fn coerce_numeric(&mut self, target: FieldType) -> Result<(), NbtError> {
+        if !matches!(
+            target,
+            FieldType::Byte
+                | FieldType::Short
+                | FieldType::Int
+                | FieldType::Long
+                | FieldType::Float
+                | FieldType::Double
+        ) {
+            return Err(NbtError::Unsupported(
+                "Values can only be coerced to numeric types",
+            ));
+        }
+
+        let out_of_range = || {
+            NbtError::Other(Cow::Owned(format!(
+                "{self} cannot be represented as {}",
+                target.name()
+            )))
+        };
+
+        // Integers are kept as integers, so that large longs do not lose precision.
+        let (int, float) = match *self {
+            Value::Byte(v) => (Some(v as i64), v as f64),
+            Value::Short(v) => (Some(v as i64), v as f64),
+            Value::Int(v) => (Some(v as i64), v as f64),
+            Value::Long(v) => (Some(v), v as f64),
+            Value::Float(v) => (None, v as f64),
+            Value::Double(v) => (None, v),
+            _ => {
+                return Err(NbtError::UnexpectedType {
+                    expected: target,
+                    actual: self.field_type(),
+                })
+            }
+        };
+
+        // Whole floating point numbers can become integers. `i64::MAX as f64` is 2^63, which is just out of range.
+        let int = match int {
+            Some(int) => Some(int),
+            None if float % 1.0 == 0.0 && float >= i64::MIN as f64 && float < i64::MAX as f64 => {
+                Some(float as i64)
+            }
+            None => None,
+        };
+
+        let value = match target {
+            FieldType::Byte => Value::Byte(
+                int.and_then(|v| v.try_into().ok())
+                    .ok_or_else(out_of_range)?,
+            ),
+            FieldType::Short => Value::Short(
+                int.and_then(|v| v.try_into().ok())
+                    .ok_or_else(out_of_range)?,
+            ),
+            FieldType::Int => Value::Int(
+                int.and_then(|v| v.try_into().ok())
+                    .ok_or_else(out_of_range)?,
+            ),
+            FieldType::Long => Value::Long(int.ok_or_else(out_of_range)?),
+            FieldType::Float => {
+                // Longs are converted directly, since rounding twice can give a different result.
+                let v = match *self {
+                    Value::Long(v) => v as f32,
+                    _ => float as f32,
+                };
+                if v.is_infinite() && float.is_finite() {
+                    return Err(out_of_range());
+                }
+                Value::Float(v)
+            }
+            FieldType::Double => Value::Double(float),
+            _ => unreachable!(),
+        };
+
+        *self = value;
+        Ok(())
+    }
+
+    /// Returns the value as a `bool` if it is a [`Byte`](Self::Byte), in the same way as booleans are deserialized.
+    ///
+    /// NBT does not have a boolean type, so any non-zero byte is `true`.
+    #[inline]
+    pub fn as_bool(&self) -> Option<bool> {
+        self.as_byte().map(|v| *v != 0)
+    }
+
+    /// Deep-merges `other` into this value.
+    ///
+    /// If both values are [`Compound`](Self::Compound)s, every entry of `other` is merged into the
+    /// entry with the same key, or inserted if no such entry exists. Entries that only exist in `self` are kept.
+    /// In all other cases, including when the tags differ, `other` replaces `self`.
+    /// Lists and arrays are replaced as a whole rather than merged element-wise.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use nbtx::nbt;
+    /// let mut base = nbt!({ "facing": "north", "properties": { "lit": 0i8, "level": 1 } });
+    /// base.merge(nbt!({ "properties": { "lit": 1i8 } }));
+    ///
+    /// assert_eq!(base, nbt!({ "facing": "north", "properties": { "lit": 1i8, "level": 1 } }));
+    /// ```
+    pub fn merge(&mut self, other: Value) {
+        match (self, other) {
+            (Self::Compound(base), Self::Compound(other)) => {
+                for (key, value) in other {
+                    match base.get_mut(&key) {
+                        Some(entry) => entry.merge(value),
+                        None => {
+                            base.insert(key, value);
+                        }
+                    }
+                }
+            }
+            (this, other) => *this = other,
+        }
+    }
+
+    /// Returns the result of deep-merging `other` into this value, see [`merge`](Self::merge).
+    #[inline]
+    #[must_use]
+    pub fn merged(mut self, other: Value) -> Value {
+        self.merge(other);
+        self
+    }
+
+    /// Returns an iterator over the entries of a [`Compound`](Self::Compound).
+    ///
+    /// Returns `None` if the value is not a compound.
+    #[inline]
+    pub fn entries(&self) -> Option<impl Iterator<Item = (&String, &Value)>> {
+        self.as_compound().map(|map| map.iter())
+    }
+
+    /// Returns an iterator over the entries of a [`Compound`](Self::Compound) that allows modifying the values.
+    ///
+    /// Returns `None` if the value is not a compound.
+    #[inline]
+    pub fn entries_mut(&mut self) -> Option<impl Iterator<Item = (&String, &mut Value)>> {
+        match self {
+            Self::Compound(map) => Some(map.iter_mut()),
+            _ => None,
+        }
+    }
+
+    /// Returns an iterator over the elements of a [`List`](Self::List).
+    ///
+    /// Returns `None` if the value is not a list. The elements of the typed arrays are not [`Value`]s,
+    /// use [`as_byte_array`](Self::as_byte_array), [`as_int_array`](Self::as_int_array) or
+    /// [`as_long_array`](Self::as_long_array) to access those.
+    #[inline]
+    pub fn iter(&self) -> Option<impl Iterator<Item = &Value>> {
+        self.as_list().map(|list| list.iter())
+    }
+
+    /// Returns an iterator over the elements of a [`List`](Self::List) that allows modifying them.
+    ///
+    /// Returns `None` if the value is not a list.
+    #[inline]
+    pub fn iter_mut(&mut self) -> Option<impl Iterator<Item = &mut Value>> {
+        match self {
+            Self::List(list) => Some(list.iter_mut()),
+            _ => None,
+        }
+    }
+
+    /// Returns the amount of elements in a list or array, or the amount of entries in a compound.
+    ///
+    /// Returns `None` rather than `0` for scalars, including strings.
+    #[inline]
+    pub fn len(&self) -> Option<usize> {
+        match self {
+            Self::ByteArray(v) => Some(v.len()),
+            Self::List(v) => Some(v.len()),
+            Self::Compound(v) => Some(v.len()),
+            Self::IntArray(v) => Some(v.len()),
+            Self::LongArray(v) => Some(v.len()),
+            _ => None,
+        }
+    }
+
+    /// Returns whether this is a list, array or compound without any elements.
+    ///
+    /// Scalars, including strings, are never empty, see [`len`](Self::len).
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == Some(0)
+    }
+
+    /// Returns whether this is a [`Compound`](Self::Compound) containing the given key.
+    ///
+    /// Returns `false` if the value is not a compound.
+    #[inline]
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.as_compound().is_some_and(|map| map.contains_key(key))
+    }
+
+    /// Inserts an entry into a [`Compound`](Self::Compound), returning the previous value of the key.
+    ///
+    /// If the value is not a compound, nothing is inserted and `None` is returned.
+    #[inline]
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<Value>) -> Option<Value> {
+        match self {
+            Self::Compound(map) => map.insert(key.into(), value.into()),
+            _ => None,
+        }
+    }
+
+    /// Removes an entry from a [`Compound`](Self::Compound), returning its value.
+    ///
+    /// When the `preserve_order` feature is enabled, the order of the remaining entries is preserved.
+    /// Returns `None` if the key does not exist or the value is not a compound.
+    #[inline]
+    pub fn remove(&mut self, key: &str) -> Option<Value> {
+        match self {
+            #[cfg(not(feature = "preserve_order"))]
+            Self::Compound(map) => map.remove(key),
+            #[cfg(feature = "preserve_order")]
+            Self::Compound(map) => map.shift_remove(key),
+            _ => None,
+        }
+    }
+
+    /// Retains only the entries of a [`Compound`](Self::Compound) for which the predicate returns `true`.
+    ///
+    /// Values that are not compounds are left unchanged.
+    #[inline]
+    pub fn retain_compound(&mut self, mut f: impl FnMut(&str, &Value) -> bool) {
+        if let Self::Compound(map) = self {
+            map.retain(|k, v| f(k, v));
+        }
+    }
+
+    /// Retains only the elements of a [`List`](Self::List) for which the predicate returns `true`.
+    ///
+    /// Values that are not lists, including the typed arrays, are left unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use nbtx::nbt;
+    /// let mut palette = nbt!([{ "name": "minecraft:air" }, { "name": "minecraft:stone" }]);
+    /// palette.retain_list(|block| block.as_compound().and_then(|b| b.get("name")) != Some(&nbt!("minecraft:air")));
+    ///
+    /// assert_eq!(palette, nbt!([{ "name": "minecraft:stone" }]));
+    /// ```
+    #[inline]
+    pub fn retain_list(&mut self, f: impl FnMut(&Value) -> bool) {
+        if let Self::List(list) = self {
+            list.retain(f);
+        }
+    }
+
+    /// Takes the value out, leaving an empty [`Compound`](Self::Compound) in its place.
+    ///
+    /// This is the same as [`core::mem::take`], see the [`Default`] implementation. An empty
+    /// compound does not allocate, so this is cheap.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use nbtx::{nbt, Value};
+    /// let mut value = nbt!({ "inventory": [1i8, 2i8] });
+    /// let Value::Compound(map) = &mut value else { unreachable!() };
+    /// let inventory = map.get_mut("inventory").unwrap().take();
+    ///
+    /// assert_eq!(inventory, nbt!([1i8, 2i8]));
+    /// assert_eq!(value, nbt!({ "inventory": {} }));
+    /// ```
+    #[inline]
+    pub fn take(&mut self) -> Value {
+        core::mem::take(self)
+    }
+
+    /// Returns the UUID stored in this value, if it is an [`IntArray`](Self::IntArray) of length 4.
+    ///
+    /// This is how modern versions of Minecraft: Java Edition store UUIDs. The first int holds the
+    /// most significant 32 bits of the UUID and the last int the least significant ones, so the ints are
+    /// in big endian order regardless of the NBT variant. Legacy data stores UUIDs as a pair of longs
+    /// instead, such as `UUIDMost` and `UUIDLeast`, which can be combined as `(most as u128) << 64 | least as u64 as u128`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use nbtx::Value;
+    /// let uuid = 0x069a79f4_44e9_4726_a5be_fca90e38aaf5;
+    /// let value = Value::from_uuid(uuid);
+    ///
+    /// assert_eq!(value, Value::IntArray(vec![0x069a79f4, 0x44e94726, 0xa5befca9_u32 as i32, 0x0e38aaf5]));
+    /// assert_eq!(value.as_uuid(), Some(uuid));
+    /// ```
+    pub fn as_uuid(&self) -> Option<u128> {
+        match self.as_int_array()?.as_slice() {
+            &[a, b, c, d] => Some(
+                (a as u32 as u128) << 96
+                    | (b as u32 as u128) << 64
+                    | (c as u32 as u128) << 32
+                    | d as u32 as u128,
+            ),
+            _ => None,
+        }
+    }
+
+    /// Creates an [`IntArray`](Self::IntArray) of length 4 that stores the given UUID,
+    /// see [`as_uuid`](Self::as_uuid) for the layout.
+    pub fn from_uuid(uuid: u128) -> Value {
+        Value::IntArray(vec![
+            (uuid >> 96) as i32,
+            (uuid >> 64) as i32,
+            (uuid >> 32) as i32,
+            uuid as i32,
+        ])
+    }
+
+    /// Converts lists of bytes, ints or longs into the corresponding array, in this value and all values it contains.
+    ///
+    /// Such lists use considerably more memory than arrays, since every element is a separate [`Value`].
+    /// Lists of other types, lists that contain different types and empty lists are left unchanged.
+    /// Note that this changes how the values are serialized, as arrays are written as array tags.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use nbtx::{nbt, Value};
+    /// let mut value = nbt!({ "heights": [1i32, 2i32, 3i32], "names": ["a", "b"] });
+    /// value.compact();
+    ///
+    /// assert_eq!(value, nbt!({ "heights": Value::IntArray(vec![1, 2, 3]), "names": ["a", "b"] }));
+    /// ```
+    pub fn compact(&mut self) {
+        match self {
+            Value::Compound(map) => map.values_mut().for_each(Value::compact),
+            Value::List(list) => {
+                list.iter_mut().for_each(Value::compact);
+
+                let compacted = match list.first() {
+                    Some(Value::Byte(_)) => list
+                        .iter()
+                        .map(|v| v.as_byte().map(|v| *v as u8))
+                        .collect::<Option<_>>()
+                        .map(Value::ByteArray),
+                    Some(Value::Int(_)) => list
+                        .iter()
+                        .map(|v| v.as_int().copied())
+                        .collect::<Option<_>>()
+                        .map(Value::IntArray),
+                    Some(Value::Long(_)) => list
+                        .iter()
+                        .map(|v| v.as_long().copied())
+                        .collect::<Option<_>>()
+                        .map(Value::LongArray),
+                    _ => None,
+                };
+
+                if let Some(compacted) = compacted {
+                    *self = compacted;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the entry of a compound with the given key, for in-place insertion or modification.
+    ///
+    /// This works the same as [`HashMap::entry`]. Only compounds have entries, so
+    /// [`NbtError::UnexpectedType`] is returned for any other value rather than replacing it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use nbtx::{nbt, Value};
+    /// let mut player = nbt!({ "deaths": 2i32 });
+    ///
+    /// for key in ["deaths", "kills"] {
+    ///     player
+    ///         .entry(key)
+    ///         .unwrap()
+    ///         .and_modify(|v| *v = Value::Int(v.as_int().unwrap() + 1))
+    ///         .or_insert(Value::Int(1));
+    /// }
+    ///
+    /// assert_eq!(player, nbt!({ "deaths": 3i32, "kills": 1i32 }));
+    /// assert!(Value::Int(1).entry("deaths").is_err());
+    /// ```
+    pub fn entry(&mut self, key: impl Into<String>) -> Result<Entry<'_>, NbtError> {
+        match self {
+            Value::Compound(map) => Ok(map.entry(key.into())),
+            value => Err(NbtError::UnexpectedType {
+                expected: FieldType::Compound,
+                actual: value.field_type(),
+            }),
+        }
+    }
+
+    /// Converts a [`List`](Self::List) of [`Byte`](Self::Byte)s into a [`ByteArray`](Self::ByteArray).
+    ///
+    /// Unlike [`compact`](Self::compact), this only converts this value itself. If this is not a list of bytes,
+    /// the value is returned unchanged as the error. Empty lists are converted into empty arrays.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use nbtx::{nbt, Value};
+    /// assert_eq!(nbt!([1i8, -1i8]).list_to_byte_array(), Ok(Value::ByteArray(vec![1, 255])));
+    /// assert_eq!(nbt!([1i32]).list_to_byte_array(), Err(nbt!([1i32])));
+    /// ```
+    pub fn list_to_byte_array(self) -> Result<Value, Value> {
+        match self.is_list().then(|| self.get_i8_list()).flatten() {
+            Some(list) => Ok(Value::ByteArray(
+                list.into_iter().map(|v| v as u8).collect(),
+            )),
+            None => Err(self),
+        }
+    }
+
+    /// Converts a [`List`](Self::List) of [`Int`](Self::Int)s into an [`IntArray`](Self::IntArray).
+    ///
+    /// See [`list_to_byte_array`](Self::list_to_byte_array) for details.
+    pub fn list_to_int_array(self) -> Result<Value, Value> {
+        match self.is_list().then(|| self.get_i32_list()).flatten() {
+            Some(list) => Ok(Value::IntArray(list)),
+            None => Err(self),
+        }
+    }
+
+    /// Converts a [`List`](Self::List) of [`Long`](Self::Long)s into a [`LongArray`](Self::LongArray).
+    ///
+    /// See [`list_to_byte_array`](Self::list_to_byte_array) for details.
+    pub fn list_to_long_array(self) -> Result<Value, Value> {
+        match self.is_list().then(|| self.get_i64_list()).flatten() {
+            Some(list) => Ok(Value::LongArray(list)),
+            None => Err(self),
+        }
+    }
+
+    /// Converts a [`ByteArray`](Self::ByteArray) into a [`List`](Self::List) of [`Byte`](Self::Byte)s.
+    ///
+    /// If this is not a byte array, the value is returned unchanged as the error.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use nbtx::{nbt, Value};
+    /// assert_eq!(Value::ByteArray(vec![1, 255]).byte_array_to_list(), Ok(nbt!([1i8, -1i8])));
+    /// assert_eq!(Value::Int(1).byte_array_to_list(), Err(Value::Int(1)));
+    /// ```
+    pub fn byte_array_to_list(self) -> Result<Value, Value> {
+        match self {
+            Value::ByteArray(array) => {
+                Ok(array.into_iter().map(|v| Value::Byte(v as i8)).collect())
+            }
+            value => Err(value),
+        }
+    }
+
+    /// Converts an [`IntArray`](Self::IntArray) into a [`List`](Self::List) of [`Int`](Self::Int)s.
+    ///
+    /// If this is not an int array, the value is returned unchanged as the error.
+    pub fn int_array_to_list(self) -> Result<Value, Value> {
+        match self {
+            Value::IntArray(array) => Ok(array.into_iter().map(Value::Int).collect()),
+            value => Err(value),
+        }
+    }
+
+    /// Converts a [`LongArray`](Self::LongArray) into a [`List`](Self::List) of [`Long`](Self::Long)s.
+    ///
+    /// If this is not a long array, the value is returned unchanged as the error.
+    pub fn long_array_to_list(self) -> Result<Value, Value> {
+        match self {
+            Value::LongArray(array) => Ok(array.into_iter().map(Value::Long).collect()),
+            value => Err(value),
+        }
+    }
+
+    /// Returns the smallest element of a list or array, according to the [`Ord`] implementation of [`Value`].
+    ///
+    /// Array elements are returned as the corresponding value, so the elements of a [`ByteArray`](Self::ByteArray)
+    /// are compared as signed [`Byte`](Self::Byte)s. Returns `None` if this is not a list or array, or if it is empty.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use nbtx::{nbt, Value};
+    /// let heights = nbt!([I; 64, 72, 63]);
+    /// assert_eq!(heights.min_element(), Some(Value::Int(63)));
+    /// assert_eq!(heights.max_element(), Some(Value::Int(72)));
+    ///
+    /// assert_eq!(nbt!([]).max_element(), None);
+    /// ```
+    pub fn min_element(&self) -> Option<Value> {
+        match self {
+            Value::List(list) => list.iter().min().cloned(),
+            Value::ByteArray(array) => array.iter().map(|&v| v as i8).min().map(Value::Byte),
+            Value::IntArray(array) => array.iter().copied().min().map(Value::Int),
+            Value::LongArray(array) => array.iter().copied().min().map(Value::Long),
+            _ => None,
+        }
+    }
+
+    /// Returns the largest element of a list or array, according to the [`Ord`] implementation of [`Value`].
+    ///
+    /// See [`min_element`](Self::min_element) for details.
+    pub fn max_element(&self) -> Option<Value> {
+        match self {
+            Value::List(list) => list.iter().max().cloned(),
+            Value::ByteArray(array) => array.iter().map(|&v| v as i8).max().map(Value::Byte),
+            Value::IntArray(array) => array.iter().copied().max().map(Value::Int),
+            Value::LongArray(array) => array.iter().copied().max().map(Value::Long),
+            _ => None,
+        }
+    }
+
+    /// Sorts every list in this value and all values it contains, using the [`Ord`] implementation of [`Value`].
+    ///
+    /// This is useful to compare values in which the order of lists is irrelevant, such as lists of enchantments.
+    /// See [`sort_lists_by`](Self::sort_lists_by) to use a different order.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use nbtx::nbt;
+    /// let mut a = nbt!({ "Enchantments": [{ "id": "sharpness" }, { "id": "looting" }] });
+    /// let mut b = nbt!({ "Enchantments": [{ "id": "looting" }, { "id": "sharpness" }] });
+    /// a.sort_lists();
+    /// b.sort_lists();
+    ///
+    /// assert_eq!(a, b);
+    /// ```
+    #[inline]
+    pub fn sort_lists(&mut self) {
+        self.sort_lists_by(Value::cmp);
+    }
+
+    /// Sorts every list in this value and all values it contains, using the given comparator.
+    ///
+    /// Lists are sorted after the lists they contain, so the comparator sees elements whose lists are already sorted.
+    /// The sort is stable. Arrays and the entries of compounds are left unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use nbtx::nbt;
+    /// let mut value = nbt!({ "scores": [3i32, 1i32, 2i32], "heights": [I; 3, 1, 2] });
+    /// value.sort_lists_by(|a, b| b.cmp(a));
+    ///
+    /// assert_eq!(value, nbt!({ "scores": [3i32, 2i32, 1i32], "heights": [I; 3, 1, 2] }));
+    /// ```
+    #[inline]
+    pub fn sort_lists_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&Value, &Value) -> Ordering,
+    {
+        self.sort_lists_with(&mut compare);
+    }
+
+    /// Implementation of [`sort_lists_by`](Self::sort_lists_by), which borrows the comparator for recursion.
+    fn sort_lists_with<F>(&mut self, compare: &mut F)
+    where
+        F: FnMut(&Value, &Value) -> Ordering,
+    {
+        match self {
+            Value::Compound(map) => map.values_mut().for_each(|v| v.sort_lists_with(compare)),
+            Value::List(list) => {
+                list.iter_mut().for_each(|v| v.sort_lists_with(compare));
+                list.sort_by(|a, b| compare(a, b));
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns a mutable reference to the value at the given path, creating missing compounds along the way.
+    ///
+    /// The path is a JSON pointer such as `/Level/GameRules/doDaylightCycle`, the same format as used by
+    /// [`diff_paths`](Self::diff_paths). Segments index into compounds by key and into lists by index.
+    /// The empty path refers to the value itself.
+    ///
+    /// Missing compound entries are created as empty compounds, including the last one, so that it can be
+    /// assigned to directly. Existing values are never overwritten: if a segment refers to a value that is not
+    /// a compound or list, [`NbtError::UnexpectedType`] is returned. An error is also returned for list indices
+    /// that are out of bounds, as lists cannot contain gaps.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use nbtx::{nbt, Value};
+    /// let mut level = nbt!({ "Level": { "Time": 100i64 } });
+    /// *level.get_path_mut("/Level/GameRules/doDaylightCycle").unwrap() = nbt!("false");
+    ///
+    /// assert_eq!(level, nbt!({
+    ///     "Level": {
+    ///         "Time": 100i64,
+    ///         "GameRules": { "doDaylightCycle": "false" }
+    ///     }
+    /// }));
+    ///
+    /// // `Time` is not a compound.
+    /// assert!(level.get_path_mut("/Level/Time/Ticks").is_err());
+    /// ```
+    pub fn get_path_mut(&mut self, path: &str) -> Result<&mut Value, NbtError> {
+        if path.is_empty() {
+            return Ok(self);
+        }
+
+        let Some(path) = path.strip_prefix('/') else {
+            return Err(NbtError::Other(Cow::Owned(format!(
+                "Path `{path}` must be empty or start with `/`"
+            ))));
+        };
+
+        let mut value = self;
+        for segment in path.split('/') {
+            let segment = segment.replace("~1", "/").replace("~0", "~");
+            value = match value {
+                Value::Compound(map) => map.entry(segment).or_default(),
+                Value::List(list) => {
+                    let len = list.len();
+                    segment
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|i| list.get_mut(i))
+                        .ok_or_else(|| {
+                            NbtError::Other(Cow::Owned(format!(
+                                "Index `{segment}` is out of bounds for a list of length {len}"
+                            )))
+                        })?
+                }
+                value => {
+                    return Err(NbtError::UnexpectedType {
+                        expected: FieldType::Compound,
+                        actual: value.field_type(),
+                    })
+                }
+            };
+        }
+
+        Ok(value)
+    }
+
+    /// Serializes this value in the given format, writing the entries of compounds in sorted key order.
+    ///
+    /// Values that are equal always produce the same bytes, regardless of the iteration order of their compounds,
+    /// which makes this suitable for hashing or deduplicating values by content. The output is only canonical
+    /// within a single format: the same value produces different bytes in each [`EndiannessImpl`].
+    ///
+    /// This is the same as [`to_bytes_sorted`](crate::to_bytes_sorted), and fails in the same cases,
+    /// such as when a list contains elements of different types.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use nbtx::{nbt, LittleEndian};
+    /// let a = nbt!({ "x": 1i32, "y": 2i32, "z": 3i32 });
+    /// let b = nbt!({ "z": 3i32, "y": 2i32, "x": 1i32 });
+    ///
+    /// assert_eq!(a.canonical_bytes::<LittleEndian>().unwrap(), b.canonical_bytes::<LittleEndian>().unwrap());
+    /// ```
+    #[inline]
+    pub fn canonical_bytes<F>(&self) -> Result<Vec<u8>, NbtError>
+    where
+        F: EndiannessImpl,
+    {
+        crate::to_bytes_sorted::<F>(self)
+    }
+
+    /// Serializes this value in little endian format, see [`to_le_bytes`](crate::to_le_bytes).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use nbtx::{nbt, Value};
+    /// let value = nbt!({ "name": "Steve" });
+    ///
+    /// let encoded = value.to_le_bytes().unwrap();
+    /// assert_eq!(Value::from_le_bytes(&encoded).unwrap(), value);
+    /// ```
+    #[inline]
+    pub fn to_le_bytes(&self) -> Result<Vec<u8>, NbtError> {
+        crate::to_le_bytes(self)
+    }
+
+    /// Serializes this value in big endian format, see [`to_be_bytes`](crate::to_be_bytes).
+    #[inline]
+    pub fn to_be_bytes(&self) -> Result<Vec<u8>, NbtError> {
+        crate::to_be_bytes(self)
+    }
+
+    /// Serializes this value in network little endian format, see [`to_net_bytes`](crate::to_net_bytes).
+    #[inline]
+    pub fn to_net_bytes(&self) -> Result<Vec<u8>, NbtError> {
+        crate::to_net_bytes(self)
+    }
+
+    /// Reads a value in little endian format from the start of the given data, see [`from_le_bytes`](crate::from_le_bytes).
+    ///
+    /// Any data after the root compound is ignored. Use [`from_bytes_exact`](crate::from_bytes_exact)
+    /// to reject it instead.
+    #[inline]
+    pub fn from_le_bytes(mut data: &[u8]) -> Result<Value, NbtError> {
+        crate::from_le_bytes(&mut data)
+    }
+
+    /// Reads a value in big endian format from the start of the given data, see [`from_be_bytes`](crate::from_be_bytes).
+    ///
+    /// Any data after the root compound is ignored, as with [`from_le_bytes`](Self::from_le_bytes).
+    #[inline]
+    pub fn from_be_bytes(mut data: &[u8]) -> Result<Value, NbtError> {
+        crate::from_be_bytes(&mut data)
+    }
+
+    /// Reads a value in network little endian format from the start of the given data,
+    /// see [`from_net_bytes`](crate::from_net_bytes).
+    ///
+    /// Any data after the root compound is ignored, as with [`from_le_bytes`](Self::from_le_bytes).
+    #[inline]
+    pub fn from_net_bytes(mut data: &[u8]) -> Result<Value, NbtError> {
+        crate::from_net_bytes(&mut data)
+    }
+
+    /// Returns the amount of bytes that this value is serialized to in the given format,
+    /// without serializing it.
+    ///
+    /// The result is equal to the length of the output of [`to_bytes`](crate::to_bytes).
+    /// A compound is written as the root compound, including its type and an empty name.
+    /// Other values are written without a header, just like they are written inside a compound.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use nbtx::{nbt, BigEndian, NetworkLittleEndian};
+    /// let value = nbt!({ "name": "Steve", "health": 20i32 });
+    ///
+    /// let len = value.serialized_len::<BigEndian>();
+    /// assert_eq!(len, nbtx::to_bytes::<BigEndian>(&value).unwrap().len());
+    ///
+    /// let len = value.serialized_len::<NetworkLittleEndian>();
+    /// assert_eq!(len, nbtx::to_net_bytes(&value).unwrap().len());
+    /// ```
+    pub fn serialized_len<F>(&self) -> usize
+    where
+        F: EndiannessImpl,
+    {
+        match self {
+            // The root compound has a type and a name.
+            Value::Compound(_) => 1 + string_len::<F>("") + self.payload_len::<F>(),
+            _ => self.payload_len::<F>(),
+        }
+    }
+
+    /// Returns the amount of bytes that the value is written as, excluding its type.
+    fn payload_len<F>(&self) -> usize
+    where
+        F: EndiannessImpl,
+    {
+        let is_network = F::AS_ENUM == Variant::NetworkEndian;
+
+        match self {
+            Value::Byte(_) => 1,
+            Value::Short(_) => 2,
+            Value::Int(v) if is_network => varint_len(zigzag(*v as i64)),
+            Value::Long(v) if is_network => varint_len(zigzag(*v)),
+            Value::Int(_) | Value::Float(_) => 4,
+            Value::Long(_) | Value::Double(_) => 8,
+            Value::ByteArray(v) => seq_len_len::<F>(v.len()) + v.len(),
+            Value::String(v) => string_len::<F>(v),
+            Value::List(v) => {
+                1 + seq_len_len::<F>(v.len()) + v.iter().map(Value::payload_len::<F>).sum::<usize>()
+            }
+            Value::Compound(v) => {
+                v.iter()
+                    .map(|(k, v)| 1 + string_len::<F>(k) + v.payload_len::<F>())
+                    .sum::<usize>()
+                    + 1
+            }
+            Value::IntArray(v) if is_network => {
+                seq_len_len::<F>(v.len())
+                    + v.iter()
+                        .map(|v| varint_len(zigzag(*v as i64)))
+                        .sum::<usize>()
+            }
+            Value::LongArray(v) if is_network => {
+                seq_len_len::<F>(v.len()) + v.iter().map(|v| varint_len(zigzag(*v))).sum::<usize>()
+            }
+            Value::IntArray(v) => seq_len_len::<F>(v.len()) + v.len() * 4,
+            Value::LongArray(v) => seq_len_len::<F>(v.len()) + v.len() * 8,
+        }
+    }
+}
+
+/// Encodes a signed integer using zigzag encoding, as done by the network format.
+#[inline]
+fn zigzag(v: i64) -> u64 {
+    ((v << 1) ^ (v >> 63)) as u64
+}
+
+/// Returns the amount of bytes that a variable-length integer is written as.
+#[inline]
+fn varint_len(v: u64) -> usize {
+    (64 - (v | 1).leading_zeros() as usize).div_ceil(7)
+}
+
+/// Returns the amount of bytes that a length-prefixed string is written as.
+#[inline]
+fn string_len<F>(v: &str) -> usize
+where
+    F: EndiannessImpl,
+{
+    match F::AS_ENUM {
+        Variant::BigEndian | Variant::LittleEndian => 2 + v.len(),
+        Variant::NetworkEndian => varint_len(v.len() as u64) + v.len(),
+    }
+}
+
+/// Returns the amount of bytes that the length prefix of a list or array is written as.
+#[inline]
+fn seq_len_len<F>(len: usize) -> usize
+where
+    F: EndiannessImpl,
+{
+    match F::AS_ENUM {
+        Variant::BigEndian | Variant::LittleEndian => 4,
+        Variant::NetworkEndian => varint_len(zigzag(len as i32 as i64)),
+    }
+}
+
+/// Builder for [`Value::Compound`].
+///
+/// # Example
+///
+/// ```rust
+/// # use nbtx::{nbt, CompoundBuilder};
+/// let player = CompoundBuilder::new()
+///     .with("name", "Steve")
+///     .with("health", 20i8)
+///     .with("pos", nbt!([0.5f64, 64.0f64, 0.5f64]))
+///     .build();
+///
+/// assert_eq!(player, nbt!({
+///     "name": "Steve",
+///     "health": 20i8,
+///     "pos": [0.5f64, 64.0f64, 0.5f64]
+/// }));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CompoundBuilder {
+    map: Map,
+}
+
+impl CompoundBuilder {
+    /// Creates a builder for an empty compound.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts an entry into the compound, replacing any existing entry with the same key.
+    #[inline]
+    pub fn with(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.map.insert(key.into(), value.into());
+        self
+    }
+
+    /// Returns the built [`Value::Compound`].
+    #[inline]
+    pub fn build(self) -> Value {
+        Value::Compound(self.map)
+    }
+}
+
+macro_rules! impl_from {
+    ($($ty: ty => $tag: ident),+) => {
+        $(
+            impl From<$ty> for Value {
+                #[inline]
+                fn from(v: $ty) -> Self {
+                    Self::$tag(v.into())
+                }
+            }
+        )+
+    }
+}
+
+impl_from!(
+    i8 => Byte,
+    i16 => Short,
+    i32 => Int,
+    i64 => Long,
+    f32 => Float,
+    f64 => Double,
+    String => String,
+    &str => String,
+    Vec<u8> => ByteArray,
+    Vec<i32> => IntArray,
+    Vec<i64> => LongArray,
+    Vec<Value> => List,
+    Map => Compound
+);
+
+impl From<Vec<i8>> for Value {
+    /// NBT bytes are signed, so a `Vec<i8>` is stored as a [`ByteArray`](Value::ByteArray),
+    /// just like a `Vec<u8>`. Use [`Value::List`] directly to create a list of bytes instead.
     #[inline]
-    pub fn discriminant(&self) -> u8 {
-        match self {
-            Self::Byte(_) => 1,
-            Self::Short(_) => 2,
-            Self::Int(_) => 3,
-            Self::Long(_) => 4,
-            Self::Float(_) => 5,
-            Self::Double(_) => 6,
-            Self::ByteArray(_) => 7,
-            Self::String(_) => 8,
-            Self::List(_) => 9,
-            Self::Compound(_) => 10,
-            Self::IntArray(_) => 11,
-            Self::LongArray(_) => 12,
-        }
+    fn from(v: Vec<i8>) -> Self {
+        Self::ByteArray(v.into_iter().map(|b| b as u8).collect())
     }
 }
 
-macro_rules! impl_access_fns {
+#[cfg(feature = "preserve_order")]
+impl From<HashMap<String, Value>> for Value {
+    #[inline]
+    fn from(v: HashMap<String, Value>) -> Self {
+        Self::Compound(v.into_iter().collect())
+    }
+}
+
+impl FromIterator<Value> for Value {
+    /// Collects the values into a [`List`](Value::List).
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = Value>>(iter: I) -> Self {
+        Self::List(iter.into_iter().collect())
+    }
+}
+
+impl<K: Into<String>> FromIterator<(K, Value)> for Value {
+    /// Collects the key-value pairs into a [`Compound`](Value::Compound).
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = (K, Value)>>(iter: I) -> Self {
+        Self::Compound(iter.into_iter().map(|(k, v)| (k.into(), v)).collect())
+    }
+}
+
+impl Default for Value {
+    /// Returns an empty [`Compound`](Value::Compound).
+    #[inline]
+    fn default() -> Self {
+        Self::Compound(Map::default())
+    }
+}
+
+impl From<bool> for Value {
+    /// NBT does not have a boolean type, booleans are stored as a [`Byte`](Value::Byte) instead.
+    #[inline]
+    fn from(v: bool) -> Self {
+        Self::Byte(v as i8)
+    }
+}
+
+macro_rules! impl_try_from {
     ($($tag: ident = $ty: ty),+) => {
-        $(paste::paste! {
-            #[inline]
-            #[doc = concat!(
-                "Returns the inner value if the tag is of a, ", stringify!($tag), ", otherwise returns self.
-                This method is the same as [`as_", stringify!([<$tag:snake>]), "`](Self::as_", stringify!([<$tag:snake>]), ") but instead takes ownership of the value."
-            )]
-            pub fn [<into_ $tag:snake>](self) -> Result<$ty, Self> {
-                match self {
-                    Self::$tag(val) => Ok(val),
-                    _ => Err(self)
+        $(
+            impl TryFrom<Value> for $ty {
+                type Error = NbtError;
+
+                #[inline]
+                fn try_from(v: Value) -> Result<Self, NbtError> {
+                    match v {
+                        Value::$tag(v) => Ok(v),
+                        v => Err(NbtError::UnexpectedType {
+                            expected: FieldType::$tag,
+                            actual: v.field_type(),
+                        }),
+                    }
                 }
             }
 
-            #[inline]
-            #[doc = concat!(
-                "Returns a reference to the inner value of the tag is the requested type is present.
-                Use [`into_", stringify!([<$tag:snake>]), "`](Self::into_", stringify!([<$tag:snake>]), ")."
-            )]
-            pub fn [<as_ $tag:snake>](&self) -> Option<&$ty> {
-                match self {
-                    Self::$tag(val) => Some(val),
-                    _ => None
+            impl<'a> TryFrom<&'a Value> for &'a $ty {
+                type Error = NbtError;
+
+                #[inline]
+                fn try_from(v: &'a Value) -> Result<Self, NbtError> {
+                    match v {
+                        Value::$tag(v) => Ok(v),
+                        v => Err(NbtError::UnexpectedType {
+                            expected: FieldType::$tag,
+                            actual: v.field_type(),
+                        }),
+                    }
                 }
             }
 
-            #[inline]
-            #[doc = concat!(
-                "Returns whether the inner value is of type `", stringify!($tag), "`."
-            )]
-            pub fn [<is_ $tag:snake>](&self) -> bool {
-                matches!(self, Self::$tag(_))
+            impl<'a> TryFrom<&'a mut Value> for &'a mut $ty {
+                type Error = NbtError;
+
+                #[inline]
+                fn try_from(v: &'a mut Value) -> Result<Self, NbtError> {
+                    match v {
+                        Value::$tag(v) => Ok(v),
+                        v => Err(NbtError::UnexpectedType {
+                            expected: FieldType::$tag,
+                            actual: v.field_type(),
+                        }),
+                    }
+                }
             }
-        })+
+        )+
     }
 }
 
-impl Value {
-    impl_access_fns!(
-        Byte = i8,
-        Short = i16,
-        Int = i32,
-        Long = i64,
-        Float = f32,
-        Double = f64,
-        String = String,
-        List = Vec<Self>,
-        Compound = HashMap<String, Self>,
-        ByteArray = Vec<u8>,
-        IntArray = Vec<i32>,
-        LongArray = Vec<i64>
-    );
+impl_try_from!(
+    Byte = i8,
+    Short = i16,
+    Int = i32,
+    Long = i64,
+    Float = f32,
+    Double = f64,
+    String = String,
+    List = Vec<Value>,
+    Compound = Map,
+    ByteArray = Vec<u8>,
+    IntArray = Vec<i32>,
+    LongArray = Vec<i64>
+);
+
+impl TryFrom<Value> for bool {
+    type Error = NbtError;
+
+    /// Booleans are stored as a [`Byte`](Value::Byte), see [`Value::as_bool`].
+    #[inline]
+    fn try_from(v: Value) -> Result<Self, NbtError> {
+        v.as_bool().ok_or_else(|| NbtError::UnexpectedType {
+            expected: FieldType::Byte,
+            actual: v.field_type(),
+        })
+    }
 }
 
 impl PartialEq<Value> for Value {
@@ -125,8 +1396,10 @@ impl PartialEq<Value> for Value {
             Value::Short(lhs) => rhs.as_short() == Some(lhs),
             Value::Int(lhs) => rhs.as_int() == Some(lhs),
             Value::Long(lhs) => rhs.as_long() == Some(lhs),
-            Value::Float(lhs) => rhs.as_float() == Some(lhs),
-            Value::Double(lhs) => rhs.as_double() == Some(lhs),
+            Value::Float(lhs) => rhs.as_float().is_some_and(|rhs| lhs.total_cmp(rhs).is_eq()),
+            Value::Double(lhs) => rhs
+                .as_double()
+                .is_some_and(|rhs| lhs.total_cmp(rhs).is_eq()),
             Value::ByteArray(lhs) => rhs.as_byte_array().is_some_and(|rhs| lhs.as_slice() == rhs),
             Value::String(lhs) => rhs.as_string() == Some(lhs),
             Value::List(lhs) => rhs.as_list() == Some(lhs),
@@ -137,6 +1410,43 @@ impl PartialEq<Value> for Value {
     }
 }
 
+impl Eq for Value {}
+
+impl PartialOrd for Value {
+    #[inline]
+    fn partial_cmp(&self, rhs: &Value) -> Option<Ordering> {
+        Some(self.cmp(rhs))
+    }
+}
+
+impl Ord for Value {
+    fn cmp(&self, rhs: &Value) -> Ordering {
+        match (self, rhs) {
+            (Value::Byte(lhs), Value::Byte(rhs)) => lhs.cmp(rhs),
+            (Value::Short(lhs), Value::Short(rhs)) => lhs.cmp(rhs),
+            (Value::Int(lhs), Value::Int(rhs)) => lhs.cmp(rhs),
+            (Value::Long(lhs), Value::Long(rhs)) => lhs.cmp(rhs),
+            (Value::Float(lhs), Value::Float(rhs)) => lhs.total_cmp(rhs),
+            (Value::Double(lhs), Value::Double(rhs)) => lhs.total_cmp(rhs),
+            (Value::ByteArray(lhs), Value::ByteArray(rhs)) => lhs.cmp(rhs),
+            (Value::String(lhs), Value::String(rhs)) => lhs.cmp(rhs),
+            (Value::List(lhs), Value::List(rhs)) => lhs.cmp(rhs),
+            (Value::Compound(lhs), Value::Compound(rhs)) => {
+                // Maps are unordered, so compare their entries sorted by key.
+                let mut lhs: Vec<_> = lhs.iter().collect();
+                let mut rhs: Vec<_> = rhs.iter().collect();
+                lhs.sort_unstable_by_key(|(k, _)| *k);
+                rhs.sort_unstable_by_key(|(k, _)| *k);
+
+                lhs.cmp(&rhs)
+            }
+            (Value::IntArray(lhs), Value::IntArray(rhs)) => lhs.cmp(rhs),
+            (Value::LongArray(lhs), Value::LongArray(rhs)) => lhs.cmp(rhs),
+            (lhs, rhs) => lhs.discriminant().cmp(&rhs.discriminant()),
+        }
+    }
+}
+
 impl PartialEq<i8> for Value {
     #[inline]
     fn eq(&self, rhs: &i8) -> bool {
@@ -222,44 +1532,75 @@ impl PartialEq<i64> for &mut Value {
 }
 
 impl PartialEq<f32> for Value {
+    /// Floats are compared the same way as in `Value == Value`, so `NaN` is equal to itself.
     #[inline]
     fn eq(&self, rhs: &f32) -> bool {
-        self.as_float() == Some(rhs)
+        self.as_float()
+            .is_some_and(|lhs| lhs.total_cmp(rhs).is_eq())
     }
 }
 
 impl PartialEq<f32> for &Value {
     #[inline]
     fn eq(&self, rhs: &f32) -> bool {
-        self.as_float() == Some(rhs)
+        self.as_float()
+            .is_some_and(|lhs| lhs.total_cmp(rhs).is_eq())
     }
 }
 
 impl PartialEq<f32> for &mut Value {
     #[inline]
     fn eq(&self, rhs: &f32) -> bool {
-        self.as_float() == Some(rhs)
+        self.as_float()
+            .is_some_and(|lhs| lhs.total_cmp(rhs).is_eq())
     }
 }
 
 impl PartialEq<f64> for Value {
+    /// Doubles are compared the same way as in `Value == Value`, so `NaN` is equal to itself.
     #[inline]
     fn eq(&self, rhs: &f64) -> bool {
-        self.as_double() == Some(rhs)
+        self.as_double()
+            .is_some_and(|lhs| lhs.total_cmp(rhs).is_eq())
     }
 }
 
 impl PartialEq<f64> for &Value {
     #[inline]
     fn eq(&self, rhs: &f64) -> bool {
-        self.as_double() == Some(rhs)
+        self.as_double()
+            .is_some_and(|lhs| lhs.total_cmp(rhs).is_eq())
     }
 }
 
 impl PartialEq<f64> for &mut Value {
     #[inline]
     fn eq(&self, rhs: &f64) -> bool {
-        self.as_double() == Some(rhs)
+        self.as_double()
+            .is_some_and(|lhs| lhs.total_cmp(rhs).is_eq())
+    }
+}
+
+impl PartialEq<bool> for Value {
+    /// NBT does not have a boolean type, so this compares against a [`Byte`](Value::Byte),
+    /// of which any non-zero value is `true`. Other types are never equal to a boolean.
+    #[inline]
+    fn eq(&self, rhs: &bool) -> bool {
+        self.as_bool() == Some(*rhs)
+    }
+}
+
+impl PartialEq<bool> for &Value {
+    #[inline]
+    fn eq(&self, rhs: &bool) -> bool {
+        self.as_bool() == Some(*rhs)
+    }
+}
+
+impl PartialEq<bool> for &mut Value {
+    #[inline]
+    fn eq(&self, rhs: &bool) -> bool {
+        self.as_bool() == Some(*rhs)
     }
 }
 
@@ -326,23 +1667,23 @@ impl PartialEq<&[Value]> for &mut Value {
     }
 }
 
-impl PartialEq<HashMap<String, Value>> for Value {
+impl PartialEq<Map> for Value {
     #[inline]
-    fn eq(&self, rhs: &HashMap<String, Value>) -> bool {
+    fn eq(&self, rhs: &Map) -> bool {
         self.as_compound() == Some(rhs)
     }
 }
 
-impl PartialEq<HashMap<String, Value>> for &Value {
+impl PartialEq<Map> for &Value {
     #[inline]
-    fn eq(&self, rhs: &HashMap<String, Value>) -> bool {
+    fn eq(&self, rhs: &Map) -> bool {
         self.as_compound() == Some(rhs)
     }
 }
 
-impl PartialEq<HashMap<String, Value>> for &mut Value {
+impl PartialEq<Map> for &mut Value {
     #[inline]
-    fn eq(&self, rhs: &HashMap<String, Value>) -> bool {
+    fn eq(&self, rhs: &Map) -> bool {
         self.as_compound() == Some(rhs)
     }
 }
@@ -389,6 +1730,151 @@ impl PartialEq<&[i64]> for &mut Value {
     }
 }
 
+impl PartialEq<Vec<u8>> for Value {
+    #[inline]
+    fn eq(&self, rhs: &Vec<u8>) -> bool {
+        self.as_byte_array() == Some(rhs)
+    }
+}
+
+impl PartialEq<Vec<u8>> for &Value {
+    #[inline]
+    fn eq(&self, rhs: &Vec<u8>) -> bool {
+        self.as_byte_array() == Some(rhs)
+    }
+}
+
+impl PartialEq<Vec<u8>> for &mut Value {
+    #[inline]
+    fn eq(&self, rhs: &Vec<u8>) -> bool {
+        self.as_byte_array() == Some(rhs)
+    }
+}
+
+impl PartialEq<&Vec<u8>> for Value {
+    #[inline]
+    fn eq(&self, rhs: &&Vec<u8>) -> bool {
+        self.as_byte_array() == Some(*rhs)
+    }
+}
+
+impl PartialEq<Vec<i8>> for Value {
+    /// Compares against a [`ByteArray`](Value::ByteArray), as NBT bytes are signed.
+    #[inline]
+    fn eq(&self, rhs: &Vec<i8>) -> bool {
+        self.as_byte_array()
+            .is_some_and(|lhs| lhs.iter().map(|b| *b as i8).eq(rhs.iter().copied()))
+    }
+}
+
+impl PartialEq<Vec<i8>> for &Value {
+    #[inline]
+    fn eq(&self, rhs: &Vec<i8>) -> bool {
+        self.as_byte_array()
+            .is_some_and(|lhs| lhs.iter().map(|b| *b as i8).eq(rhs.iter().copied()))
+    }
+}
+
+impl PartialEq<Vec<i8>> for &mut Value {
+    #[inline]
+    fn eq(&self, rhs: &Vec<i8>) -> bool {
+        self.as_byte_array()
+            .is_some_and(|lhs| lhs.iter().map(|b| *b as i8).eq(rhs.iter().copied()))
+    }
+}
+
+impl PartialEq<&Vec<i8>> for Value {
+    #[inline]
+    fn eq(&self, rhs: &&Vec<i8>) -> bool {
+        self.as_byte_array()
+            .is_some_and(|lhs| lhs.iter().map(|b| *b as i8).eq(rhs.iter().copied()))
+    }
+}
+
+impl PartialEq<Vec<i32>> for Value {
+    #[inline]
+    fn eq(&self, rhs: &Vec<i32>) -> bool {
+        self.as_int_array() == Some(rhs)
+    }
+}
+
+impl PartialEq<Vec<i32>> for &Value {
+    #[inline]
+    fn eq(&self, rhs: &Vec<i32>) -> bool {
+        self.as_int_array() == Some(rhs)
+    }
+}
+
+impl PartialEq<Vec<i32>> for &mut Value {
+    #[inline]
+    fn eq(&self, rhs: &Vec<i32>) -> bool {
+        self.as_int_array() == Some(rhs)
+    }
+}
+
+impl PartialEq<&Vec<i32>> for Value {
+    #[inline]
+    fn eq(&self, rhs: &&Vec<i32>) -> bool {
+        self.as_int_array() == Some(*rhs)
+    }
+}
+
+impl PartialEq<Vec<i64>> for Value {
+    #[inline]
+    fn eq(&self, rhs: &Vec<i64>) -> bool {
+        self.as_long_array() == Some(rhs)
+    }
+}
+
+impl PartialEq<Vec<i64>> for &Value {
+    #[inline]
+    fn eq(&self, rhs: &Vec<i64>) -> bool {
+        self.as_long_array() == Some(rhs)
+    }
+}
+
+impl PartialEq<Vec<i64>> for &mut Value {
+    #[inline]
+    fn eq(&self, rhs: &Vec<i64>) -> bool {
+        self.as_long_array() == Some(rhs)
+    }
+}
+
+impl PartialEq<&Vec<i64>> for Value {
+    #[inline]
+    fn eq(&self, rhs: &&Vec<i64>) -> bool {
+        self.as_long_array() == Some(*rhs)
+    }
+}
+
+impl PartialEq<Vec<Value>> for Value {
+    #[inline]
+    fn eq(&self, rhs: &Vec<Value>) -> bool {
+        self.as_list() == Some(rhs)
+    }
+}
+
+impl PartialEq<Vec<Value>> for &Value {
+    #[inline]
+    fn eq(&self, rhs: &Vec<Value>) -> bool {
+        self.as_list() == Some(rhs)
+    }
+}
+
+impl PartialEq<Vec<Value>> for &mut Value {
+    #[inline]
+    fn eq(&self, rhs: &Vec<Value>) -> bool {
+        self.as_list() == Some(rhs)
+    }
+}
+
+impl PartialEq<&Vec<Value>> for Value {
+    #[inline]
+    fn eq(&self, rhs: &&Vec<Value>) -> bool {
+        self.as_list() == Some(*rhs)
+    }
+}
+
 impl Hash for Value {
     fn hash<H>(&self, state: &mut H)
     where
@@ -400,28 +1886,16 @@ impl Hash for Value {
             Value::Int(v) => state.write_i32(*v),
             Value::Long(v) => state.write_i64(*v),
             Value::String(v) => state.write(v.as_bytes()),
-            Value::Float(v) => {
-                // f32 does not implement Hash, so simply hash the byte representation
-                // IEEE floats have + 0 and - 0, which are the same value but have different byte representations
-                let bytes = if *v == (-0_f32) {
-                    0_f32.to_le_bytes()
-                } else {
-                    v.to_le_bytes()
-                };
-                state.write(&bytes);
-            }
-            Value::Double(v) => {
-                // f64 does not implement Hash, so simply hash the byte representation
-                // IEEE floats have + 0 and - 0, which are the same value but have different byte representations
-                let bytes = if *v == (-0_f64) {
-                    0_f64.to_le_bytes()
-                } else {
-                    v.to_le_bytes()
-                };
-                state.write(&bytes);
-            }
+            // Floats do not implement Hash, so hash the bit pattern instead.
+            // This is consistent with equality, which also compares floats bitwise.
+            Value::Float(v) => state.write_u32(v.to_bits()),
+            Value::Double(v) => state.write_u64(v.to_bits()),
             Value::Compound(map) => {
-                for (k, v) in map {
+                // Equal maps can iterate in a different order, so hash the entries sorted by key.
+                let mut entries: Vec<_> = map.iter().collect();
+                entries.sort_unstable_by_key(|(k, _)| *k);
+
+                for (k, v) in entries {
                     state.write(k.as_bytes());
                     v.hash(state);
                 }
@@ -440,7 +1914,9 @@ impl<'de> Deserialize<'de> for Value {
     where
         D: Deserializer<'de>,
     {
-        deserializer.deserialize_any(ValueVisitor)
+        // This lets the NBT deserializer know that int and long arrays can be distinguished from lists.
+        // Other deserializers simply forward to `deserialize_any`.
+        deserializer.deserialize_newtype_struct(VALUE_TOKEN, ValueVisitor)
     }
 }
 
@@ -479,12 +1955,20 @@ impl Serialize for Value {
                 }
                 map_ser.end()
             }
-            Value::IntArray(seq) => serialize_seq(ser, seq),
-            Value::LongArray(seq) => serialize_seq(ser, seq),
+            Value::IntArray(seq) => ser.serialize_newtype_struct(INT_ARRAY_TOKEN, seq),
+            Value::LongArray(seq) => ser.serialize_newtype_struct(LONG_ARRAY_TOKEN, seq),
         }
     }
 }
 
+/// Limits a size hint, so that a length read from untrusted data cannot allocate more than 1 MiB up front.
+///
+/// This is the same limit that serde uses for its own collections.
+#[inline]
+fn cautious_hint(hint: usize) -> usize {
+    hint.min(1024 * 1024 / core::mem::size_of::<Value>())
+}
+
 struct ValueVisitor;
 
 impl<'de> Visitor<'de> for ValueVisitor {
@@ -567,6 +2051,15 @@ impl<'de> Visitor<'de> for ValueVisitor {
         Ok(Value::String(v))
     }
 
+    /// Used when byte arrays are buffered by serde, such as for `#[serde(flatten)]`.
+    #[inline]
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Value::ByteArray(v.to_vec()))
+    }
+
     #[inline]
     fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
     where
@@ -575,6 +2068,30 @@ impl<'de> Visitor<'de> for ValueVisitor {
         Ok(Value::ByteArray(v))
     }
 
+    #[inline]
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+
+    /// Int and long arrays are passed as an [`ArrayAccess`], so they can be distinguished from lists.
+    fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+    where
+        A: EnumAccess<'de>,
+    {
+        let (name, variant) = data.variant::<String>()?;
+        match name.as_str() {
+            INT_ARRAY_TOKEN => Ok(Value::IntArray(variant.newtype_variant()?)),
+            LONG_ARRAY_TOKEN => Ok(Value::LongArray(variant.newtype_variant()?)),
+            _ => Err(de::Error::unknown_variant(
+                &name,
+                &[INT_ARRAY_TOKEN, LONG_ARRAY_TOKEN],
+            )),
+        }
+    }
+
     #[inline]
     fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
     where
@@ -582,7 +2099,7 @@ impl<'de> Visitor<'de> for ValueVisitor {
     {
         let mut out = Vec::new();
         if let Some(hint) = seq.size_hint() {
-            out.reserve(hint);
+            out.reserve(cautious_hint(hint));
         }
 
         while let Some(element) = seq.next_element()? {
@@ -597,9 +2114,10 @@ impl<'de> Visitor<'de> for ValueVisitor {
     where
         A: MapAccess<'de>,
     {
-        let mut out: HashMap<String, Value> = HashMap::new();
+        let mut out = Map::new();
+        #[cfg(feature = "std")]
         if let Some(hint) = map.size_hint() {
-            out.reserve(hint);
+            out.reserve(cautious_hint(hint));
         }
 
         while let Some((key, value)) = map.next_entry()? {
@@ -609,3 +2127,86 @@ impl<'de> Visitor<'de> for ValueVisitor {
         Ok(Value::Compound(out))
     }
 }
+
+/// Passes an [`IntArray`](Value::IntArray) or [`LongArray`](Value::LongArray) to a [`Value`] visitor.
+///
+/// Arrays are passed as an enum variant with a private name rather than as a map, so that they cannot be
+/// confused with compounds that contain a key with the same name.
+pub(crate) struct ArrayAccess<E> {
+    value: Value,
+    _marker: PhantomData<E>,
+}
+
+impl<E> ArrayAccess<E> {
+    /// Creates an access for the given value, which must be an int or long array.
+    #[inline]
+    pub(crate) fn new(value: Value) -> Self {
+        debug_assert!(matches!(value, Value::IntArray(_) | Value::LongArray(_)));
+        Self {
+            value,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, E> EnumAccess<'de> for ArrayAccess<E>
+where
+    E: de::Error,
+{
+    type Error = E;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self), E>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let name = match self.value {
+            Value::IntArray(_) => INT_ARRAY_TOKEN,
+            _ => LONG_ARRAY_TOKEN,
+        };
+        let variant = seed.deserialize(name.into_deserializer())?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de, E> VariantAccess<'de> for ArrayAccess<E>
+where
+    E: de::Error,
+{
+    type Error = E;
+
+    fn unit_variant(self) -> Result<(), E> {
+        Err(E::invalid_type(Unexpected::NewtypeVariant, &"unit variant"))
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, E>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.value {
+            Value::IntArray(v) => seed.deserialize(v.into_deserializer()),
+            Value::LongArray(v) => seed.deserialize(v.into_deserializer()),
+            _ => unreachable!("array accesses are only created for int and long arrays"),
+        }
+    }
+
+    fn tuple_variant<V>(self, _len: usize, _visitor: V) -> Result<V::Value, E>
+    where
+        V: Visitor<'de>,
+    {
+        Err(E::invalid_type(
+            Unexpected::NewtypeVariant,
+            &"tuple variant",
+        ))
+    }
+
+    fn struct_variant<V>(self, _fields: &'static [&'static str], _visitor: V) -> Result<V::Value, E>
+    where
+        V: Visitor<'de>,
+    {
+        Err(E::invalid_type(
+            Unexpected::NewtypeVariant,
+            &"struct variant",
+        ))
+    }
+}
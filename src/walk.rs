@@ -0,0 +1,104 @@
+use alloc::string::String;
+use core::fmt::Write;
+
+use crate::diff::push_segment;
+use crate::Value;
+
+impl Value {
+    /// Calls `f` on this value and every value contained in it, together with its path.
+    ///
+    /// Values are visited in pre-order, so a compound or list is visited before its contents.
+    /// Paths consist of `/`-delimited compound keys and `[i]` list indices, such as `/Level/Sections[0]/Y`.
+    /// Keys are escaped like in JSON pointers, `~` becomes `~0` and `/` becomes `~1`. Unlike the JSON pointers
+    /// used by [`diff_paths`](Self::diff_paths) and [`get_path_mut`](Self::get_path_mut), list indices are
+    /// written in brackets. The root value has the empty path.
+    /// Entries of compounds are visited in the iteration order of [`Map`](crate::Map).
+    ///
+    /// The elements of arrays are not visited separately, as they are not stored as values.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use nbtx::{nbt, Value};
+    /// let value = nbt!({ "Items": [{ "id": "minecraft:stone" }] });
+    ///
+    /// let mut paths = Vec::new();
+    /// value.walk(|path, _| paths.push(path.to_owned()));
+    ///
+    /// assert_eq!(paths, ["", "/Items", "/Items[0]", "/Items[0]/id"]);
+    /// ```
+    pub fn walk(&self, mut f: impl FnMut(&str, &Value)) {
+        walk(&mut String::new(), self, &mut f);
+    }
+
+    /// Calls `f` on this value and every value contained in it, together with its path, allowing them to be modified.
+    ///
+    /// This visits values in the same order as [`walk`](Self::walk). As values are visited before their contents,
+    /// the contents visited afterwards are those of the modified value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use nbtx::{nbt, Value};
+    /// let mut value = nbt!({ "name": "Steve", "tags": ["Builder", "Admin"] });
+    ///
+    /// value.walk_mut(|_, value| {
+    ///     if let Value::String(s) = value {
+    ///         *s = s.to_lowercase();
+    ///     }
+    /// });
+    ///
+    /// assert_eq!(value, nbt!({ "name": "steve", "tags": ["builder", "admin"] }));
+    /// ```
+    pub fn walk_mut(&mut self, mut f: impl FnMut(&str, &mut Value)) {
+        walk_mut(&mut String::new(), self, &mut f);
+    }
+}
+
+fn walk(path: &mut String, value: &Value, f: &mut impl FnMut(&str, &Value)) {
+    f(path, value);
+
+    match value {
+        Value::Compound(map) => {
+            for (key, value) in map {
+                let len = path.len();
+                push_segment(path, key);
+                walk(path, value, f);
+                path.truncate(len);
+            }
+        }
+        Value::List(list) => {
+            for (i, value) in list.iter().enumerate() {
+                let len = path.len();
+                let _ = write!(path, "[{i}]");
+                walk(path, value, f);
+                path.truncate(len);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn walk_mut(path: &mut String, value: &mut Value, f: &mut impl FnMut(&str, &mut Value)) {
+    f(path, value);
+
+    match value {
+        Value::Compound(map) => {
+            for (key, value) in map {
+                let len = path.len();
+                push_segment(path, key);
+                walk_mut(path, value, f);
+                path.truncate(len);
+            }
+        }
+        Value::List(list) => {
+            for (i, value) in list.iter_mut().enumerate() {
+                let len = path.len();
+                let _ = write!(path, "[{i}]");
+                walk_mut(path, value, f);
+                path.truncate(len);
+            }
+        }
+        _ => {}
+    }
+}